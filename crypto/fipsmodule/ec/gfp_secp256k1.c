@@ -0,0 +1,395 @@
+/* Arithmetic for secp256k1 (y**2 = x**3 + 7). This follows the structure of
+ * the generic P-384 code in gfp_p384.c and ecp_nistz384.inl, except that point
+ * doubling uses the formula for a = 0 instead of the one for a = -3. */
+
+#include "../../limbs/limbs.h"
+
+#include "ecp_nistz.h"
+#include "../bn/internal.h"
+#include "../../internal.h"
+
+#include "../../limbs/limbs.inl"
+
+#if defined(__GNUC__)
+#pragma GCC diagnostic push
+#pragma GCC diagnostic ignored "-Wsign-conversion"
+#endif
+
+#define SECP256K1_LIMBS (256u / LIMB_BITS)
+
+typedef Limb Elem[SECP256K1_LIMBS];
+typedef Limb ScalarMont[SECP256K1_LIMBS];
+
+typedef struct {
+  Limb X[SECP256K1_LIMBS];
+  Limb Y[SECP256K1_LIMBS];
+  Limb Z[SECP256K1_LIMBS];
+} SECP256K1_POINT;
+
+
+static const BN_ULONG Q[SECP256K1_LIMBS] = {
+  TOBN(0xfffffffe, 0xfffffc2f),
+  TOBN(0xffffffff, 0xffffffff),
+  TOBN(0xffffffff, 0xffffffff),
+  TOBN(0xffffffff, 0xffffffff),
+};
+
+static const BN_ULONG N[SECP256K1_LIMBS] = {
+  TOBN(0xbfd25e8c, 0xd0364141),
+  TOBN(0xbaaedce6, 0xaf48a03b),
+  TOBN(0xffffffff, 0xfffffffe),
+  TOBN(0xffffffff, 0xffffffff),
+};
+
+/* One converted into the Montgomery domain. */
+static const BN_ULONG ONE[SECP256K1_LIMBS] = {
+  TOBN(0x00000001, 0x000003d1), TOBN(0, 0), TOBN(0, 0), TOBN(0, 0),
+};
+
+
+static inline Limb is_equal(const Elem a, const Elem b) {
+  return LIMBS_equal(a, b, SECP256K1_LIMBS);
+}
+
+static inline Limb is_zero(const BN_ULONG a[SECP256K1_LIMBS]) {
+  return LIMBS_are_zero(a, SECP256K1_LIMBS);
+}
+
+static inline void copy_conditional(Elem r, const Elem a,
+                                    const Limb condition) {
+  for (size_t i = 0; i < SECP256K1_LIMBS; ++i) {
+    r[i] = constant_time_select_w(condition, a[i], r[i]);
+  }
+}
+
+static inline void elem_add(Elem r, const Elem a, const Elem b) {
+  LIMBS_add_mod(r, a, b, Q, SECP256K1_LIMBS);
+}
+
+static inline void elem_sub(Elem r, const Elem a, const Elem b) {
+  LIMBS_sub_mod(r, a, b, Q, SECP256K1_LIMBS);
+}
+
+/* r = a * b * 2**-256 (mod m), where |n0| = -m**-1 (mod 2**LIMB_BITS). This
+ * is word-by-word Montgomery multiplication (Algorithm 14.36 of the Handbook
+ * of Applied Cryptography) with a branch-free final subtraction, so the
+ * sequence of operations and memory accesses doesn't depend on the values of
+ * |a| and |b|. |a| and |b| must be less than |m|. |r| may alias |a| or |b|. */
+static void mul_mont(Limb r[SECP256K1_LIMBS], const Limb a[SECP256K1_LIMBS],
+                     const Limb b[SECP256K1_LIMBS],
+                     const Limb m[SECP256K1_LIMBS], Limb n0) {
+  /* |t| is always less than 2*m after each iteration, so two extra limbs are
+   * enough to hold the carries before the division by 2**LIMB_BITS. */
+  Limb t[SECP256K1_LIMBS + 2] = { 0 };
+  for (size_t i = 0; i < SECP256K1_LIMBS; ++i) {
+    /* t += a * b[i] */
+    Limb hi = GFp_limbs_mul_add_limb(t, a, b[i], SECP256K1_LIMBS);
+    Carry c = limb_add(&t[SECP256K1_LIMBS], t[SECP256K1_LIMBS], hi);
+    (void)limb_add(&t[SECP256K1_LIMBS + 1], t[SECP256K1_LIMBS + 1], c);
+
+    /* t += m * (t[0] * n0), which makes the low limb of |t| zero. */
+    hi = GFp_limbs_mul_add_limb(t, m, t[0] * n0, SECP256K1_LIMBS);
+    c = limb_add(&t[SECP256K1_LIMBS], t[SECP256K1_LIMBS], hi);
+    (void)limb_add(&t[SECP256K1_LIMBS + 1], t[SECP256K1_LIMBS + 1], c);
+    dev_assert_secret(t[0] == 0);
+
+    /* t /= 2**LIMB_BITS */
+    for (size_t j = 0; j < SECP256K1_LIMBS + 1; ++j) {
+      t[j] = t[j + 1];
+    }
+    t[SECP256K1_LIMBS + 1] = 0;
+  }
+
+  /* t < 2*m, so it is reduced by subtracting |m| at most once. t >= m unless
+   * the subtraction borrows and t doesn't have a high limb to borrow from. */
+  Limb reduced[SECP256K1_LIMBS];
+  Carry borrow = limbs_sub(reduced, t, m, SECP256K1_LIMBS);
+  Limb t_is_reduced = constant_time_is_zero_w(t[SECP256K1_LIMBS]) &
+                      constant_time_is_nonzero_w(borrow);
+  for (size_t i = 0; i < SECP256K1_LIMBS; ++i) {
+    r[i] = constant_time_select_w(t_is_reduced, t[i], reduced[i]);
+  }
+}
+
+static inline void elem_mul_mont(Elem r, const Elem a, const Elem b) {
+  static const BN_ULONG Q_N0[] = {
+    BN_MONT_CTX_N0(0xd838091d, 0xd2253531)
+  };
+  /* On 32-bit targets |Q_N0| is two limbs; the low one is -Q**-1 mod 2**32. */
+  mul_mont(r, a, b, Q, Q_N0[0]);
+}
+
+static inline void elem_sqr_mont(Elem r, const Elem a) {
+  /* XXX: Inefficient. TODO: Add a dedicated squaring routine. */
+  elem_mul_mont(r, a, a);
+}
+
+static inline void elem_mul_by_2(Elem r, const Elem a) {
+  LIMBS_shl_mod(r, a, Q, SECP256K1_LIMBS);
+}
+
+static void elem_neg(Elem r, const Elem a) {
+  Limb is_zero = LIMBS_are_zero(a, SECP256K1_LIMBS);
+  Carry borrow = limbs_sub(r, Q, a, SECP256K1_LIMBS);
+  dev_assert_secret(borrow == 0);
+  (void)borrow;
+  for (size_t i = 0; i < SECP256K1_LIMBS; ++i) {
+    r[i] = constant_time_select_w(is_zero, 0, r[i]);
+  }
+}
+
+void GFp_secp256k1_elem_add(Elem r, const Elem a, const Elem b) {
+  elem_add(r, a, b);
+}
+
+void GFp_secp256k1_elem_mul_mont(Elem r, const Elem a, const Elem b) {
+  elem_mul_mont(r, a, b);
+}
+
+void GFp_secp256k1_scalar_mul_mont(ScalarMont r, const ScalarMont a,
+                                   const ScalarMont b) {
+  static const BN_ULONG N_N0[] = {
+    BN_MONT_CTX_N0(0x4b0dff66, 0x5588b13f)
+  };
+  mul_mont(r, a, b, N, N_N0[0]);
+}
+
+
+/* Point double: r = 2*a, using the "dbl-2009-l" formula for a = 0 from the
+ * Explicit-Formulas Database. `r` may alias `a`. */
+static void point_double(SECP256K1_POINT *r, const SECP256K1_POINT *a) {
+  Elem A, B, C, D, E, F, tmp;
+  Elem res_x, res_y, res_z;
+
+  elem_sqr_mont(A, a->X);          /* A = X1^2 */
+  elem_sqr_mont(B, a->Y);          /* B = Y1^2 */
+  elem_sqr_mont(C, B);             /* C = B^2 */
+
+  elem_add(D, a->X, B);            /* D = 2*((X1+B)^2-A-C) */
+  elem_sqr_mont(D, D);
+  elem_sub(D, D, A);
+  elem_sub(D, D, C);
+  elem_mul_by_2(D, D);
+
+  elem_mul_by_2(E, A);             /* E = 3*A */
+  elem_add(E, E, A);
+  elem_sqr_mont(F, E);             /* F = E^2 */
+
+  elem_mul_by_2(tmp, D);           /* X3 = F-2*D */
+  elem_sub(res_x, F, tmp);
+
+  elem_sub(res_y, D, res_x);       /* Y3 = E*(D-X3)-8*C */
+  elem_mul_mont(res_y, E, res_y);
+  elem_mul_by_2(tmp, C);
+  elem_mul_by_2(tmp, tmp);
+  elem_mul_by_2(tmp, tmp);
+  elem_sub(res_y, res_y, tmp);
+
+  elem_mul_mont(res_z, a->Y, a->Z); /* Z3 = 2*Y1*Z1 */
+  elem_mul_by_2(res_z, res_z);
+
+  limbs_copy(r->X, res_x, SECP256K1_LIMBS);
+  limbs_copy(r->Y, res_y, SECP256K1_LIMBS);
+  limbs_copy(r->Z, res_z, SECP256K1_LIMBS);
+}
+
+/* Point addition: r = a+b. `r` may alias `a` or `b`. */
+void GFp_secp256k1_point_add(SECP256K1_POINT *r, const SECP256K1_POINT *a,
+                             const SECP256K1_POINT *b) {
+  Elem U2, S2;
+  Elem U1, S1;
+  Elem Z1sqr;
+  Elem Z2sqr;
+  Elem H, R;
+  Elem Hsqr;
+  Elem Rsqr;
+  Elem Hcub;
+
+  Elem res_x;
+  Elem res_y;
+  Elem res_z;
+
+  const BN_ULONG *in1_x = a->X;
+  const BN_ULONG *in1_y = a->Y;
+  const BN_ULONG *in1_z = a->Z;
+
+  const BN_ULONG *in2_x = b->X;
+  const BN_ULONG *in2_y = b->Y;
+  const BN_ULONG *in2_z = b->Z;
+
+  BN_ULONG in1infty = is_zero(a->Z);
+  BN_ULONG in2infty = is_zero(b->Z);
+
+  elem_sqr_mont(Z2sqr, in2_z); /* Z2^2 */
+  elem_sqr_mont(Z1sqr, in1_z); /* Z1^2 */
+
+  elem_mul_mont(S1, Z2sqr, in2_z); /* S1 = Z2^3 */
+  elem_mul_mont(S2, Z1sqr, in1_z); /* S2 = Z1^3 */
+
+  elem_mul_mont(S1, S1, in1_y); /* S1 = Y1*Z2^3 */
+  elem_mul_mont(S2, S2, in2_y); /* S2 = Y2*Z1^3 */
+  elem_sub(R, S2, S1);          /* R = S2 - S1 */
+
+  elem_mul_mont(U1, in1_x, Z2sqr); /* U1 = X1*Z2^2 */
+  elem_mul_mont(U2, in2_x, Z1sqr); /* U2 = X2*Z1^2 */
+  elem_sub(H, U2, U1);             /* H = U2 - U1 */
+
+  BN_ULONG is_exceptional = is_equal(U1, U2) & ~in1infty & ~in2infty;
+  if (is_exceptional) {
+    if (is_equal(S1, S2)) {
+      point_double(r, a);
+    } else {
+      limbs_zero(r->X, SECP256K1_LIMBS);
+      limbs_zero(r->Y, SECP256K1_LIMBS);
+      limbs_zero(r->Z, SECP256K1_LIMBS);
+    }
+    return;
+  }
+
+  elem_sqr_mont(Rsqr, R);             /* R^2 */
+  elem_mul_mont(res_z, H, in1_z);     /* Z3 = H*Z1*Z2 */
+  elem_sqr_mont(Hsqr, H);             /* H^2 */
+  elem_mul_mont(res_z, res_z, in2_z); /* Z3 = H*Z1*Z2 */
+  elem_mul_mont(Hcub, Hsqr, H);       /* H^3 */
+
+  elem_mul_mont(U2, U1, Hsqr); /* U1*H^2 */
+  elem_mul_by_2(Hsqr, U2);     /* 2*U1*H^2 */
+
+  elem_sub(res_x, Rsqr, Hsqr);
+  elem_sub(res_x, res_x, Hcub);
+
+  elem_sub(res_y, U2, res_x);
+
+  elem_mul_mont(S2, S1, Hcub);
+  elem_mul_mont(res_y, R, res_y);
+  elem_sub(res_y, res_y, S2);
+
+  copy_conditional(res_x, in2_x, in1infty);
+  copy_conditional(res_y, in2_y, in1infty);
+  copy_conditional(res_z, in2_z, in1infty);
+
+  copy_conditional(res_x, in1_x, in2infty);
+  copy_conditional(res_y, in1_y, in2infty);
+  copy_conditional(res_z, in1_z, in2infty);
+
+  limbs_copy(r->X, res_x, SECP256K1_LIMBS);
+  limbs_copy(r->Y, res_y, SECP256K1_LIMBS);
+  limbs_copy(r->Z, res_z, SECP256K1_LIMBS);
+}
+
+/* TODO(perf): Optimize this. */
+static void point_select_w5(SECP256K1_POINT *out,
+                            const SECP256K1_POINT table[16], size_t index) {
+  Elem x; limbs_zero(x, SECP256K1_LIMBS);
+  Elem y; limbs_zero(y, SECP256K1_LIMBS);
+  Elem z; limbs_zero(z, SECP256K1_LIMBS);
+
+  for (size_t i = 0; i < 16; ++i) {
+    crypto_word equal = constant_time_eq_w(index, (crypto_word)i + 1);
+    for (size_t j = 0; j < SECP256K1_LIMBS; ++j) {
+      x[j] = constant_time_select_w(equal, table[i].X[j], x[j]);
+      y[j] = constant_time_select_w(equal, table[i].Y[j], y[j]);
+      z[j] = constant_time_select_w(equal, table[i].Z[j], z[j]);
+    }
+  }
+
+  limbs_copy(out->X, x, SECP256K1_LIMBS);
+  limbs_copy(out->Y, y, SECP256K1_LIMBS);
+  limbs_copy(out->Z, z, SECP256K1_LIMBS);
+}
+
+static void add_precomputed_w5(SECP256K1_POINT *r, crypto_word wvalue,
+                               const SECP256K1_POINT table[16]) {
+  crypto_word recoded_is_negative;
+  crypto_word recoded;
+  booth_recode(&recoded_is_negative, &recoded, wvalue, 5);
+
+  alignas(64) SECP256K1_POINT h;
+  point_select_w5(&h, table, recoded);
+
+  alignas(64) BN_ULONG tmp[SECP256K1_LIMBS];
+  elem_neg(tmp, h.Y);
+  copy_conditional(h.Y, tmp, recoded_is_negative);
+
+  GFp_secp256k1_point_add(r, r, &h);
+}
+
+/* r = p * p_scalar */
+void GFp_secp256k1_point_mul(SECP256K1_POINT *r,
+                             const BN_ULONG p_scalar[SECP256K1_LIMBS],
+                             const BN_ULONG p_x[SECP256K1_LIMBS],
+                             const BN_ULONG p_y[SECP256K1_LIMBS]) {
+  static const size_t kWindowSize = 5;
+  static const crypto_word kMask = (1 << (5 /* kWindowSize */ + 1)) - 1;
+
+  uint8_t p_str[(SECP256K1_LIMBS * sizeof(Limb)) + 1];
+  gfp_little_endian_bytes_from_scalar(p_str, sizeof(p_str) / sizeof(p_str[0]),
+                                      p_scalar, SECP256K1_LIMBS);
+
+  /* table[0] is implicitly (0,0,0) (the point at infinity), therefore it is
+   * not stored. All other values are actually stored with an offset of -1 in
+   * table. */
+  alignas(64) SECP256K1_POINT table[16];
+  SECP256K1_POINT *row = table;
+
+  limbs_copy(row[1 - 1].X, p_x, SECP256K1_LIMBS);
+  limbs_copy(row[1 - 1].Y, p_y, SECP256K1_LIMBS);
+  limbs_copy(row[1 - 1].Z, ONE, SECP256K1_LIMBS);
+
+  point_double(&row[2 - 1], &row[1 - 1]);
+  GFp_secp256k1_point_add(&row[3 - 1], &row[2 - 1], &row[1 - 1]);
+  point_double(&row[4 - 1], &row[2 - 1]);
+  point_double(&row[6 - 1], &row[3 - 1]);
+  point_double(&row[8 - 1], &row[4 - 1]);
+  point_double(&row[12 - 1], &row[6 - 1]);
+  GFp_secp256k1_point_add(&row[5 - 1], &row[4 - 1], &row[1 - 1]);
+  GFp_secp256k1_point_add(&row[7 - 1], &row[6 - 1], &row[1 - 1]);
+  GFp_secp256k1_point_add(&row[9 - 1], &row[8 - 1], &row[1 - 1]);
+  GFp_secp256k1_point_add(&row[13 - 1], &row[12 - 1], &row[1 - 1]);
+  point_double(&row[14 - 1], &row[7 - 1]);
+  point_double(&row[10 - 1], &row[5 - 1]);
+  GFp_secp256k1_point_add(&row[15 - 1], &row[14 - 1], &row[1 - 1]);
+  GFp_secp256k1_point_add(&row[11 - 1], &row[10 - 1], &row[1 - 1]);
+  point_double(&row[16 - 1], &row[8 - 1]);
+
+  static const size_t START_INDEX = 256 - 1;
+  size_t index = START_INDEX;
+
+  crypto_word recoded_is_negative;
+  crypto_word recoded;
+
+  crypto_word wvalue = p_str[(index - 1) / 8];
+  wvalue = (wvalue >> ((index - 1) % 8)) & kMask;
+
+  booth_recode(&recoded_is_negative, &recoded, wvalue, 5);
+  dev_assert_secret(!recoded_is_negative);
+
+  point_select_w5(r, table, recoded);
+
+  while (index >= kWindowSize) {
+    if (index != START_INDEX) {
+      size_t off = (index - 1) / 8;
+
+      wvalue = p_str[off] | p_str[off + 1] << 8;
+      wvalue = (wvalue >> ((index - 1) % 8)) & kMask;
+      add_precomputed_w5(r, wvalue, table);
+    }
+
+    index -= kWindowSize;
+
+    point_double(r, r);
+    point_double(r, r);
+    point_double(r, r);
+    point_double(r, r);
+    point_double(r, r);
+  }
+
+  /* Final window */
+  wvalue = p_str[0];
+  wvalue = (wvalue << 1) & kMask;
+  add_precomputed_w5(r, wvalue, table);
+}
+
+#if defined(__GNUC__)
+#pragma GCC diagnostic pop
+#endif
@@ -22,8 +22,13 @@
 //! [`crypto.cipher.AEAD`]: https://golang.org/pkg/crypto/cipher/#AEAD
 
 use self::block::{Block, BLOCK_LEN};
-use crate::{constant_time, cpu, error, hkdf, polyfill};
-use core::ops::RangeFrom;
+#[cfg(feature = "alloc")]
+use crate::rand;
+use crate::{constant_time, cpu, error, hkdf, hmac, polyfill};
+use core::{convert::TryInto, ops::RangeFrom};
+
+#[cfg(feature = "nonce_reuse_check")]
+extern crate std;
 
 pub use self::{
     aes_gcm::{AES_128_GCM, AES_256_GCM},
@@ -202,7 +207,7 @@ fn open_within_<'in_out, A: AsRef<[u8]>>(
         let ciphertext_len = ciphertext_and_tag_len
             .checked_sub(TAG_LEN)
             .ok_or(error::Unspecified)?;
-        check_per_nonce_max_bytes(key.algorithm, ciphertext_len)?;
+        check_per_nonce_max_bytes(key.algorithm, aad.as_ref().len(), ciphertext_len)?;
         let (in_out, received_tag) = in_out.split_at_mut(in_prefix_len + ciphertext_len);
         let Tag(calculated_tag) = (key.algorithm.open)(
             &key.inner,
@@ -212,16 +217,11 @@ fn open_within_<'in_out, A: AsRef<[u8]>>(
             in_out,
             key.cpu_features,
         );
-        if constant_time::verify_slices_are_equal(calculated_tag.as_ref(), received_tag).is_err() {
-            // Zero out the plaintext so that it isn't accidentally leaked or used
-            // after verification fails. It would be safest if we could check the
-            // tag before decrypting, but some `open` implementations interleave
-            // authentication with decryption for performance.
-            for b in &mut in_out[..ciphertext_len] {
-                *b = 0;
-            }
-            return Err(error::Unspecified);
-        }
+        verify_tag_and_zero_on_failure(
+            &calculated_tag[..],
+            received_tag,
+            &mut in_out[..ciphertext_len],
+        )?;
         // `ciphertext_len` is also the plaintext length.
         Ok(&mut in_out[..ciphertext_len])
     }
@@ -235,14 +235,251 @@ fn open_within_<'in_out, A: AsRef<[u8]>>(
     )
 }
 
+/// Authenticates and decrypts (“opens”) `ciphertext_and_tag` into `out`,
+/// without modifying `ciphertext_and_tag`.
+///
+/// `aad` is the additional authenticated data (AAD), if any.
+/// `ciphertext_and_tag` must be the ciphertext followed by the tag, and `out`
+/// must be at least as long as the ciphertext, i.e.
+/// `ciphertext_and_tag.len() - key.algorithm().tag_len()` bytes. This is
+/// useful when the ciphertext is in read-only memory, e.g. a memory-mapped
+/// file.
+///
+/// When `open()` returns `Ok(plaintext_len)`, the plaintext is at
+/// `out[..plaintext_len]` and the rest of `out` is untouched. When `open()`
+/// returns `Err(..)`, `out` may have been overwritten in an unspecified way.
+///
+/// `nonce` must be unique for every use of the key to open data.
+pub fn open<A: AsRef<[u8]>>(
+    key: &LessSafeKey,
+    nonce: Nonce,
+    Aad(aad): Aad<A>,
+    ciphertext_and_tag: &[u8],
+    out: &mut [u8],
+) -> Result<usize, error::Unspecified> {
+    let key = &key.key;
+    let ciphertext_len = ciphertext_and_tag
+        .len()
+        .checked_sub(TAG_LEN)
+        .ok_or(error::Unspecified)?;
+    let aad = Aad::from(aad.as_ref());
+    check_per_nonce_max_bytes(key.algorithm, aad.as_ref().len(), ciphertext_len)?;
+    let (ciphertext, received_tag) = ciphertext_and_tag.split_at(ciphertext_len);
+    let out = out.get_mut(..ciphertext_len).ok_or(error::Unspecified)?;
+    out.copy_from_slice(ciphertext);
+    let Tag(calculated_tag) =
+        (key.algorithm.open)(&key.inner, nonce, aad, 0, out, key.cpu_features);
+    verify_tag_and_zero_on_failure(&calculated_tag[..], received_tag, out)?;
+    // `ciphertext_len` is also the plaintext length.
+    Ok(ciphertext_len)
+}
+
+/// Like [`LessSafeKey::seal_in_place_append_tag()`], except the nonce is
+/// computed by calling `make_nonce`.
+///
+/// This is useful when the nonce is derived from other state, e.g. from a
+/// sequence number using a KDF, and avoids the need for an intermediate
+/// variable at the call site. `make_nonce` is called exactly once, before
+/// anything is encrypted. See also [`NonceSequence`].
+///
+/// The nonce returned by `make_nonce` must be unique for every use of the key
+/// to seal data.
+#[inline]
+pub fn seal_in_place_with<A, InOut, F>(
+    key: &LessSafeKey,
+    aad: Aad<A>,
+    in_out: &mut InOut,
+    make_nonce: F,
+) -> Result<(), error::Unspecified>
+where
+    A: AsRef<[u8]>,
+    InOut: AsMut<[u8]> + for<'in_out> Extend<&'in_out u8>,
+    F: FnOnce() -> Nonce,
+{
+    key.seal_in_place_append_tag(make_nonce(), aad, in_out)
+}
+
+/// Like [`LessSafeKey::open_in_place()`], except the nonce is computed by
+/// calling `make_nonce`.
+///
+/// This is the counterpart of [`seal_in_place_with()`]. `make_nonce` is
+/// called exactly once.
+#[inline]
+pub fn open_in_place_with<'in_out, A, F>(
+    key: &LessSafeKey,
+    aad: Aad<A>,
+    in_out: &'in_out mut [u8],
+    make_nonce: F,
+) -> Result<&'in_out mut [u8], error::Unspecified>
+where
+    A: AsRef<[u8]>,
+    F: FnOnce() -> Nonce,
+{
+    key.open_in_place(make_nonce(), aad, in_out)
+}
+
+/// Encrypts and signs (“seals”) `plaintext`, returning a newly-allocated
+/// vector containing the ciphertext followed by the tag.
+///
+/// This is a simpler, but less efficient, alternative to
+/// [`LessSafeKey::seal_in_place_append_tag()`] that doesn't require the
+/// caller to manage the layout of the buffer.
+///
+/// `nonce` must be unique for every use of the key to seal data.
+///
+/// Requires the `alloc` feature.
+#[cfg(feature = "alloc")]
+pub fn seal_to_vec<A: AsRef<[u8]>>(
+    key: &LessSafeKey,
+    nonce: Nonce,
+    aad: Aad<A>,
+    plaintext: &[u8],
+) -> Result<alloc::vec::Vec<u8>, error::Unspecified> {
+    let mut in_out = alloc::vec::Vec::with_capacity(plaintext.len() + key.algorithm().tag_len());
+    in_out.extend_from_slice(plaintext);
+    key.seal_in_place_append_tag(nonce, aad, &mut in_out)?;
+    Ok(in_out)
+}
+
+/// Authenticates and decrypts (“opens”) `ciphertext_and_tag`, returning a
+/// newly-allocated vector containing the plaintext.
+///
+/// This is the counterpart of [`seal_to_vec()`]; see [`open()`] for the
+/// details. Nothing is returned if authentication fails.
+///
+/// `nonce` must be unique for every use of the key to open data.
+///
+/// Requires the `alloc` feature.
+#[cfg(feature = "alloc")]
+pub fn open_to_vec<A: AsRef<[u8]>>(
+    key: &LessSafeKey,
+    nonce: Nonce,
+    aad: Aad<A>,
+    ciphertext_and_tag: &[u8],
+) -> Result<alloc::vec::Vec<u8>, error::Unspecified> {
+    let ciphertext_len = ciphertext_and_tag
+        .len()
+        .checked_sub(key.algorithm().tag_len())
+        .ok_or(error::Unspecified)?;
+    let mut out = alloc::vec![0; ciphertext_len];
+    let plaintext_len = open(key, nonce, aad, ciphertext_and_tag, &mut out)?;
+    debug_assert_eq!(plaintext_len, ciphertext_len);
+    Ok(out)
+}
+
+/// Encrypts and signs (“seals”) `plaintext` using a random nonce generated
+/// from `rng`, returning a newly-allocated vector containing the nonce, the
+/// ciphertext, and the tag, in that order.
+///
+/// The result can be opened with [`open_self_contained()`], which doesn't
+/// need to be told the nonce. This is convenient for encrypting data at rest,
+/// where everything needed to decrypt the data, other than the key and the
+/// AAD, is stored together in one blob.
+///
+/// Random nonces are only safe when the number of messages sealed with the
+/// same key is limited, because the probability of a nonce collision becomes
+/// significant after about 2**32 messages with the 96-bit nonces used here. A
+/// nonce collision is catastrophic for all of the AEAD algorithms in this
+/// module. Use a `NonceSequence` when a key is used for many messages.
+///
+/// Requires the `alloc` feature.
+#[cfg(feature = "alloc")]
+pub fn seal_self_contained<A: AsRef<[u8]>>(
+    key: &LessSafeKey,
+    aad: Aad<A>,
+    plaintext: &[u8],
+    rng: &dyn rand::SecureRandom,
+) -> Result<alloc::vec::Vec<u8>, error::Unspecified> {
+    let mut nonce_bytes = [0u8; NONCE_LEN];
+    rng.fill(&mut nonce_bytes)?;
+    let nonce = Nonce::assume_unique_for_key(nonce_bytes);
+
+    let mut blob =
+        alloc::vec::Vec::with_capacity(NONCE_LEN + plaintext.len() + key.algorithm().tag_len());
+    blob.extend_from_slice(&nonce_bytes);
+    blob.extend_from_slice(plaintext);
+    let tag = key.seal_in_place_separate_tag(nonce, aad, &mut blob[NONCE_LEN..])?;
+    blob.extend_from_slice(tag.as_ref());
+    Ok(blob)
+}
+
+/// Authenticates and decrypts (“opens”) `blob`, which must have been produced
+/// by [`seal_self_contained()`], returning a newly-allocated vector containing
+/// the plaintext.
+///
+/// `blob` must be the nonce followed by the ciphertext and the tag. `aad`
+/// must be the same AAD that was used to seal it.
+///
+/// Requires the `alloc` feature.
+#[cfg(feature = "alloc")]
+pub fn open_self_contained<A: AsRef<[u8]>>(
+    key: &LessSafeKey,
+    aad: Aad<A>,
+    blob: &[u8],
+) -> Result<alloc::vec::Vec<u8>, error::Unspecified> {
+    if blob.len() < NONCE_LEN {
+        return Err(error::Unspecified);
+    }
+    let (nonce, ciphertext_and_tag) = blob.split_at(NONCE_LEN);
+    let nonce = Nonce::try_assume_unique_for_key(nonce)?;
+    open_to_vec(key, nonce, aad, ciphertext_and_tag)
+}
+
+/// Computes GMAC, the authentication tag that AES-GCM produces for `aad` when
+/// the plaintext is empty.
+///
+/// This uses AES-GCM as a MAC: `aad` is authenticated but nothing is
+/// encrypted. The result is the same as the tag returned by
+/// [`LessSafeKey::seal_in_place_separate_tag()`] for an empty `in_out`, and it
+/// can be checked with [`verify_tag()`].
+///
+/// Fails unless `key` is an `AES_128_GCM` or `AES_256_GCM` key, or if `aad`
+/// is too long.
+///
+/// `nonce` must be unique for every use of the key, whether to compute a GMAC
+/// or to seal data.
+pub fn gmac(key: &LessSafeKey, nonce: Nonce, aad: &[u8]) -> Result<Tag, error::Unspecified> {
+    match key.key.algorithm.id {
+        AlgorithmID::AES_128_GCM | AlgorithmID::AES_256_GCM => {}
+        AlgorithmID::CHACHA20_POLY1305 => return Err(error::Unspecified),
+    }
+    key.seal_in_place_separate_tag(nonce, Aad::from(aad), &mut [])
+}
+
+fn verify_tag_and_zero_on_failure(
+    calculated_tag: &[u8],
+    received_tag: &[u8],
+    plaintext: &mut [u8],
+) -> Result<(), error::Unspecified> {
+    constant_time::verify_slices_are_equal(calculated_tag, received_tag).map_err(|e| {
+        // Zero out the plaintext so that it isn't accidentally leaked or used
+        // after verification fails. It would be safest if we could check the
+        // tag before decrypting, but some `open` implementations interleave
+        // authentication with decryption for performance.
+        for b in plaintext {
+            *b = 0;
+        }
+        e
+    })
+}
+
 /// An AEAD key for encrypting and signing ("sealing"), bound to a nonce
 /// sequence.
 ///
 /// Intentionally not `Clone` or `Copy` since cloning would allow duplication
 /// of the nonce sequence.
+///
+/// With the `nonce_reuse_check` feature, a `SealingKey` remembers every nonce
+/// it has used, and sealing fails without encrypting anything if its
+/// `NonceSequence` ever produces the same nonce twice. This is a development
+/// aid for catching broken `NonceSequence` implementations; it uses memory
+/// proportional to the number of messages sealed, so it must not be enabled
+/// in production. Without the feature there is no overhead.
 pub struct SealingKey<N: NonceSequence> {
     key: UnboundKey,
     nonce_sequence: N,
+    #[cfg(feature = "nonce_reuse_check")]
+    used_nonces: std::collections::HashSet<[u8; NONCE_LEN]>,
 }
 
 impl<N: NonceSequence> BoundKey<N> for SealingKey<N> {
@@ -250,6 +487,8 @@ impl<N: NonceSequence> BoundKey<N> for SealingKey<N> {
         Self {
             key,
             nonce_sequence,
+            #[cfg(feature = "nonce_reuse_check")]
+            used_nonces: std::collections::HashSet::new(),
         }
     }
 
@@ -326,12 +565,16 @@ impl<N: NonceSequence> SealingKey<N> {
     where
         A: AsRef<[u8]>,
     {
-        seal_in_place_separate_tag_(
-            &self.key,
-            self.nonce_sequence.advance()?,
-            Aad::from(aad.as_ref()),
-            in_out,
-        )
+        let nonce = self.nonce_sequence.advance()?;
+
+        #[cfg(feature = "nonce_reuse_check")]
+        {
+            if !self.used_nonces.insert(*nonce.as_ref()) {
+                return Err(error::Unspecified);
+            }
+        }
+
+        seal_in_place_separate_tag_(&self.key, nonce, Aad::from(aad.as_ref()), in_out)
     }
 }
 
@@ -342,7 +585,7 @@ fn seal_in_place_separate_tag_(
     aad: Aad<&[u8]>,
     in_out: &mut [u8],
 ) -> Result<Tag, error::Unspecified> {
-    check_per_nonce_max_bytes(key.algorithm, in_out.len())?;
+    check_per_nonce_max_bytes(key.algorithm, aad.as_ref().len(), in_out.len())?;
     Ok((key.algorithm.seal)(
         &key.inner,
         nonce,
@@ -420,6 +663,27 @@ impl UnboundKey {
         })
     }
 
+    /// Derives a key for `algorithm` from `master_key`, bound to `context`.
+    ///
+    /// The key is the output of [HKDF-Expand] using `master_key` as the PRK
+    /// and `context` as the info, so keys derived from the same master key
+    /// for different contexts (e.g. different protocols, or different
+    /// directions of a connection) are independent. `master_key` must already
+    /// be a uniformly random key, such as the output of HKDF-Extract; see
+    /// `hkdf::Prk::new_less_safe()`.
+    ///
+    /// [HKDF-Expand]: https://tools.ietf.org/html/rfc5869#section-2.3
+    pub fn derive(
+        master_key: &hmac::Key,
+        context: &[u8],
+        algorithm: &'static Algorithm,
+    ) -> Result<Self, error::Unspecified> {
+        let prk = hkdf::Prk::from_hmac_key(master_key.clone());
+        let info = [context];
+        let okm = prk.expand(&info, algorithm)?;
+        Ok(Self::from(okm))
+    }
+
     /// The key's AEAD algorithm.
     #[inline]
     pub fn algorithm(&self) -> &'static Algorithm {
@@ -491,6 +755,35 @@ impl LessSafeKey {
         open_within_(&self.key, nonce, aad, in_out, ciphertext_and_tag)
     }
 
+    /// Like [`LessSafeKey::open_in_place()`], except the tag is given
+    /// separately instead of at the end of `in_out`, e.g. as it was returned
+    /// from [`LessSafeKey::seal_in_place_separate_tag()`].
+    ///
+    /// On input, `in_out` must be the ciphertext, and on success it will have
+    /// been overwritten with the plaintext. When
+    /// `open_in_place_separate_tag()` returns `Err(..)`, `in_out` may have
+    /// been overwritten in an unspecified way.
+    ///
+    /// `nonce` must be unique for every use of the key to open data.
+    pub fn open_in_place_separate_tag<'in_out, A>(
+        &self,
+        nonce: Nonce,
+        Aad(aad): Aad<A>,
+        tag: &Tag,
+        in_out: &'in_out mut [u8],
+    ) -> Result<&'in_out mut [u8], error::Unspecified>
+    where
+        A: AsRef<[u8]>,
+    {
+        let key = &self.key;
+        let aad = Aad::from(aad.as_ref());
+        check_per_nonce_max_bytes(key.algorithm, aad.as_ref().len(), in_out.len())?;
+        let Tag(calculated_tag) =
+            (key.algorithm.open)(&key.inner, nonce, aad, 0, in_out, key.cpu_features);
+        verify_tag_and_zero_on_failure(&calculated_tag[..], tag.as_ref(), in_out)?;
+        Ok(in_out)
+    }
+
     /// Deprecated. Renamed to `seal_in_place_append_tag()`.
     #[deprecated(note = "Renamed to `seal_in_place_append_tag`.")]
     #[inline]
@@ -558,6 +851,113 @@ impl core::fmt::Debug for LessSafeKey {
     }
 }
 
+/// An AES-GCM key that uses tags shorter than the usual 128 bits, for
+/// interoperability with peers that require them.
+///
+/// NIST SP 800-38D permits AES-GCM tags of 96, 104, 112, 120, or 128 bits.
+/// Short tags weaken authentication: a forgery succeeds with probability
+/// roughly 2<sup>-t</sup> for a `t`-bit tag, and for AES-GCM the forgery
+/// probability increases further with the length of the messages and the
+/// number of forgery attempts, so prefer full-length tags whenever the
+/// protocol allows. A truncated tag is the prefix of the full tag.
+///
+/// Like `LessSafeKey`, a `TruncatedTagKey` accepts an arbitrary nonce for
+/// each operation; `nonce` must be unique for every use of the key.
+pub struct TruncatedTagKey {
+    key: UnboundKey,
+    tag_len: usize,
+}
+
+impl TruncatedTagKey {
+    /// Constructs a `TruncatedTagKey` from an `UnboundKey` that uses tags of
+    /// `tag_len` bytes.
+    ///
+    /// Fails unless `key` is an `AES_128_GCM` or `AES_256_GCM` key and
+    /// `tag_len` is 12, 13, 14, 15, or 16.
+    pub fn new(key: UnboundKey, tag_len: usize) -> Result<Self, error::Unspecified> {
+        match key.algorithm.id {
+            AlgorithmID::AES_128_GCM | AlgorithmID::AES_256_GCM => {}
+            AlgorithmID::CHACHA20_POLY1305 => return Err(error::Unspecified),
+        }
+        if tag_len < 96 / 8 || tag_len > TAG_LEN {
+            return Err(error::Unspecified);
+        }
+        Ok(Self { key, tag_len })
+    }
+
+    /// Like [`LessSafeKey::open_in_place()`], except the tag at the end of
+    /// `in_out` is `self.tag_len()` bytes long.
+    ///
+    /// `nonce` must be unique for every use of the key to open data.
+    pub fn open_in_place<'in_out, A>(
+        &self,
+        nonce: Nonce,
+        Aad(aad): Aad<A>,
+        in_out: &'in_out mut [u8],
+    ) -> Result<&'in_out mut [u8], error::Unspecified>
+    where
+        A: AsRef<[u8]>,
+    {
+        let key = &self.key;
+        let ciphertext_len = in_out
+            .len()
+            .checked_sub(self.tag_len)
+            .ok_or(error::Unspecified)?;
+        let aad = Aad::from(aad.as_ref());
+        check_per_nonce_max_bytes(key.algorithm, aad.as_ref().len(), ciphertext_len)?;
+        let (in_out, received_tag) = in_out.split_at_mut(ciphertext_len);
+        let Tag(calculated_tag) =
+            (key.algorithm.open)(&key.inner, nonce, aad, 0, in_out, key.cpu_features);
+        verify_tag_and_zero_on_failure(&calculated_tag[..self.tag_len], received_tag, in_out)?;
+        Ok(in_out)
+    }
+
+    /// Like [`LessSafeKey::seal_in_place_append_tag()`], except only the
+    /// first `self.tag_len()` bytes of the tag are appended.
+    ///
+    /// `nonce` must be unique for every use of the key to seal data.
+    pub fn seal_in_place_append_tag<A, InOut>(
+        &self,
+        nonce: Nonce,
+        aad: Aad<A>,
+        in_out: &mut InOut,
+    ) -> Result<(), error::Unspecified>
+    where
+        A: AsRef<[u8]>,
+        InOut: AsMut<[u8]> + for<'in_out> Extend<&'in_out u8>,
+    {
+        let tag = seal_in_place_separate_tag_(
+            &self.key,
+            nonce,
+            Aad::from(aad.as_ref()),
+            in_out.as_mut(),
+        )?;
+        in_out.extend(&tag.as_ref()[..self.tag_len]);
+        Ok(())
+    }
+
+    /// The length of the key's (truncated) tags, in bytes.
+    #[inline]
+    pub fn tag_len(&self) -> usize {
+        self.tag_len
+    }
+
+    /// The key's AEAD algorithm.
+    #[inline]
+    pub fn algorithm(&self) -> &'static Algorithm {
+        &self.key.algorithm
+    }
+}
+
+impl core::fmt::Debug for TruncatedTagKey {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        f.debug_struct("TruncatedTagKey")
+            .field("algorithm", self.algorithm())
+            .field("tag_len", &self.tag_len)
+            .finish()
+    }
+}
+
 /// An AEAD Algorithm.
 pub struct Algorithm {
     init: fn(key: &[u8], cpu_features: cpu::Features) -> Result<KeyInner, error::Unspecified>,
@@ -584,6 +984,9 @@ pub struct Algorithm {
     /// Use `max_input_len!()` to initialize this.
     // TODO: Make this `usize`.
     max_input_len: u64,
+
+    /// The maximum length of the AAD, in bytes.
+    max_aad_len: u64,
 }
 
 const fn max_input_len(block_len: usize, overhead_blocks_per_nonce: usize) -> u64 {
@@ -613,6 +1016,20 @@ impl Algorithm {
     pub fn nonce_len(&self) -> usize {
         NONCE_LEN
     }
+
+    /// Looks up an algorithm by its canonical name.
+    ///
+    /// The recognized names are "aes-128-gcm", "aes-256-gcm", and
+    /// "chacha20-poly1305". Matching is exact; `None` is returned for any
+    /// other name.
+    pub fn from_name(name: &str) -> Option<&'static Self> {
+        match name {
+            "aes-128-gcm" => Some(&AES_128_GCM),
+            "aes-256-gcm" => Some(&AES_256_GCM),
+            "chacha20-poly1305" => Some(&CHACHA20_POLY1305),
+            _ => None,
+        }
+    }
 }
 
 derive_debug_via_id!(Algorithm);
@@ -635,14 +1052,50 @@ impl Eq for Algorithm {}
 /// An authentication tag.
 #[must_use]
 #[repr(C)]
+#[derive(Clone, Copy)]
 pub struct Tag([u8; TAG_LEN]);
 
+impl Tag {
+    /// Verifies, in constant time, that `other` is equal to this tag.
+    ///
+    /// Fails if `other` isn't the same length as the tag.
+    pub fn verify_eq(&self, other: &[u8]) -> Result<(), error::Unspecified> {
+        constant_time::verify_slices_are_equal(&self.0, other)
+    }
+}
+
+/// Verifies, in constant time, that `received` is equal to `computed`, e.g.
+/// for a tag that was transmitted separately from the ciphertext.
+///
+/// This is a free-function form of [`Tag::verify_eq()`], which it calls.
+#[inline]
+pub fn verify_tag(computed: &Tag, received: &[u8]) -> Result<(), error::Unspecified> {
+    computed.verify_eq(received)
+}
+
 impl AsRef<[u8]> for Tag {
     fn as_ref(&self) -> &[u8] {
         self.0.as_ref()
     }
 }
 
+impl From<[u8; TAG_LEN]> for Tag {
+    #[inline]
+    fn from(value: [u8; TAG_LEN]) -> Self {
+        Self(value)
+    }
+}
+
+impl core::convert::TryFrom<&[u8]> for Tag {
+    type Error = error::Unspecified;
+
+    /// Fails if `value` isn't `MAX_TAG_LEN` bytes long.
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        let value: &[u8; TAG_LEN] = value.try_into()?;
+        Ok(Self(*value))
+    }
+}
+
 const MAX_KEY_LEN: usize = 32;
 
 // All the AEADs we support use 128-bit tags.
@@ -651,10 +1104,17 @@ const TAG_LEN: usize = BLOCK_LEN;
 /// The maximum length of a tag for the algorithms in this module.
 pub const MAX_TAG_LEN: usize = TAG_LEN;
 
-fn check_per_nonce_max_bytes(alg: &Algorithm, in_out_len: usize) -> Result<(), error::Unspecified> {
+fn check_per_nonce_max_bytes(
+    alg: &Algorithm,
+    aad_len: usize,
+    in_out_len: usize,
+) -> Result<(), error::Unspecified> {
     if polyfill::u64_from_usize(in_out_len) > alg.max_input_len {
         return Err(error::Unspecified);
     }
+    if polyfill::u64_from_usize(aad_len) > alg.max_aad_len {
+        return Err(error::Unspecified);
+    }
     Ok(())
 }
 
@@ -664,14 +1124,15 @@ enum Direction {
     Sealing,
 }
 
-mod aes;
+pub(crate) mod aes;
 mod aes_gcm;
-mod block;
+pub(crate) mod block;
 mod chacha;
+pub mod chacha20;
 mod chacha20_poly1305;
 pub mod chacha20_poly1305_openssh;
 mod counter;
-mod gcm;
+pub(crate) mod gcm;
 mod iv;
 mod nonce;
 mod poly1305;
@@ -111,6 +111,17 @@ fn ctr32_encrypt_blocks_(
     ctr.increment_by_less_safe(blocks_u32);
 }
 
+fn key_bits(bytes: &[u8], variant: Variant) -> Result<BitLength, error::Unspecified> {
+    let key_bits = match variant {
+        Variant::AES_128 => BitLength::from_usize_bits(128),
+        Variant::AES_256 => BitLength::from_usize_bits(256),
+    };
+    if BitLength::from_usize_bytes(bytes.len())? != key_bits {
+        return Err(error::Unspecified);
+    }
+    Ok(key_bits)
+}
+
 impl Key {
     #[inline]
     pub fn new(
@@ -118,13 +129,7 @@ impl Key {
         variant: Variant,
         cpu_features: cpu::Features,
     ) -> Result<Self, error::Unspecified> {
-        let key_bits = match variant {
-            Variant::AES_128 => BitLength::from_usize_bits(128),
-            Variant::AES_256 => BitLength::from_usize_bits(256),
-        };
-        if BitLength::from_usize_bytes(bytes.len())? != key_bits {
-            return Err(error::Unspecified);
-        }
+        let key_bits = key_bits(bytes, variant)?;
 
         let mut key = AES_KEY {
             rd_key: [0u32; 4 * (MAX_ROUNDS + 1)],
@@ -320,6 +325,40 @@ impl Key {
     }
 }
 
+/// A key for the AES inverse cipher, for the few modes that need to decrypt.
+///
+/// Only the portable bitsliced implementation implements the inverse cipher,
+/// so this is slower than `Key` on targets with AES or vector instructions.
+pub(crate) struct DecryptKey {
+    inner: AES_KEY,
+}
+
+impl DecryptKey {
+    pub fn new(bytes: &[u8], variant: Variant) -> Result<Self, error::Unspecified> {
+        let key_bits = key_bits(bytes, variant)?;
+
+        let mut key = AES_KEY {
+            rd_key: [0u32; 4 * (MAX_ROUNDS + 1)],
+            rounds: 0,
+        };
+        // The inverse cipher uses the encryption key schedule in reverse.
+        set_encrypt_key!(GFp_aes_nohw_set_encrypt_key, bytes, key_bits, &mut key)?;
+
+        Ok(Self { inner: key })
+    }
+
+    pub fn decrypt_block(&self, a: Block) -> Block {
+        extern "C" {
+            fn GFp_aes_nohw_decrypt(a: &Block, r: *mut Block, key: &AES_KEY);
+        }
+        let mut result = core::mem::MaybeUninit::uninit();
+        unsafe {
+            GFp_aes_nohw_decrypt(&a, result.as_mut_ptr(), &self.inner);
+            result.assume_init()
+        }
+    }
+}
+
 // Keep this in sync with AES_KEY in aes.h.
 #[repr(C)]
 pub(super) struct AES_KEY {
@@ -418,26 +457,25 @@ mod tests {
     pub fn test_aes() {
         test::run(test_file!("aes_tests.txt"), |section, test_case| {
             assert_eq!(section, "");
-            let key = consume_key(test_case, "Key");
+            let key = test_case.consume_bytes("Key");
+            let variant = || match key.len() {
+                16 => Variant::AES_128,
+                32 => Variant::AES_256,
+                _ => unreachable!(),
+            };
             let input = test_case.consume_bytes("Input");
             let input: &[u8; BLOCK_LEN] = input.as_slice().try_into()?;
             let expected_output = test_case.consume_bytes("Output");
 
+            let aes_key = Key::new(&key, variant(), cpu::features())?;
             let block = Block::from(input);
-            let output = key.encrypt_block(block);
+            let output = aes_key.encrypt_block(block);
             assert_eq!(output.as_ref(), &expected_output[..]);
 
+            let decrypt_key = DecryptKey::new(&key, variant())?;
+            assert_eq!(decrypt_key.decrypt_block(output).as_ref(), input);
+
             Ok(())
         })
     }
-
-    fn consume_key(test_case: &mut test::TestCase, name: &str) -> Key {
-        let key = test_case.consume_bytes(name);
-        let variant = match key.len() {
-            16 => Variant::AES_128,
-            32 => Variant::AES_256,
-            _ => unreachable!(),
-        };
-        Key::new(&key[..], variant, cpu::features()).unwrap()
-    }
 }
@@ -26,6 +26,7 @@ pub static AES_128_GCM: aead::Algorithm = aead::Algorithm {
     open: aes_gcm_open,
     id: aead::AlgorithmID::AES_128_GCM,
     max_input_len: AES_GCM_MAX_INPUT_LEN,
+    max_aad_len: AES_GCM_MAX_AAD_LEN,
 };
 
 /// AES-256 in GCM mode with 128-bit tags and 96 bit nonces.
@@ -36,6 +37,7 @@ pub static AES_256_GCM: aead::Algorithm = aead::Algorithm {
     open: aes_gcm_open,
     id: aead::AlgorithmID::AES_256_GCM,
     max_input_len: AES_GCM_MAX_INPUT_LEN,
+    max_aad_len: AES_GCM_MAX_AAD_LEN,
 };
 
 pub struct Key {
@@ -177,7 +179,9 @@ fn aead(
         output
     });
 
-    // Authenticate the final block containing the input lengths.
+    // Authenticate the final block containing the input lengths, in bits.
+    // `check_per_nonce_max_bytes` ensures that neither length overflows when
+    // converted to bits.
     let aad_bits = polyfill::u64_from_usize(aad_len) << 3;
     let ciphertext_bits = polyfill::u64_from_usize(total_in_out_len) << 3;
     gcm_ctx.update_block(Block::from_u64_be(
@@ -276,6 +280,10 @@ fn integrated_aes_gcm<'a>(
 
 const AES_GCM_MAX_INPUT_LEN: u64 = super::max_input_len(BLOCK_LEN, 2);
 
+// [NIST SP800-38D] Section 5.2.1.1 limits the AAD to 2^64 - 1 bits, as the
+// length block encodes its length in bits as a 64-bit value.
+const AES_GCM_MAX_AAD_LEN: u64 = u64::max_value() / 8;
+
 #[cfg(test)]
 mod tests {
     #[test]
@@ -298,4 +306,35 @@ mod tests {
             NIST_SP800_38D_MAX_BITS
         );
     }
+
+    #[test]
+    fn max_aad_len_test() {
+        // [NIST SP800-38D] Section 5.2.1.1: len(A) <= 2^64 - 1 bits, i.e. the
+        // length in bits must be representable as a `u64`.
+        assert_eq!(super::AES_GCM_MAX_AAD_LEN, (1u64 << 61) - 1);
+        assert!(super::AES_GCM_MAX_AAD_LEN.checked_mul(8).is_some());
+        assert!((super::AES_GCM_MAX_AAD_LEN + 1).checked_mul(8).is_none());
+        assert_eq!(super::AES_128_GCM.max_aad_len, super::AES_GCM_MAX_AAD_LEN);
+        assert_eq!(super::AES_256_GCM.max_aad_len, super::AES_GCM_MAX_AAD_LEN);
+    }
+
+    // The lengths are checked before any data is processed, so synthetic
+    // lengths can be used to test the limits without any actual data.
+    #[cfg(target_pointer_width = "64")]
+    #[test]
+    fn max_lens_enforced_test() {
+        use crate::aead::check_per_nonce_max_bytes;
+
+        for alg in &[&super::AES_128_GCM, &super::AES_256_GCM] {
+            let max_input_len = alg.max_input_len as usize;
+            assert!(check_per_nonce_max_bytes(alg, 0, max_input_len).is_ok());
+            assert!(check_per_nonce_max_bytes(alg, 0, max_input_len + 1).is_err());
+
+            let max_aad_len = alg.max_aad_len as usize;
+            assert!(check_per_nonce_max_bytes(alg, max_aad_len, 0).is_ok());
+            assert!(check_per_nonce_max_bytes(alg, max_aad_len + 1, 0).is_err());
+
+            assert!(check_per_nonce_max_bytes(alg, max_aad_len, max_input_len).is_ok());
+        }
+    }
 }
@@ -0,0 +1,64 @@
+//! The ChaCha20 stream cipher, without authentication.
+//!
+//! This is the variant of ChaCha20 specified in [RFC 8439], with a 96-bit
+//! nonce and a 32-bit block counter. It is the same implementation that backs
+//! `aead::CHACHA20_POLY1305`.
+//!
+//! ChaCha20 on its own provides confidentiality but no integrity; the
+//! ciphertext is malleable. Only use this module to build a construction that
+//! authenticates the ciphertext some other way. Almost all applications
+//! should use `ring::aead` instead.
+//!
+//! [RFC 8439]: https://tools.ietf.org/html/rfc8439
+
+use super::{
+    chacha::{self, Counter},
+    Nonce, NONCE_LEN,
+};
+use crate::{cpu, error, polyfill};
+
+/// A ChaCha20 key.
+pub struct Key(chacha::Key);
+
+impl Key {
+    /// Constructs a new `Key` from the given key material.
+    pub fn new(key_bytes: [u8; KEY_LEN]) -> Self {
+        let _ = cpu::features();
+        Self(chacha::Key::from(key_bytes))
+    }
+
+    /// XORs `in_out` with the keystream for `nonce`, starting at block
+    /// `counter`.
+    ///
+    /// Encryption and decryption are the same operation. Blocks are 64 bytes
+    /// long, so to skip the first `n` bytes of the keystream, where `n` is a
+    /// multiple of 64, start at block `counter + n / 64`.
+    ///
+    /// The same (`nonce`, `counter`) pair must never be used twice with the
+    /// same key for different inputs.
+    ///
+    /// Fails if the block counter would overflow, i.e. if `in_out` extends
+    /// beyond the 2^32th block of the keystream.
+    pub fn apply_keystream(
+        &self,
+        nonce: &[u8; NONCE_LEN],
+        counter: u32,
+        in_out: &mut [u8],
+    ) -> Result<(), error::Unspecified> {
+        let max_len = (polyfill::u64_from_usize(KEYSTREAM_BLOCK_LEN) << 32)
+            - (u64::from(counter) * polyfill::u64_from_usize(KEYSTREAM_BLOCK_LEN));
+        if polyfill::u64_from_usize(in_out.len()) > max_len {
+            return Err(error::Unspecified);
+        }
+
+        let counter = Counter::new(Nonce::assume_unique_for_key(*nonce), counter);
+        self.0.encrypt_in_place(counter, in_out);
+        Ok(())
+    }
+}
+
+/// The length of a ChaCha20 key.
+pub const KEY_LEN: usize = chacha::KEY_LEN;
+
+// The length of a block of the ChaCha20 keystream.
+const KEYSTREAM_BLOCK_LEN: usize = 64;
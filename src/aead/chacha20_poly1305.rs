@@ -32,6 +32,9 @@ pub static CHACHA20_POLY1305: aead::Algorithm = aead::Algorithm {
     open: chacha20_poly1305_open,
     id: aead::AlgorithmID::CHACHA20_POLY1305,
     max_input_len: super::max_input_len(64, 1),
+
+    // RFC 8439 Section 2.8 limits the AAD to 2^64 - 1 bytes.
+    max_aad_len: u64::max_value(),
 };
 
 /// Copies |key| into |ctx_buf|.
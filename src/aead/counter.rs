@@ -52,7 +52,7 @@ where
         )
     }
 
-    fn new(nonce: Nonce, initial_counter: u32) -> Self {
+    pub fn new(nonce: Nonce, initial_counter: u32) -> Self {
         let mut r = Self {
             u32s: [U32::ZERO; COUNTER_LEN],
         };
@@ -75,6 +75,19 @@ impl Key {
     }
 }
 
+/// Multiplies `a` by `b` in GHASH's field, GF(2^128), using the same
+/// constant-time implementation as GHASH.
+pub(crate) fn gf128_mul(
+    a: &[u8; BLOCK_LEN],
+    b: &[u8; BLOCK_LEN],
+    cpu_features: cpu::Features,
+) -> [u8; BLOCK_LEN] {
+    let key = Key::new(Block::from(b), cpu_features);
+    let mut ctx = Context::new(&key, Aad::from(&[][..]), cpu_features);
+    ctx.update_block(Block::from(a));
+    *Block::from(ctx.inner.Xi).as_ref()
+}
+
 pub struct Context {
     inner: ContextInner,
     cpu_features: cpu::Features,
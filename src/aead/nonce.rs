@@ -18,6 +18,12 @@ use core::convert::TryInto;
 /// A nonce for a single AEAD opening or sealing operation.
 ///
 /// The user must ensure, for a particular key, that each nonce is unique.
+/// Reusing a nonce with the same key can reveal the plaintexts and, for some
+/// algorithms, allow forgeries. The constructors are named
+/// `assume_unique_for_key` and `try_assume_unique_for_key` so that this
+/// obligation is visible wherever a `Nonce` is constructed; when possible,
+/// use a `NonceSequence` with `SealingKey`/`OpeningKey` instead of
+/// constructing each `Nonce` manually.
 ///
 /// `Nonce` intentionally doesn't implement `Clone` to ensure that each one is
 /// consumed at most once.
@@ -0,0 +1,5 @@
+//! AES modes of operation that aren't AEADs.
+//!
+//! Almost all applications should use `ring::aead` instead.
+
+pub mod xts;
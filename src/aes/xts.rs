@@ -0,0 +1,234 @@
+//! XTS-AES, as specified in [IEEE 1619] and [NIST SP 800-38E], for encrypting
+//! fixed-size units of storage such as disk sectors.
+//!
+//! XTS provides confidentiality but no integrity: the ciphertext is
+//! malleable at the granularity of 16-byte blocks, and the same plaintext
+//! written to the same sector always produces the same ciphertext. It exists
+//! for the case where the ciphertext must be exactly as long as the
+//! plaintext, so that there is nowhere to store a nonce or a tag. Almost all
+//! other applications should use `ring::aead` instead.
+//!
+//! Encryption uses the same AES implementation as `aead::AES_128_GCM` and
+//! `aead::AES_256_GCM`. *ring* doesn't otherwise need the AES inverse
+//! cipher, so decryption always uses the portable bitsliced implementation,
+//! which is much slower than encryption on most targets.
+//!
+//! [IEEE 1619]: https://standards.ieee.org/standard/1619-2018.html
+//! [NIST SP 800-38E]: https://nvlpubs.nist.gov/nistpubs/Legacy/SP/nistspecialpublication800-38e.pdf
+
+use crate::{
+    aead::{
+        aes::{self, Variant},
+        block::{Block, BLOCK_LEN},
+    },
+    cpu, error,
+};
+
+/// The maximum length of a sector, in bytes. IEEE 1619 limits a data unit to
+/// 2**20 blocks.
+pub const MAX_SECTOR_LEN: usize = (1 << 20) * BLOCK_LEN;
+
+/// The minimum length of a sector, in bytes: one AES block.
+pub const MIN_SECTOR_LEN: usize = BLOCK_LEN;
+
+/// An XTS-AES-128 or XTS-AES-256 key.
+pub struct XtsKey {
+    data_key: aes::Key,
+    data_decrypt_key: aes::DecryptKey,
+    tweak_key: aes::Key,
+}
+
+impl XtsKey {
+    /// Constructs a new key from `key1`, the key used to encrypt the data,
+    /// and `key2`, the key used to encrypt the tweak.
+    ///
+    /// The keys must be both 16 bytes long, for XTS-AES-128, or both 32 bytes
+    /// long, for XTS-AES-256. They should be independent; NIST SP 800-38E
+    /// additionally requires them to be different, but that isn't enforced
+    /// here so that the IEEE 1619 test vectors can be used.
+    pub fn new(key1: &[u8], key2: &[u8]) -> Result<Self, error::Unspecified> {
+        let variant = || match key1.len() {
+            16 => Ok(Variant::AES_128),
+            32 => Ok(Variant::AES_256),
+            _ => Err(error::Unspecified),
+        };
+        if key2.len() != key1.len() {
+            return Err(error::Unspecified);
+        }
+        let cpu_features = cpu::features();
+        Ok(Self {
+            data_key: aes::Key::new(key1, variant()?, cpu_features)?,
+            data_decrypt_key: aes::DecryptKey::new(key1, variant()?)?,
+            tweak_key: aes::Key::new(key2, variant()?, cpu_features)?,
+        })
+    }
+
+    /// Encrypts the sector with the given index in place.
+    ///
+    /// The index is the IEEE 1619 data unit sequence number. `buf` must be
+    /// between `MIN_SECTOR_LEN` and `MAX_SECTOR_LEN` bytes long; it doesn't
+    /// need to be a multiple of the block length, since XTS uses ciphertext
+    /// stealing for the last partial block.
+    pub fn encrypt_sector(
+        &self,
+        sector_index: u128,
+        buf: &mut [u8],
+    ) -> Result<(), error::Unspecified> {
+        check_sector_len(buf)?;
+        let mut tweak = self.initial_tweak(sector_index);
+
+        let partial_len = buf.len() % BLOCK_LEN;
+        let whole_len = buf.len() - partial_len;
+        for block in buf[..whole_len].chunks_mut(BLOCK_LEN) {
+            self.encrypt_block(block, &tweak);
+            tweak = mul_alpha(tweak);
+        }
+
+        if partial_len > 0 {
+            // The last whole block of ciphertext provides the tail of the
+            // final block of plaintext, and is truncated to become the final
+            // partial block of ciphertext (IEEE 1619 Section 5.3.2).
+            let (head, partial) = buf.split_at_mut(whole_len);
+            let last_whole = &mut head[(whole_len - BLOCK_LEN)..];
+            steal(last_whole, partial);
+            self.encrypt_block(last_whole, &tweak);
+        }
+
+        Ok(())
+    }
+
+    /// Decrypts the sector with the given index in place.
+    ///
+    /// `sector_index` and the length of `buf` must be the same as when the
+    /// sector was encrypted.
+    pub fn decrypt_sector(
+        &self,
+        sector_index: u128,
+        buf: &mut [u8],
+    ) -> Result<(), error::Unspecified> {
+        check_sector_len(buf)?;
+        let mut tweak = self.initial_tweak(sector_index);
+
+        let partial_len = buf.len() % BLOCK_LEN;
+        let whole_len = buf.len() - partial_len;
+
+        // With ciphertext stealing, the last whole block is decrypted with
+        // the tweak that follows it (IEEE 1619 Section 5.4.2).
+        let normal_len = if partial_len > 0 {
+            whole_len - BLOCK_LEN
+        } else {
+            whole_len
+        };
+        for block in buf[..normal_len].chunks_mut(BLOCK_LEN) {
+            self.decrypt_block(block, &tweak);
+            tweak = mul_alpha(tweak);
+        }
+
+        if partial_len > 0 {
+            let next_tweak = mul_alpha(tweak);
+            let (head, partial) = buf.split_at_mut(whole_len);
+            let last_whole = &mut head[normal_len..];
+            self.decrypt_block(last_whole, &next_tweak);
+            steal(last_whole, partial);
+            self.decrypt_block(last_whole, &tweak);
+        }
+
+        Ok(())
+    }
+
+    fn initial_tweak(&self, sector_index: u128) -> [u8; BLOCK_LEN] {
+        let tweak = self
+            .tweak_key
+            .encrypt_block(Block::from(&sector_index.to_le_bytes()));
+        *tweak.as_ref()
+    }
+
+    fn encrypt_block(&self, block: &mut [u8], tweak: &[u8; BLOCK_LEN]) {
+        let mut b = [0u8; BLOCK_LEN];
+        b.copy_from_slice(block);
+        xor_assign(&mut b, tweak);
+        let mut b = *self.data_key.encrypt_block(Block::from(&b)).as_ref();
+        xor_assign(&mut b, tweak);
+        block.copy_from_slice(&b);
+    }
+
+    fn decrypt_block(&self, block: &mut [u8], tweak: &[u8; BLOCK_LEN]) {
+        let mut b = [0u8; BLOCK_LEN];
+        b.copy_from_slice(block);
+        xor_assign(&mut b, tweak);
+        let mut b = *self
+            .data_decrypt_key
+            .decrypt_block(Block::from(&b))
+            .as_ref();
+        xor_assign(&mut b, tweak);
+        block.copy_from_slice(&b);
+    }
+}
+
+impl core::fmt::Debug for XtsKey {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        f.debug_struct("XtsKey").finish()
+    }
+}
+
+fn check_sector_len(buf: &[u8]) -> Result<(), error::Unspecified> {
+    if buf.len() < MIN_SECTOR_LEN || buf.len() > MAX_SECTOR_LEN {
+        return Err(error::Unspecified);
+    }
+    Ok(())
+}
+
+// Swaps the first `partial.len()` bytes of `last_whole` with `partial`.
+fn steal(last_whole: &mut [u8], partial: &mut [u8]) {
+    for (a, b) in last_whole.iter_mut().zip(partial.iter_mut()) {
+        core::mem::swap(a, b);
+    }
+}
+
+fn xor_assign(a: &mut [u8; BLOCK_LEN], b: &[u8; BLOCK_LEN]) {
+    for (a, b) in a.iter_mut().zip(b.iter()) {
+        *a ^= b;
+    }
+}
+
+// Multiplies the tweak by the primitive element α in GF(2**128), with the
+// little-endian byte order and the reduction polynomial
+// x**128 + x**7 + x**2 + x + 1 of IEEE 1619 Section 5.2.
+fn mul_alpha(t: [u8; BLOCK_LEN]) -> [u8; BLOCK_LEN] {
+    let mut out = [0u8; BLOCK_LEN];
+    let mut carry = 0;
+    for (out, t) in out.iter_mut().zip(t.iter()) {
+        *out = (t << 1) | carry;
+        carry = t >> 7;
+    }
+    out[0] ^= 0x87 & 0u8.wrapping_sub(carry);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mul_alpha() {
+        let mut one = [0u8; BLOCK_LEN];
+        one[0] = 1;
+        let mut expected = [0u8; BLOCK_LEN];
+        expected[0] = 2;
+        assert_eq!(mul_alpha(one), expected);
+
+        // The carry propagates between bytes.
+        let mut t = [0u8; BLOCK_LEN];
+        t[3] = 0x80;
+        let mut expected = [0u8; BLOCK_LEN];
+        expected[4] = 1;
+        assert_eq!(mul_alpha(t), expected);
+
+        // x**127 * x = x**7 + x**2 + x + 1.
+        let mut high = [0u8; BLOCK_LEN];
+        high[15] = 0x80;
+        let mut expected = [0u8; BLOCK_LEN];
+        expected[0] = 0x87;
+        assert_eq!(mul_alpha(high), expected);
+    }
+}
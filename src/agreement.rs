@@ -58,11 +58,15 @@
 //!
 //! # Ok::<(), ring::error::Unspecified>(())
 //! ```
+//!
+//! For protocols where the same private key is used for many key agreements,
+//! use a `StaticPrivateKey` with `agree_static` instead of an
+//! `EphemeralPrivateKey` with `agree_ephemeral`.
 
 // The "NSA Guide" steps here are from from section 3.1, "Ephemeral Unified
 // Model."
 
-use crate::{cpu, debug, ec, error, rand};
+use crate::{cpu, debug, ec, error, hkdf, rand};
 
 pub use crate::ec::{
     curve25519::x25519::X25519,
@@ -77,6 +81,10 @@ pub struct Algorithm {
         private_key: &ec::Seed,
         peer_public_key: untrusted::Input,
     ) -> Result<(), error::Unspecified>,
+    pub(crate) private_key_from_pkcs8: fn(
+        input: untrusted::Input,
+        cpu_features: cpu::Features,
+    ) -> Result<ec::Seed, error::KeyRejected>,
 }
 
 derive_debug_via_field!(Algorithm, curve);
@@ -121,6 +129,33 @@ impl EphemeralPrivateKey {
         })
     }
 
+    /// Constructs an ephemeral private key from its raw encoding: the
+    /// big-endian encoding of the scalar for ECDH P-256 and P-384, and the
+    /// 32-byte scalar as specified in [RFC 7748] for X25519.
+    ///
+    /// This is intended for reproducing test vectors that fix the ephemeral
+    /// private key. In real protocols, ephemeral private keys should always
+    /// be constructed with `generate()`; reusing the same bytes for multiple
+    /// key agreements defeats the purpose of an ephemeral key.
+    ///
+    /// Fails if `bytes` isn't the right length for `alg` or if the scalar is
+    /// out of range for the curve.
+    ///
+    /// Requires the `dangerous` feature.
+    ///
+    /// [RFC 7748]: https://tools.ietf.org/html/rfc7748
+    #[cfg(feature = "dangerous")]
+    pub fn from_bytes(alg: &'static Algorithm, bytes: &[u8]) -> Result<Self, error::KeyRejected> {
+        let cpu_features = cpu::features();
+        let private_key =
+            ec::Seed::from_bytes(alg.curve, untrusted::Input::from(bytes), cpu_features)
+                .map_err(|error::Unspecified| error::KeyRejected::invalid_component())?;
+        Ok(Self {
+            private_key,
+            algorithm: alg,
+        })
+    }
+
     /// Computes the public key from the private key.
     #[inline(always)]
     pub fn compute_public_key(&self) -> Result<PublicKey, error::Unspecified> {
@@ -149,6 +184,196 @@ impl EphemeralPrivateKey {
     }
 }
 
+/// A static private key for use with `agree_static`.
+///
+/// Unlike an `EphemeralPrivateKey`, a `StaticPrivateKey` is borrowed, not
+/// consumed, by key agreement, so it can be used for any number of key
+/// agreements. Use it only when the protocol calls for a long-lived key, e.g.
+/// a server's static Diffie-Hellman key; otherwise use `EphemeralPrivateKey`,
+/// which guarantees forward secrecy by construction.
+pub struct StaticPrivateKey {
+    private_key: ec::Seed,
+    algorithm: &'static Algorithm,
+}
+
+derive_debug_via_field!(StaticPrivateKey, stringify!(StaticPrivateKey), algorithm);
+
+impl StaticPrivateKey {
+    /// Generate a new static private key for the given algorithm.
+    pub fn generate(
+        alg: &'static Algorithm,
+        rng: &dyn rand::SecureRandom,
+    ) -> Result<Self, error::Unspecified> {
+        let cpu_features = cpu::features();
+        let private_key = ec::Seed::generate(alg.curve, rng, cpu_features)?;
+        Ok(Self {
+            private_key,
+            algorithm: alg,
+        })
+    }
+
+    /// Constructs a static private key from its raw encoding: the big-endian
+    /// encoding of the scalar for ECDH P-256 and P-384, and the 32-byte
+    /// scalar as specified in [RFC 7748] for X25519.
+    ///
+    /// [RFC 7748]: https://tools.ietf.org/html/rfc7748
+    pub fn from_bytes(alg: &'static Algorithm, bytes: &[u8]) -> Result<Self, error::KeyRejected> {
+        let cpu_features = cpu::features();
+        let private_key =
+            ec::Seed::from_bytes(alg.curve, untrusted::Input::from(bytes), cpu_features)
+                .map_err(|error::Unspecified| error::KeyRejected::invalid_component())?;
+        Ok(Self {
+            private_key,
+            algorithm: alg,
+        })
+    }
+
+    /// Constructs a static private key from an unencrypted PKCS#8 document.
+    ///
+    /// For ECDH P-256 and P-384 the document must be in the same format that
+    /// `signature::EcdsaKeyPair::from_pkcs8()` accepts for the same curve,
+    /// including the public key. For X25519 the document must be in the
+    /// format specified in [RFC 8410]; the public key is optional, but it
+    /// must match the private key if present.
+    ///
+    /// [RFC 8410]: https://tools.ietf.org/html/rfc8410
+    pub fn from_pkcs8(alg: &'static Algorithm, pkcs8: &[u8]) -> Result<Self, error::KeyRejected> {
+        let cpu_features = cpu::features();
+        let private_key =
+            (alg.private_key_from_pkcs8)(untrusted::Input::from(pkcs8), cpu_features)?;
+        Ok(Self {
+            private_key,
+            algorithm: alg,
+        })
+    }
+
+    /// Computes the public key from the private key.
+    pub fn compute_public_key(&self) -> Result<PublicKey, error::Unspecified> {
+        self.private_key
+            .compute_public_key()
+            .map(|public_key| PublicKey {
+                algorithm: self.algorithm,
+                bytes: public_key,
+            })
+    }
+
+    /// The algorithm for the private key.
+    #[inline]
+    pub fn algorithm(&self) -> &'static Algorithm {
+        self.algorithm
+    }
+}
+
+/// Computes the encoded public key for the raw private key `private_key_bytes`,
+/// without constructing a private key object for key agreement, e.g. to
+/// publish the public key of a static private key.
+///
+/// `private_key_bytes` is in the format that `StaticPrivateKey::from_bytes()`
+/// accepts, and is validated the same way; for ECDH P-256 and P-384, the
+/// scalar must be in the range [1, *n*). The public key is encoded the same way as `PublicKey`.
+#[cfg(feature = "alloc")]
+pub fn public_key_from_private(
+    alg: &'static Algorithm,
+    private_key_bytes: &[u8],
+) -> Result<alloc::vec::Vec<u8>, error::Unspecified> {
+    let cpu_features = cpu::features();
+    let private_key = ec::Seed::from_bytes(
+        alg.curve,
+        untrusted::Input::from(private_key_bytes),
+        cpu_features,
+    )?;
+    let public_key = private_key.compute_public_key()?;
+    Ok(public_key.as_ref().to_vec())
+}
+
+/// A scalar modulo the order of an elliptic curve group, as returned by
+/// `reduce_scalar()` and `generate_private_scalar()`.
+#[derive(Clone)]
+pub struct Scalar {
+    bytes: [u8; ec::SCALAR_MAX_BYTES],
+    len: usize,
+    algorithm: &'static Algorithm,
+}
+
+derive_debug_via_field!(Scalar, algorithm);
+
+impl AsRef<[u8]> for Scalar {
+    /// The big-endian encoding of the scalar, padded with leading zeros to the
+    /// length of the group order.
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes[..self.len]
+    }
+}
+
+impl Scalar {
+    /// The algorithm whose group order the scalar is modulo.
+    #[inline]
+    pub fn algorithm(&self) -> &'static Algorithm {
+        self.algorithm
+    }
+}
+
+/// Reduces `wide_bytes` modulo the order *n* of the group for `alg`.
+///
+/// `wide_bytes` is the big-endian encoding of a non-negative integer. It must
+/// not be empty and must be at most twice as long as an encoded scalar, i.e.
+/// 64 bytes for ECDH P-256 and 96 bytes for ECDH P-384. This is intended for
+/// hash-to-scalar constructions, where a value much wider than *n* (e.g. 48
+/// bytes for P-256) is reduced so that the result is nearly uniformly
+/// distributed. The reduction is done in constant time with respect to the
+/// value of `wide_bytes`, but not with respect to its length.
+///
+/// The result may be zero. X25519 isn't supported.
+pub fn reduce_scalar(
+    alg: &'static Algorithm,
+    wide_bytes: &[u8],
+) -> Result<Scalar, error::Unspecified> {
+    let len = alg.curve.elem_scalar_seed_len;
+    let mut bytes = [0u8; ec::SCALAR_MAX_BYTES];
+    ec::suite_b::reduce_wide_scalar(
+        alg.curve,
+        untrusted::Input::from(wide_bytes),
+        &mut bytes[..len],
+    )?;
+    Ok(Scalar {
+        bytes,
+        len,
+        algorithm: alg,
+    })
+}
+
+/// Generates a private scalar for `alg`, uniformly distributed in the range
+/// [1, *n*), where *n* is the order of the group.
+///
+/// This uses the same rejection sampling that is used to generate private
+/// keys, as described in the "NSA Suite B Implementer's Guide to FIPS 186-3,"
+/// Appendix B.4.1: candidates are drawn from `rng` until one is in range.
+/// The result is big-endian encoded and padded with leading zeros to the
+/// length of the group order.
+///
+/// Fails if `rng` fails or if no candidate is accepted after a bounded number
+/// of attempts. X25519 isn't supported.
+pub fn generate_private_scalar(
+    alg: &'static Algorithm,
+    rng: &dyn rand::SecureRandom,
+) -> Result<Scalar, error::Unspecified> {
+    if alg.curve.id == ec::CurveID::Curve25519 {
+        return Err(error::Unspecified);
+    }
+    let cpu_features = cpu::features();
+    let seed = ec::Seed::generate(alg.curve, rng, cpu_features)?;
+    let seed_bytes = seed.bytes_less_safe();
+    let len = seed_bytes.len();
+    let mut bytes = [0u8; ec::SCALAR_MAX_BYTES];
+    bytes[..len].copy_from_slice(seed_bytes);
+    Ok(Scalar {
+        bytes,
+        len,
+        algorithm: alg,
+    })
+}
+
 /// A public key for key agreement.
 #[derive(Clone)]
 pub struct PublicKey {
@@ -265,11 +490,85 @@ where
         algorithm: peer_public_key.algorithm,
         bytes: peer_public_key.bytes.as_ref(),
     };
-    agree_ephemeral_(my_private_key, peer_public_key, error_value, kdf)
+    agree_(
+        &my_private_key.private_key,
+        my_private_key.algorithm,
+        peer_public_key,
+        error_value,
+        kdf,
+    )
 }
 
-fn agree_ephemeral_<F, R, E>(
+/// Performs a key agreement with an ephemeral private key and the given public
+/// key, and derives `out` from the result with HKDF.
+///
+/// The shared secret is HKDF-Extracted with `salt` and the resulting PRK is
+/// HKDF-Expanded with `info` to fill `out`, so the raw shared secret is never
+/// exposed to the caller. The hash function is the one `salt` was constructed
+/// with.
+///
+/// `my_private_key` and `peer_public_key` are used exactly as in
+/// `agree_ephemeral`. Fails if the agreement fails or if `out` is too long for
+/// HKDF-Expand (see `hkdf::Okm::fill()`).
+pub fn agree_ephemeral_hkdf<B: AsRef<[u8]>>(
     my_private_key: EphemeralPrivateKey,
+    peer_public_key: &UnparsedPublicKey<B>,
+    salt: &hkdf::Salt,
+    info: &[&[u8]],
+    out: &mut [u8],
+) -> Result<(), error::Unspecified> {
+    struct OutLen(usize);
+
+    impl hkdf::KeyType for OutLen {
+        fn len(&self) -> usize {
+            self.0
+        }
+    }
+
+    agree_ephemeral(
+        my_private_key,
+        peer_public_key,
+        error::Unspecified,
+        |shared_key| {
+            salt.extract(shared_key)
+                .expand(info, OutLen(out.len()))?
+                .fill(out)
+        },
+    )
+}
+
+/// Performs a key agreement with a static private key and the given public
+/// key.
+///
+/// `my_private_key` is only borrowed, so it can be used for any number of key
+/// agreements. Otherwise, `agree_static` is exactly like `agree_ephemeral`;
+/// in particular, `peer_public_key` is validated in the same way.
+#[inline]
+pub fn agree_static<B: AsRef<[u8]>, F, R, E>(
+    my_private_key: &StaticPrivateKey,
+    peer_public_key: &UnparsedPublicKey<B>,
+    error_value: E,
+    kdf: F,
+) -> Result<R, E>
+where
+    F: FnOnce(&[u8]) -> Result<R, E>,
+{
+    let peer_public_key = UnparsedPublicKey {
+        algorithm: peer_public_key.algorithm,
+        bytes: peer_public_key.bytes.as_ref(),
+    };
+    agree_(
+        &my_private_key.private_key,
+        my_private_key.algorithm,
+        peer_public_key,
+        error_value,
+        kdf,
+    )
+}
+
+fn agree_<F, R, E>(
+    my_private_key: &ec::Seed,
+    my_algorithm: &'static Algorithm,
     peer_public_key: UnparsedPublicKey<&[u8]>,
     error_value: E,
     kdf: F,
@@ -282,11 +581,11 @@ where
     // The domain parameters are hard-coded. This check verifies that the
     // peer's public key's domain parameters match the domain parameters of
     // this private key.
-    if peer_public_key.algorithm != my_private_key.algorithm {
+    if peer_public_key.algorithm != my_algorithm {
         return Err(error_value);
     }
 
-    let alg = &my_private_key.algorithm;
+    let alg = my_algorithm;
 
     // NSA Guide Prerequisite 2, regarding which KDFs are allowed, is delegated
     // to the caller.
@@ -296,7 +595,8 @@ where
     // during the key-agreement scheme," is delegated to the caller.
 
     // NSA Guide Step 1 is handled by `EphemeralPrivateKey::generate()` and
-    // `EphemeralPrivateKey::compute_public_key()`.
+    // `EphemeralPrivateKey::compute_public_key()`, or by the equivalent
+    // `StaticPrivateKey` functions.
 
     let mut shared_key = [0u8; ec::ELEM_MAX_BYTES];
     let shared_key = &mut shared_key[..alg.curve.elem_scalar_seed_len];
@@ -307,7 +607,7 @@ where
     // that doesn't meet the NSA requirement to "zeroize."
     (alg.ecdh)(
         shared_key,
-        &my_private_key.private_key,
+        my_private_key,
         untrusted::Input::from(peer_public_key.bytes),
     )
     .map_err(|_| error_value)?;
@@ -25,6 +25,9 @@ impl BitLength {
         Self(bits)
     }
 
+    /// The length of `bytes` bytes, in bits.
+    ///
+    /// Fails if the number of bits doesn't fit in a `usize`.
     #[inline]
     pub fn from_usize_bytes(bytes: usize) -> Result<Self, error::Unspecified> {
         let bits = bytes.checked_mul(8).ok_or(error::Unspecified)?;
@@ -43,6 +46,8 @@ impl BitLength {
         self.0
     }
 
+    /// The number of bytes needed to hold this many bits, i.e. the bit length
+    /// divided by 8, rounded up.
     #[cfg(feature = "alloc")]
     #[inline]
     pub fn as_usize_bytes_rounded_up(&self) -> usize {
@@ -62,3 +67,41 @@ impl BitLength {
         Ok(BitLength(sum))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_as_usize_bytes_rounded_up() {
+        for &(bits, bytes) in &[
+            (0, 0),
+            (1, 1),
+            (7, 1),
+            (8, 1),
+            (9, 2),
+            (2047, 256),
+            (2048, 256),
+            (2049, 257),
+            (usize::max_value(), (usize::max_value() / 8) + 1),
+        ] {
+            assert_eq!(
+                BitLength::from_usize_bits(bits).as_usize_bytes_rounded_up(),
+                bytes
+            );
+        }
+    }
+
+    #[test]
+    fn test_from_usize_bytes() {
+        for &bytes in &[0, 1, 32, 256, usize::max_value() / 8] {
+            let bits = BitLength::from_usize_bytes(bytes).unwrap();
+            assert_eq!(bits.as_usize_bits(), bytes * 8);
+            #[cfg(feature = "alloc")]
+            assert_eq!(bits.as_usize_bytes_rounded_up(), bytes);
+        }
+        assert!(BitLength::from_usize_bytes((usize::max_value() / 8) + 1).is_err());
+        assert!(BitLength::from_usize_bytes(usize::max_value()).is_err());
+    }
+}
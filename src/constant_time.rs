@@ -14,21 +14,263 @@
 
 //! Constant-time operations.
 
-use crate::{c, error};
+use crate::{aead, c, cpu, error, polyfill};
 
 /// Returns `Ok(())` if `a == b` and `Err(error::Unspecified)` otherwise.
 /// The comparison of `a` and `b` is done in constant time with respect to the
 /// contents of each, but NOT in constant time with respect to the lengths of
 /// `a` and `b`.
 pub fn verify_slices_are_equal(a: &[u8], b: &[u8]) -> Result<(), error::Unspecified> {
+    verify_slices_are_equal_checked(a, b).map_err(error::Unspecified::from)
+}
+
+/// Like `verify_slices_are_equal`, but distinguishes a length mismatch from a
+/// value mismatch.
+///
+/// A length mismatch almost always indicates a programming error (e.g. passing
+/// a truncated tag) rather than a forgery, so it is reported as
+/// `Mismatch::Length`. The lengths of `a` and `b` are not secret and are
+/// compared first; the contents of equal-length inputs are compared in
+/// constant time exactly as `verify_slices_are_equal` does, and no
+/// information about *where* the contents differ is reported.
+pub fn verify_slices_are_equal_checked(a: &[u8], b: &[u8]) -> Result<(), Mismatch> {
     if a.len() != b.len() {
-        return Err(error::Unspecified);
+        return Err(Mismatch::Length);
     }
     let result = unsafe { GFp_memcmp(a.as_ptr(), b.as_ptr(), a.len()) };
     match result {
         0 => Ok(()),
-        _ => Err(error::Unspecified),
+        _ => Err(Mismatch::Value),
+    }
+}
+
+/// The reason `verify_slices_are_equal_checked` failed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Mismatch {
+    /// The inputs have different lengths.
+    Length,
+
+    /// The inputs have the same length but different contents.
+    Value,
+}
+
+impl From<Mismatch> for error::Unspecified {
+    fn from(_: Mismatch) -> Self {
+        error::Unspecified
+    }
+}
+
+/// Returns `true` if every byte of `bytes` is zero.
+///
+/// `bytes` is compared against zeros in constant time, as in
+/// `verify_slices_are_equal`, so the timing depends only on `bytes.len()`.
+/// This is useful for rejecting all-zero shared secrets and scalars.
+pub fn is_zero(bytes: &[u8]) -> bool {
+    const ZEROS: [u8; 64] = [0; 64];
+    let acc = bytes.chunks(ZEROS.len()).fold(0, |acc, chunk| {
+        acc | unsafe { GFp_memcmp(chunk.as_ptr(), ZEROS.as_ptr(), chunk.len()) }
+    });
+    acc == 0
+}
+
+/// A machine word for the constant-time selection primitives.
+pub type Word = u64;
+
+/// Returns a mask of all ones if `condition` is true and all zeros otherwise,
+/// for use with `select_word`.
+///
+/// The mask is computed arithmetically, without branching on `condition`.
+#[inline]
+pub fn mask_from_bool(condition: bool) -> Word {
+    Word::from(condition).wrapping_neg()
+}
+
+/// Returns `a` if `mask` is all ones and `b` if `mask` is all zeros, without
+/// branching on `mask`.
+///
+/// `mask` must be all ones or all zeros, e.g. the result of
+/// `mask_from_bool`; otherwise the result is a bitwise mix of `a` and `b`.
+#[inline]
+pub fn select_word(mask: Word, a: Word, b: Word) -> Word {
+    (a & mask) | (b & !mask)
+}
+
+/// Multiplies `a` and `b` as elements of GF(2^128), in constant time.
+///
+/// The field and the encoding of its elements are the ones that GHASH uses,
+/// as specified in [NIST SP 800-38D]: the first bit of `a[0]` is the
+/// coefficient of x^0, and the product is reduced by the polynomial
+/// x^128 + x^7 + x^2 + x + 1. This uses the same implementation as AES-GCM,
+/// including carry-less multiplication instructions such as PCLMULQDQ where
+/// available.
+///
+/// [NIST SP 800-38D]: https://nvlpubs.nist.gov/nistpubs/Legacy/SP/nistspecialpublication800-38d.pdf
+pub fn gf128_mul(a: [u8; 16], b: [u8; 16]) -> [u8; 16] {
+    aead::gcm::gf128_mul(&a, &b, cpu::features())
+}
+
+/// A base64 alphabet, as specified in [RFC 4648].
+///
+/// [RFC 4648]: https://tools.ietf.org/html/rfc4648
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Base64Alphabet {
+    /// The standard alphabet, using `+` and `/` for the values 62 and 63.
+    Standard,
+
+    /// The URL- and filename-safe alphabet, using `-` and `_` for the values
+    /// 62 and 63.
+    UrlSafe,
+}
+
+/// Encodes `input` as padded base64 into `out`, returning the encoded prefix
+/// of `out`.
+///
+/// The mapping from values to characters is done with arithmetic instead of
+/// table lookups, so the timing doesn't depend on the contents of `input`.
+/// Fails if `out` is shorter than `4 * ((input.len() + 2) / 3)` bytes.
+pub fn base64_encode<'o>(
+    input: &[u8],
+    alphabet: Base64Alphabet,
+    out: &'o mut [u8],
+) -> Result<&'o [u8], error::Unspecified> {
+    let out_len = ((input.len() + 2) / 3) * 4;
+    let out = out.get_mut(..out_len).ok_or(error::Unspecified)?;
+
+    for (chunk, out) in input.chunks(3).zip(out.chunks_mut(4)) {
+        let b0 = u32::from(chunk[0]);
+        let b1 = chunk.get(1).map_or(0, |b| u32::from(*b));
+        let b2 = chunk.get(2).map_or(0, |b| u32::from(*b));
+        let bits = (b0 << 16) | (b1 << 8) | b2;
+
+        out[0] = base64_encode_value(bits >> 18, alphabet);
+        out[1] = base64_encode_value(bits >> 12, alphabet);
+        out[2] = base64_encode_value(bits >> 6, alphabet);
+        out[3] = base64_encode_value(bits, alphabet);
+
+        // The number of padding characters depends only on the length.
+        if chunk.len() < 3 {
+            out[3] = b'=';
+        }
+        if chunk.len() < 2 {
+            out[2] = b'=';
+        }
+    }
+
+    Ok(out)
+}
+
+/// Decodes the base64 `input` into `out`, returning the decoded prefix of
+/// `out`.
+///
+/// Padding is optional; if present, `input` must consist of complete groups
+/// of four characters. Non-canonical encodings, i.e. those with nonzero
+/// unused trailing bits, are rejected. Fails if `out` is too short to hold the
+/// decoded value.
+///
+/// The mapping from characters to values is done with arithmetic instead of
+/// table lookups, and every character is processed even when an invalid one
+/// is found, so the timing doesn't depend on the contents of `input`; it only
+/// depends on its length and on the amount of padding. On failure, `out` is
+/// zeroed.
+pub fn base64_decode<'o>(
+    input: &[u8],
+    alphabet: Base64Alphabet,
+    out: &'o mut [u8],
+) -> Result<&'o [u8], error::Unspecified> {
+    // Padding and the length are not secret.
+    let input = if input.len() % 4 == 0 {
+        let padding_len = input
+            .iter()
+            .rev()
+            .take(2)
+            .take_while(|&&c| c == b'=')
+            .count();
+        &input[..(input.len() - padding_len)]
+    } else {
+        input
+    };
+
+    let out_len = match input.len() % 4 {
+        0 => input.len() / 4 * 3,
+        2 => input.len() / 4 * 3 + 1,
+        3 => input.len() / 4 * 3 + 2,
+        _ => {
+            return Err(error::Unspecified);
+        }
+    };
+    let out = out.get_mut(..out_len).ok_or(error::Unspecified)?;
+
+    let mut invalid = 0u32;
+    for (chunk, out) in input.chunks(4).zip(out.chunks_mut(3)) {
+        let mut bits = 0u32;
+        for (i, c) in chunk.iter().enumerate() {
+            let value = base64_decode_char(*c, alphabet);
+            // `value` is all ones if `c` is invalid.
+            invalid |= (value >> 8) & 1;
+            bits |= (value & 0x3f) << (18 - (6 * i));
+        }
+
+        // Any bits beyond the last whole byte must be zero.
+        let leftover = match chunk.len() {
+            2 => bits & 0xffff,
+            3 => bits & 0xff,
+            _ => 0,
+        };
+        invalid |= leftover;
+
+        let bytes = [(bits >> 16) as u8, (bits >> 8) as u8, bits as u8];
+        out.copy_from_slice(&bytes[..out.len()]);
+    }
+
+    if invalid != 0 {
+        polyfill::slice::fill(out, 0);
+        return Err(error::Unspecified);
+    }
+
+    Ok(out)
+}
+
+// Maps the low six bits of `value` to a character without secret-dependent
+// branches or memory accesses.
+fn base64_encode_value(value: u32, alphabet: Base64Alphabet) -> u8 {
+    let v = (value & 0x3f) as i32;
+    let mut diff: i32 = i32::from(b'A');
+    diff += ((25 - v) >> 8) & 6; // 26..=63: 'a' - 26
+    diff -= ((51 - v) >> 8) & 75; // 52..=63: '0' - 52
+    match alphabet {
+        Base64Alphabet::Standard => {
+            diff -= ((61 - v) >> 8) & 15; // 62: '+'
+            diff += ((62 - v) >> 8) & 3; // 63: '/'
+        }
+        Base64Alphabet::UrlSafe => {
+            diff -= ((61 - v) >> 8) & 13; // 62: '-'
+            diff += ((62 - v) >> 8) & 49; // 63: '_'
+        }
+    }
+    (v + diff) as u8
+}
+
+// Maps a character to its value, or to `u32::MAX` if it isn't in the
+// alphabet, without secret-dependent branches or memory accesses.
+//
+// `((lo - c) & (c - hi)) >> 8` is all ones exactly when `lo < c < hi`.
+fn base64_decode_char(c: u8, alphabet: Base64Alphabet) -> u32 {
+    let c = i32::from(c);
+    let mut r: i32 = -1;
+    r += (((0x40 - c) & (c - 0x5b)) >> 8) & (c - 64); // 'A'..='Z'
+    r += (((0x60 - c) & (c - 0x7b)) >> 8) & (c - 70); // 'a'..='z'
+    r += (((0x2f - c) & (c - 0x3a)) >> 8) & (c + 5); // '0'..='9'
+    match alphabet {
+        Base64Alphabet::Standard => {
+            r += (((0x2a - c) & (c - 0x2c)) >> 8) & 63; // '+'
+            r += (((0x2e - c) & (c - 0x30)) >> 8) & 64; // '/'
+        }
+        Base64Alphabet::UrlSafe => {
+            r += (((0x2c - c) & (c - 0x2e)) >> 8) & 63; // '-'
+            r += (((0x5e - c) & (c - 0x60)) >> 8) & 64; // '_'
+        }
     }
+    r as u32
 }
 
 extern "C" {
@@ -365,6 +365,11 @@ pub(crate) mod intel {
         mask: 1 << 28,
     };
 
+    pub(crate) const RDRAND: Feature = Feature {
+        word: 1,
+        mask: 1 << 30,
+    };
+
     #[cfg(all(target_arch = "x86_64", test))]
     mod x86_64_tests {
         use super::*;
@@ -82,3 +82,18 @@ pub(crate) fn write_hex_bytes(
     }
     Ok(())
 }
+
+/// Decodes a single hex digit, which may be uppercase or lowercase.
+#[cfg(any(feature = "alloc", feature = "serde"))]
+pub(crate) fn from_hex_digit(d: u8) -> Result<u8, crate::error::Unspecified> {
+    use core::ops::RangeInclusive;
+    const DECIMAL: (u8, RangeInclusive<u8>) = (0, b'0'..=b'9');
+    const HEX_LOWER: (u8, RangeInclusive<u8>) = (10, b'a'..=b'f');
+    const HEX_UPPER: (u8, RangeInclusive<u8>) = (10, b'A'..=b'F');
+    for (offset, range) in &[DECIMAL, HEX_LOWER, HEX_UPPER] {
+        if range.contains(&d) {
+            return Ok(d - range.start() + offset);
+        }
+    }
+    Err(crate::error::Unspecified)
+}
@@ -12,11 +12,19 @@
 // OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
 // CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
 
-//! SHA-2 and the legacy SHA-1 digest algorithm.
+//! SHA-2, SM3, and the legacy SHA-1 digest algorithm.
 //!
 //! If all the data is available in a single contiguous slice then the `digest`
 //! function should be used. Otherwise, the digest can be calculated in
 //! multiple steps using `Context`.
+//!
+//! SHA-3 and Keccak-256 are not implemented. Note that they are not
+//! interchangeable: Keccak-256, as used by Ethereum, is the pre-standard
+//! Keccak with the original `0x01` padding, whereas the standardized
+//! SHA3-256 uses `0x06` padding, so the two produce different digests for the
+//! same input. Both are sponge constructions that don't fit the
+//! Merkle–Damgård block processing that `Context` implements; adding either
+//! one would need a separate implementation rather than a new `Algorithm`.
 
 // Note on why are we doing things the hard way: It would be easy to implement
 // this using the C `EVP_MD`/`EVP_MD_CTX` interface. However, if we were to do
@@ -26,13 +34,17 @@
 
 use crate::{
     c, cpu, debug,
-    endian::{self, BigEndian},
-    polyfill,
+    endian::{self, BigEndian, FromByteArray},
+    error, polyfill,
 };
 use core::num::Wrapping;
 
+#[cfg(feature = "std")]
+extern crate std;
+
 mod sha1;
 mod sha2;
+mod sm3;
 
 #[derive(Clone)]
 pub(crate) struct BlockContext {
@@ -190,6 +202,45 @@ impl Context {
         }
     }
 
+    /// Updates the digest with each of `chunks`, in order. The result is the
+    /// same as calling `update` once with the concatenation of the chunks.
+    pub fn update_chunks<'a, I>(&mut self, chunks: I)
+    where
+        I: IntoIterator<Item = &'a [u8]>,
+    {
+        for chunk in chunks {
+            self.update(chunk);
+        }
+    }
+
+    /// Updates the digest with all the data read from `reader` until it
+    /// reaches end-of-file, returning the total number of bytes hashed.
+    ///
+    /// Data is read in chunks whose length is a multiple of the algorithm's
+    /// block length. Reads that fail with `ErrorKind::Interrupted` are
+    /// retried; any other error is returned, in which case the data read so
+    /// far has already been passed to `update`.
+    ///
+    /// Requires the `std` feature.
+    #[cfg(feature = "std")]
+    pub fn update_from_reader<R: std::io::Read>(&mut self, reader: &mut R) -> std::io::Result<u64> {
+        // A multiple of every supported algorithm's `block_len`.
+        const CHUNK_LEN: usize = 64 * MAX_BLOCK_LEN;
+
+        let mut buf = [0u8; CHUNK_LEN];
+        let mut total: u64 = 0;
+        loop {
+            let n = match reader.read(&mut buf) {
+                Ok(0) => return Ok(total),
+                Ok(n) => n,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            self.update(&buf[..n]);
+            total = total.checked_add(polyfill::u64_from_usize(n)).unwrap();
+        }
+    }
+
     /// Finalizes the digest calculation and returns the digest value. `finish`
     /// consumes the context so it cannot be (mis-)used after `finish` has been
     /// called.
@@ -199,11 +250,143 @@ impl Context {
             .finish(&mut self.pending[..block_len], self.num_pending)
     }
 
+    /// Like `finish`, but writes the digest value into `out` instead of
+    /// returning a `Digest`.
+    ///
+    /// `out.len()` must be exactly `self.algorithm().output_len`; otherwise
+    /// `finish_into` fails and `out` is left untouched. Either way, the
+    /// context is consumed.
+    pub fn finish_into(self, out: &mut [u8]) -> Result<(), error::Unspecified> {
+        if out.len() != self.algorithm().output_len {
+            return Err(error::Unspecified);
+        }
+        out.copy_from_slice(self.finish().as_ref());
+        Ok(())
+    }
+
     /// The algorithm that this context is using.
     #[inline(always)]
     pub fn algorithm(&self) -> &'static Algorithm {
         self.block.algorithm
     }
+
+    /// The total number of bytes passed to `update` so far.
+    ///
+    /// This is the length that will be encoded in the padding by `finish`.
+    #[inline]
+    pub fn bytes_processed(&self) -> u64 {
+        self.block.completed_data_blocks * polyfill::u64_from_usize(self.block.algorithm.block_len)
+            + polyfill::u64_from_usize(self.num_pending)
+    }
+
+    /// Serializes the internal state of the context for `restore_state`.
+    ///
+    /// The encoding is a one-byte algorithm identifier, the chaining value as
+    /// big-endian words, the number of completed blocks as a big-endian
+    /// `u64`, and then the pending (not yet processed) input bytes.
+    #[cfg(feature = "dangerous")]
+    pub(crate) fn save_state(&self) -> alloc::vec::Vec<u8> {
+        let algorithm = self.block.algorithm;
+        let mut out = alloc::vec::Vec::new();
+        out.push(algorithm.id.state_tag());
+        if algorithm.id.has_64_bit_words() {
+            for word in unsafe { &self.block.state.as64 } {
+                out.extend_from_slice(&word.0.to_be_bytes());
+            }
+        } else {
+            for word in unsafe { &self.block.state.as32 } {
+                out.extend_from_slice(&word.0.to_be_bytes());
+            }
+        }
+        out.extend_from_slice(&self.block.completed_data_blocks.to_be_bytes());
+        out.extend_from_slice(&self.pending[..self.num_pending]);
+        out
+    }
+
+    /// Reconstructs a context from the output of `save_state`.
+    ///
+    /// Fails if `state` wasn't produced by `save_state` for a context using
+    /// `algorithm`, or if it is otherwise malformed.
+    #[cfg(feature = "dangerous")]
+    pub(crate) fn restore_state(
+        algorithm: &'static Algorithm,
+        state: &[u8],
+    ) -> Result<Self, error::Unspecified> {
+        let (&tag, state) = state.split_first().ok_or(error::Unspecified)?;
+        if tag != algorithm.id.state_tag() {
+            return Err(error::Unspecified);
+        }
+
+        let mut block = BlockContext::new(algorithm);
+        let mut words_len = 0;
+        if algorithm.id.has_64_bit_words() {
+            let words = unsafe { &mut block.state.as64 };
+            for word in words.iter_mut() {
+                let mut bytes = [0u8; 8];
+                bytes.copy_from_slice(
+                    state
+                        .get(words_len..(words_len + 8))
+                        .ok_or(error::Unspecified)?,
+                );
+                *word = Wrapping(u64::from_be_bytes(bytes));
+                words_len += 8;
+            }
+        } else {
+            let words = unsafe { &mut block.state.as32 };
+            for word in words.iter_mut() {
+                let mut bytes = [0u8; 4];
+                bytes.copy_from_slice(
+                    state
+                        .get(words_len..(words_len + 4))
+                        .ok_or(error::Unspecified)?,
+                );
+                *word = Wrapping(u32::from_be_bytes(bytes));
+                words_len += 4;
+            }
+        }
+        let state = &state[words_len..];
+
+        let mut completed_data_blocks = [0u8; 8];
+        completed_data_blocks.copy_from_slice(state.get(..8).ok_or(error::Unspecified)?);
+        block.completed_data_blocks = u64::from_be_bytes(completed_data_blocks);
+
+        let pending = &state[8..];
+        if pending.len() >= algorithm.block_len {
+            return Err(error::Unspecified);
+        }
+
+        // Reject states whose length in bits can't be encoded by `finish`.
+        let _ = block
+            .completed_data_blocks
+            .checked_mul(polyfill::u64_from_usize(algorithm.block_len))
+            .and_then(|len| len.checked_add(polyfill::u64_from_usize(pending.len())))
+            .and_then(|len| len.checked_mul(8))
+            .ok_or(error::Unspecified)?;
+
+        let mut ctx = Self::clone_from(&block);
+        ctx.pending[..pending.len()].copy_from_slice(pending);
+        ctx.num_pending = pending.len();
+        Ok(ctx)
+    }
+}
+
+/// Allows a `Context` to be used as a sink for anything that writes to a
+/// `std::io::Write`. `write` passes all of its input to `update` and never
+/// fails; `flush` does nothing.
+///
+/// Requires the `std` feature.
+#[cfg(feature = "std")]
+impl std::io::Write for Context {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    #[inline]
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
 }
 
 /// Returns the digest of `data` using the given digest algorithm.
@@ -227,6 +410,46 @@ pub fn digest(algorithm: &'static Algorithm, data: &[u8]) -> Digest {
     ctx.finish()
 }
 
+/// Returns the digest of the sequence `components`, encoded so that different
+/// sequences never have the same encoding, in the style of TupleHash from
+/// [NIST SP 800-185].
+///
+/// This avoids the ambiguity of hashing the plain concatenation of the
+/// components, where e.g. `["ab", "c"]` and `["a", "bc"]` collide. Each
+/// component is encoded with SP 800-185's `encode_string`, and the digest is
+/// `algorithm` applied to the concatenation of the encoded components:
+///
+/// * `encode_string(X)` is `left_encode(8 * len(X)) || X`, i.e. the length of
+///   `X` in bits, followed by `X`.
+/// * `left_encode(x)` is a byte *n*, followed by the big-endian encoding of
+///   `x` in *n* bytes, where *n* is the smallest number of bytes (at least
+///   one) that can hold `x`.
+///
+/// For example, `["ab", ""]` is hashed as the bytes `01 10 61 62 01 00`. Note
+/// that the output differs from that of SP 800-185's TupleHash, which uses
+/// cSHAKE instead of an arbitrary digest algorithm and also encodes the
+/// output length.
+///
+/// [NIST SP 800-185]: https://doi.org/10.6028/NIST.SP.800-185
+pub fn tuple_hash(algorithm: &'static Algorithm, components: &[&[u8]]) -> Digest {
+    let mut ctx = Context::new(algorithm);
+    for component in components {
+        // `u128` can hold the length of any slice in bits.
+        let bit_len = (component.len() as u128) * 8;
+        let bit_len = bit_len.to_be_bytes();
+        let leading_zeros = bit_len
+            .iter()
+            .take(bit_len.len() - 1)
+            .take_while(|&&b| b == 0)
+            .count();
+        let bit_len = &bit_len[leading_zeros..];
+        ctx.update(&[bit_len.len() as u8]);
+        ctx.update(bit_len);
+        ctx.update(component);
+    }
+    ctx.finish()
+}
+
 /// A calculated digest value.
 ///
 /// Use `as_ref` to get the value as a `&[u8]`.
@@ -237,6 +460,29 @@ pub struct Digest {
 }
 
 impl Digest {
+    /// Constructs a `Digest` from a previously-calculated digest value, e.g.
+    /// one received from a peer, so that it can be handled like a `Digest`
+    /// calculated locally.
+    ///
+    /// Fails if `bytes.len()` isn't `algorithm.output_len`. Nothing else
+    /// about `bytes` is (or can be) checked.
+    pub fn from_bytes(
+        algorithm: &'static Algorithm,
+        bytes: &[u8],
+    ) -> Result<Self, error::Unspecified> {
+        if bytes.len() != algorithm.output_len {
+            return Err(error::Unspecified);
+        }
+        let mut value = [0u8; MAX_OUTPUT_LEN];
+        value[..bytes.len()].copy_from_slice(bytes);
+        Ok(Self {
+            value: Output {
+                as64: FromByteArray::from_byte_array(&value),
+            },
+            algorithm,
+        })
+    }
+
     /// The algorithm that was used to calculate the digest value.
     #[inline(always)]
     pub fn algorithm(&self) -> &'static Algorithm {
@@ -259,6 +505,115 @@ impl core::fmt::Debug for Digest {
     }
 }
 
+/// Formats the digest value as lowercase hex, without the algorithm name.
+impl core::fmt::Display for Digest {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+        core::fmt::LowerHex::fmt(self, fmt)
+    }
+}
+
+/// Formats the digest value as lowercase hex. The alternate flag (`{:#x}`)
+/// adds a `0x` prefix.
+impl core::fmt::LowerHex for Digest {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+        if fmt.alternate() {
+            fmt.write_str("0x")?;
+        }
+        debug::write_hex_bytes(fmt, self.as_ref())
+    }
+}
+
+/// Formats the digest value as uppercase hex. The alternate flag (`{:#X}`)
+/// adds a `0x` prefix.
+impl core::fmt::UpperHex for Digest {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+        if fmt.alternate() {
+            fmt.write_str("0x")?;
+        }
+        for byte in self.as_ref() {
+            write!(fmt, "{:02X}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+/// Serializes the digest as a string: the algorithm's serialized name (see
+/// below), a colon, and the value in lowercase hex, e.g.
+/// `"SHA256:e3b0c442...b855"`.
+///
+/// The serialized names are `SHA1`, `SHA256`, `SHA384`, `SHA512`,
+/// `SHA512_256`, and `SM3`. They are part of the format and won't change,
+/// regardless of how the algorithms are formatted with `Debug`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Digest {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&format_args!(
+            "{}:{:x}",
+            self.algorithm.id.serialized_name(),
+            self
+        ))
+    }
+}
+
+/// Deserializes a digest in the format produced by its `Serialize`
+/// implementation.
+///
+/// Fails if the algorithm isn't recognized or if the value isn't exactly
+/// `output_len` bytes of hex.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Digest {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor;
+
+        impl<'de> serde::de::Visitor<'de> for Visitor {
+            type Value = Digest;
+
+            fn expecting(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+                fmt.write_str("a digest algorithm and hex value, e.g. \"SHA256:e3b0...\"")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<Digest, E> {
+                parse_serialized(value).map_err(|error::Unspecified| {
+                    E::invalid_value(serde::de::Unexpected::Str(value), &self)
+                })
+            }
+        }
+
+        deserializer.deserialize_str(Visitor)
+    }
+}
+
+#[cfg(feature = "serde")]
+fn parse_serialized(value: &str) -> Result<Digest, error::Unspecified> {
+    use crate::debug::from_hex_digit;
+
+    const ALGORITHMS: [&Algorithm; 6] = [
+        &SHA1_FOR_LEGACY_USE_ONLY,
+        &SHA256,
+        &SHA384,
+        &SHA512,
+        &SHA512_256,
+        &SM3,
+    ];
+
+    let colon = value.find(':').ok_or(error::Unspecified)?;
+    let algorithm = ALGORITHMS
+        .iter()
+        .copied()
+        .find(|algorithm| algorithm.id.serialized_name() == &value[..colon])
+        .ok_or(error::Unspecified)?;
+
+    let hex = &value.as_bytes()[(colon + 1)..];
+    if hex.len() != 2 * algorithm.output_len {
+        return Err(error::Unspecified);
+    }
+    let mut bytes = [0u8; MAX_OUTPUT_LEN];
+    for (byte, digits) in bytes.iter_mut().zip(hex.chunks(2)) {
+        *byte = (from_hex_digit(digits[0])? << 4) | from_hex_digit(digits[1])?;
+    }
+    Digest::from_bytes(algorithm, &bytes[..algorithm.output_len])
+}
+
 /// A digest algorithm.
 pub struct Algorithm {
     /// The length of a finalized digest.
@@ -272,7 +627,9 @@ pub struct Algorithm {
     /// digest algorithm.
     pub chaining_len: usize,
 
-    /// The internal block length.
+    /// The internal block length, in bytes: 64 for SHA-1, SHA-256, and SM3,
+    /// and 128 for SHA-384, SHA-512, and SHA-512/256. This is the length to
+    /// which HMAC pads its key, for example.
     pub block_len: usize,
 
     /// The length of the length in the padding.
@@ -293,6 +650,98 @@ enum AlgorithmID {
     SHA384,
     SHA512,
     SHA512_256,
+    SM3,
+}
+
+#[cfg(feature = "serde")]
+impl AlgorithmID {
+    // The name of the algorithm in the serialized form of a `Digest`. These
+    // values must never change.
+    fn serialized_name(&self) -> &'static str {
+        match self {
+            AlgorithmID::SHA1 => "SHA1",
+            AlgorithmID::SHA256 => "SHA256",
+            AlgorithmID::SHA384 => "SHA384",
+            AlgorithmID::SHA512 => "SHA512",
+            AlgorithmID::SHA512_256 => "SHA512_256",
+            AlgorithmID::SM3 => "SM3",
+        }
+    }
+}
+
+#[cfg(feature = "dangerous")]
+impl AlgorithmID {
+    // Identifies the algorithm in the output of `Context::save_state`. These
+    // values must never change.
+    fn state_tag(&self) -> u8 {
+        match self {
+            AlgorithmID::SHA1 => 1,
+            AlgorithmID::SHA256 => 2,
+            AlgorithmID::SHA384 => 3,
+            AlgorithmID::SHA512 => 4,
+            AlgorithmID::SHA512_256 => 5,
+            AlgorithmID::SM3 => 6,
+        }
+    }
+
+    // Whether the chaining value is stored as `State::as64` (otherwise it's
+    // stored as `State::as32`).
+    fn has_64_bit_words(&self) -> bool {
+        match self {
+            AlgorithmID::SHA384 | AlgorithmID::SHA512 | AlgorithmID::SHA512_256 => true,
+            AlgorithmID::SHA1 | AlgorithmID::SHA256 | AlgorithmID::SM3 => false,
+        }
+    }
+}
+
+impl Algorithm {
+    /// Whether this algorithm will be computed using the CPU's dedicated
+    /// instructions for it on this machine, as determined by *ring*'s runtime
+    /// CPU feature detection.
+    ///
+    /// Currently this is only ever true for SHA-256 on ARM and AArch64 CPUs
+    /// that implement the ARMv8 SHA-256 instructions. Other implementations,
+    /// including ones that use general-purpose vector instructions, are not
+    /// considered hardware-accelerated.
+    pub fn has_hw_acceleration(&self) -> bool {
+        let cpu_features = cpu::features();
+        match self.id {
+            AlgorithmID::SHA256 => {
+                #[cfg(any(target_arch = "aarch64", target_arch = "arm"))]
+                {
+                    if cpu::arm::SHA256.available(cpu_features) {
+                        return true;
+                    }
+                }
+                let _ = cpu_features;
+                false
+            }
+            AlgorithmID::SHA1
+            | AlgorithmID::SHA384
+            | AlgorithmID::SHA512
+            | AlgorithmID::SHA512_256
+            | AlgorithmID::SM3 => false,
+        }
+    }
+
+    /// Whether the algorithm is one of the hash functions specified in
+    /// [FIPS 180-4]: SHA-1, SHA-256, SHA-384, SHA-512, and SHA-512/256.
+    ///
+    /// This is only about the algorithm; *ring* itself is not a FIPS
+    /// 140-validated module. Note also that SHA-1 is approved only for some
+    /// legacy uses, e.g. not for generating new digital signatures.
+    ///
+    /// [FIPS 180-4]: http://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.180-4.pdf
+    pub fn is_fips_approved(&self) -> bool {
+        match self.id {
+            AlgorithmID::SHA1
+            | AlgorithmID::SHA256
+            | AlgorithmID::SHA384
+            | AlgorithmID::SHA512
+            | AlgorithmID::SHA512_256 => true,
+            AlgorithmID::SM3 => false,
+        }
+    }
 }
 
 impl PartialEq for Algorithm {
@@ -434,6 +883,32 @@ pub static SHA512_256: Algorithm = Algorithm {
     id: AlgorithmID::SHA512_256,
 };
 
+/// SM3 as specified in GB/T 32905-2016, also known as GM/T 0004-2012.
+///
+/// SM3 is used by Chinese national standards, e.g. in SM2 signatures and
+/// TLCP. Don't use it unless interoperability requires it.
+pub static SM3: Algorithm = Algorithm {
+    output_len: sm3::OUTPUT_LEN,
+    chaining_len: sm3::CHAINING_LEN,
+    block_len: sm3::BLOCK_LEN,
+    len_len: 64 / 8,
+    block_data_order: sm3::block_data_order,
+    format_output: sha256_format_output,
+    initial_state: State {
+        as32: [
+            Wrapping(0x7380166fu32),
+            Wrapping(0x4914b2b9u32),
+            Wrapping(0x172442d7u32),
+            Wrapping(0xda8a0600u32),
+            Wrapping(0xa96f30bcu32),
+            Wrapping(0x163138aau32),
+            Wrapping(0xe38dee4du32),
+            Wrapping(0xb0fb0e4eu32),
+        ],
+    },
+    id: AlgorithmID::SM3,
+};
+
 #[derive(Clone, Copy)] // XXX: Why do we need to be `Copy`?
 #[repr(C)]
 union State {
@@ -507,6 +982,9 @@ pub const SHA512_OUTPUT_LEN: usize = 512 / 8;
 /// The length of the output of SHA-512/256, in bytes.
 pub const SHA512_256_OUTPUT_LEN: usize = 256 / 8;
 
+/// The length of the output of SM3, in bytes.
+pub const SM3_OUTPUT_LEN: usize = sm3::OUTPUT_LEN;
+
 /// The length of a block for SHA-512-based algorithms, in bytes.
 const SHA512_BLOCK_LEN: usize = 1024 / 8;
 
@@ -590,5 +1068,6 @@ mod tests {
         max_input_tests!(SHA256);
         max_input_tests!(SHA384);
         max_input_tests!(SHA512);
+        max_input_tests!(SM3);
     }
 }
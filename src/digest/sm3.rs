@@ -0,0 +1,101 @@
+//! SM3 as specified in GB/T 32905-2016 (originally GM/T 0004-2012). The
+//! section numbers below refer to that standard. An English description is
+//! in [draft-shen-sm3-hash].
+//!
+//! [draft-shen-sm3-hash]: https://tools.ietf.org/html/draft-shen-sm3-hash-01
+
+use super::sha2::{ch, maj, Word};
+use crate::c;
+use core::{convert::TryInto, num::Wrapping};
+
+pub const BLOCK_LEN: usize = 512 / 8;
+pub const CHAINING_LEN: usize = 256 / 8;
+pub const OUTPUT_LEN: usize = 256 / 8;
+const CHAINING_WORDS: usize = CHAINING_LEN / 4;
+
+type W32 = Wrapping<u32>;
+
+type State = [W32; CHAINING_WORDS];
+const ROUNDS: usize = 64;
+
+pub(super) extern "C" fn block_data_order(
+    state: &mut super::State,
+    data: *const u8,
+    num: c::size_t,
+) {
+    let state = unsafe { &mut state.as32 };
+    let state: &mut State = (&mut state[..CHAINING_WORDS]).try_into().unwrap();
+    let data = data as *const [<W32 as Word>::InputBytes; 16];
+    let blocks = unsafe { core::slice::from_raw_parts(data, num) };
+    *state = block_data_order_(*state, blocks)
+}
+
+#[inline]
+fn block_data_order_(mut V: State, B: &[[<W32 as Word>::InputBytes; 16]]) -> State {
+    for B in B {
+        // Section 5.3.2: Message expansion.
+        let mut W = [W32::ZERO; ROUNDS + 4];
+        for j in 0..16 {
+            W[j] = W32::from_be_bytes(B[j]);
+        }
+        for j in 16..(ROUNDS + 4) {
+            W[j] = p1(W[j - 16] ^ W[j - 9] ^ rotl(W[j - 3], 15)) ^ rotl(W[j - 13], 7) ^ W[j - 6];
+        }
+
+        // Section 5.3.3: Compression function.
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = V;
+        for j in 0..ROUNDS {
+            let (t, ff, gg): (W32, fn(W32, W32, W32) -> W32, fn(W32, W32, W32) -> W32) = if j < 16 {
+                (Wrapping(0x79cc4519), parity, parity)
+            } else {
+                (Wrapping(0x7a879d8a), maj, ch)
+            };
+            let a12 = rotl(a, 12);
+            let ss1 = rotl(a12 + e + rotl(t, (j % 32) as u32), 7);
+            let ss2 = ss1 ^ a12;
+            let tt1 = ff(a, b, c) + d + ss2 + (W[j] ^ W[j + 4]);
+            let tt2 = gg(e, f, g) + h + ss1 + W[j];
+            d = c;
+            c = rotl(b, 9);
+            b = a;
+            a = tt1;
+            h = g;
+            g = rotl(f, 19);
+            f = e;
+            e = p0(tt2);
+        }
+
+        V[0] ^= a;
+        V[1] ^= b;
+        V[2] ^= c;
+        V[3] ^= d;
+        V[4] ^= e;
+        V[5] ^= f;
+        V[6] ^= g;
+        V[7] ^= h;
+    }
+
+    V
+}
+
+// Section 4.3: FF_j and GG_j for 0 <= j <= 15.
+#[inline(always)]
+fn parity(x: W32, y: W32, z: W32) -> W32 {
+    x ^ y ^ z
+}
+
+// Section 4.4: Permutation functions.
+#[inline(always)]
+fn p0(x: W32) -> W32 {
+    x ^ rotl(x, 9) ^ rotl(x, 17)
+}
+
+#[inline(always)]
+fn p1(x: W32) -> W32 {
+    x ^ rotl(x, 15) ^ rotl(x, 23)
+}
+
+#[inline(always)]
+fn rotl(x: W32, n: u32) -> W32 {
+    Wrapping(x.0.rotate_left(n))
+}
@@ -39,6 +39,7 @@ pub enum CurveID {
     Curve25519,
     P256,
     P384,
+    Secp256k1,
 }
 
 const ELEM_MAX_BITS: usize = 384;
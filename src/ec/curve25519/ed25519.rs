@@ -15,7 +15,7 @@
 //! EdDSA Signatures.
 
 use super::ops::ELEM_LEN;
-use crate::digest;
+use crate::{digest, error};
 
 pub mod signing;
 pub mod verification;
@@ -23,10 +23,44 @@ pub mod verification;
 /// The length of an Ed25519 public key.
 pub const ED25519_PUBLIC_KEY_LEN: usize = ELEM_LEN;
 
-pub fn eddsa_digest(signature_r: &[u8], public_key: &[u8], msg: &[u8]) -> digest::Digest {
+pub fn eddsa_digest(
+    dom2: Option<&Dom2>,
+    signature_r: &[u8],
+    public_key: &[u8],
+    msg: &[u8],
+) -> digest::Digest {
     let mut ctx = digest::Context::new(&digest::SHA512);
+    if let Some(dom2) = dom2 {
+        dom2.update(&mut ctx);
+    }
     ctx.update(signature_r);
     ctx.update(public_key);
     ctx.update(msg);
     ctx.finish()
 }
+
+/// The `dom2(0, context)` prefix that Ed25519ctx prepends to the inputs of
+/// its hashes, as defined in [RFC 8032 Section 5.1].
+///
+/// [RFC 8032 Section 5.1]: https://tools.ietf.org/html/rfc8032#section-5.1
+pub struct Dom2<'a> {
+    context: &'a [u8],
+}
+
+impl<'a> Dom2<'a> {
+    /// Fails if `context` is longer than 255 bytes.
+    pub fn new(context: &'a [u8]) -> Result<Self, error::Unspecified> {
+        if context.len() > 255 {
+            return Err(error::Unspecified);
+        }
+        Ok(Self { context })
+    }
+
+    pub fn update(&self, ctx: &mut digest::Context) {
+        const PREFIX: &[u8] = b"SigEd25519 no Ed25519 collisions";
+        const PHFLAG: u8 = 0;
+        ctx.update(PREFIX);
+        ctx.update(&[PHFLAG, self.context.len() as u8]);
+        ctx.update(self.context);
+    }
+}
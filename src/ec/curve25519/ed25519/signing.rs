@@ -14,7 +14,7 @@
 
 //! EdDSA Signatures.
 
-use super::{super::ops::*, eddsa_digest, ED25519_PUBLIC_KEY_LEN};
+use super::{super::ops::*, eddsa_digest, Dom2, ED25519_PUBLIC_KEY_LEN};
 use crate::{
     digest, error,
     io::der,
@@ -82,6 +82,35 @@ impl Ed25519KeyPair {
         )
     }
 
+    /// Constructs an Ed25519 key pair by parsing a PEM-encoded unencrypted
+    /// PKCS#8 v2 Ed25519 private key, i.e. a `PRIVATE KEY` block.
+    ///
+    /// The first PEM block in `pem` is decoded with `pem::parse()` and then
+    /// parsed like `from_pkcs8()`.
+    #[cfg(feature = "alloc")]
+    pub fn from_pkcs8_pem(pem: &str) -> Result<Self, error::KeyRejected> {
+        let pkcs8 = crate::pem::parse_pkcs8(pem)?;
+        Self::from_pkcs8(&pkcs8)
+    }
+
+    /// Constructs an Ed25519 key pair by parsing a password-protected PKCS#8
+    /// `EncryptedPrivateKeyInfo` containing a PKCS#8 v2 Ed25519 private key.
+    ///
+    /// The key is decrypted with `pkcs8::decrypt()`, which documents the
+    /// supported encryption algorithms, and then parsed like `from_pkcs8()`.
+    /// Every failure, including a failure to parse the decrypted key, is
+    /// reported as the same error, so that the result doesn't reveal anything
+    /// about the decrypted bytes when the password is wrong.
+    #[cfg(feature = "alloc")]
+    pub fn from_pkcs8_encrypted(
+        encrypted: &[u8],
+        password: &[u8],
+    ) -> Result<Self, error::KeyRejected> {
+        let pkcs8 = pkcs8::decrypt(encrypted, password)
+            .map_err(|error::Unspecified| error::KeyRejected::decryption_failed())?;
+        Self::from_pkcs8(&pkcs8).map_err(|_| error::KeyRejected::decryption_failed())
+    }
+
     /// Constructs an Ed25519 key pair by parsing an unencrypted PKCS#8 v1 or v2
     /// Ed25519 private key.
     ///
@@ -177,6 +206,12 @@ impl Ed25519KeyPair {
 
     /// Returns the signature of the message `msg`.
     pub fn sign(&self, msg: &[u8]) -> signature::Signature {
+        self.sign_(None, msg)
+    }
+
+    /// Returns the Ed25519 signature of `msg`, or the Ed25519ctx signature if
+    /// `dom2` is given.
+    pub(super) fn sign_(&self, dom2: Option<&Dom2>, msg: &[u8]) -> signature::Signature {
         signature::Signature::new(|signature_bytes| {
             extern "C" {
                 fn GFp_x25519_sc_muladd(
@@ -191,6 +226,9 @@ impl Ed25519KeyPair {
             let (signature_r, signature_s) = signature_bytes.split_at_mut(ELEM_LEN);
             let nonce = {
                 let mut ctx = digest::Context::new(&digest::SHA512);
+                if let Some(dom2) = dom2 {
+                    dom2.update(&mut ctx);
+                }
                 ctx.update(&self.private_prefix);
                 ctx.update(msg);
                 ctx.finish()
@@ -202,7 +240,7 @@ impl Ed25519KeyPair {
                 GFp_x25519_ge_scalarmult_base(&mut r, &nonce);
             }
             signature_r.copy_from_slice(&r.into_encoded_point());
-            let hram_digest = eddsa_digest(signature_r, &self.public_key.as_ref(), msg);
+            let hram_digest = eddsa_digest(dom2, signature_r, &self.public_key.as_ref(), msg);
             let hram = Scalar::from_sha512_digest_reduced(hram_digest);
             unsafe {
                 GFp_x25519_sc_muladd(
@@ -14,7 +14,7 @@
 
 //! EdDSA Signatures.
 
-use super::{super::ops::*, eddsa_digest};
+use super::{super::ops::*, eddsa_digest, signing::Ed25519KeyPair, Dom2};
 use crate::{error, sealed, signature};
 use core::convert::TryInto;
 
@@ -31,9 +31,29 @@ impl core::fmt::Debug for EdDSAParameters {
 ///
 /// Ed25519 uses SHA-512 as the digest algorithm.
 ///
+/// Signatures whose scalar `S` is not less than the group order `L` are
+/// rejected, as required by [RFC 8032 Section 5.1.7], so a valid signature
+/// can't be modified into another valid signature by adding `L` to `S`.
+///
 /// [Ed25519]: https://ed25519.cr.yp.to/
+/// [RFC 8032 Section 5.1.7]: https://tools.ietf.org/html/rfc8032#section-5.1.7
 pub static ED25519: EdDSAParameters = EdDSAParameters {};
 
+/// Verification of [Ed25519] signatures, additionally rejecting public keys
+/// and signatures that contain non-canonical point encodings.
+///
+/// A point encoding is non-canonical if its y coordinate isn't reduced
+/// modulo p, or if its x coordinate is zero and its sign bit is set. Such
+/// encodings are never produced by a correct signer; `ED25519` accepts public
+/// keys encoded this way, while `ED25519_STRICT` rejects them. (The `R`
+/// component of a signature with a non-canonical encoding is rejected by both,
+/// since `R` is compared with the canonical encoding of the expected point.)
+///
+/// Small-order public keys with canonical encodings are NOT rejected.
+///
+/// [Ed25519]: https://ed25519.cr.yp.to/
+pub static ED25519_STRICT: Ed25519StrictParameters = Ed25519StrictParameters { _private: () };
+
 impl signature::VerificationAlgorithm for EdDSAParameters {
     fn verify(
         &self,
@@ -41,39 +61,167 @@ impl signature::VerificationAlgorithm for EdDSAParameters {
         msg: untrusted::Input,
         signature: untrusted::Input,
     ) -> Result<(), error::Unspecified> {
-        let public_key: &[u8; ELEM_LEN] = public_key.as_slice_less_safe().try_into()?;
-        let (signature_r, signature_s) = signature.read_all(error::Unspecified, |input| {
-            let signature_r: &[u8; ELEM_LEN] = input
-                .read_bytes(ELEM_LEN)?
-                .as_slice_less_safe()
-                .try_into()?;
-            let signature_s: &[u8; SCALAR_LEN] = input
-                .read_bytes(SCALAR_LEN)?
-                .as_slice_less_safe()
-                .try_into()?;
-            Ok((signature_r, signature_s))
-        })?;
-
-        let signature_s = Scalar::from_bytes_checked(*signature_s)?;
-
-        let mut a = ExtPoint::from_encoded_point_vartime(public_key)?;
-        a.invert_vartime();
-
-        let h_digest = eddsa_digest(signature_r, public_key, msg.as_slice_less_safe());
-        let h = Scalar::from_sha512_digest_reduced(h_digest);
-
-        let mut r = Point::new_at_infinity();
-        unsafe { GFp_x25519_ge_double_scalarmult_vartime(&mut r, &h, &a, &signature_s) };
-        let r_check = r.into_encoded_point();
-        if *signature_r != r_check {
-            return Err(error::Unspecified);
-        }
-        Ok(())
+        verify_(false, None, public_key, msg, signature)
     }
 }
 
 impl sealed::Sealed for EdDSAParameters {}
 
+/// Parameters for strict Ed25519 verification.
+pub struct Ed25519StrictParameters {
+    _private: (),
+}
+
+impl core::fmt::Debug for Ed25519StrictParameters {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        write!(f, "ring::signature::ED25519_STRICT")
+    }
+}
+
+impl signature::VerificationAlgorithm for Ed25519StrictParameters {
+    fn verify(
+        &self,
+        public_key: untrusted::Input,
+        msg: untrusted::Input,
+        signature: untrusted::Input,
+    ) -> Result<(), error::Unspecified> {
+        verify_(true, None, public_key, msg, signature)
+    }
+}
+
+impl sealed::Sealed for Ed25519StrictParameters {}
+
+/// Parameters for Ed25519ctx signing and verification.
+pub struct Ed25519CtxParameters {
+    _private: (),
+}
+
+impl core::fmt::Debug for Ed25519CtxParameters {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        write!(f, "ring::signature::ED25519CTX")
+    }
+}
+
+/// Signing and verification of [Ed25519ctx] signatures.
+///
+/// Ed25519ctx is Ed25519 with a context string of up to 255 bytes that is
+/// bound into the signature using the `dom2` prefix, so that a signature
+/// made for one context never verifies for another. Its signatures don't
+/// verify as plain `ED25519` signatures, and vice versa.
+///
+/// RFC 8032 recommends against using an empty context.
+///
+/// [Ed25519ctx]: https://tools.ietf.org/html/rfc8032#section-5.1
+pub static ED25519CTX: Ed25519CtxParameters = Ed25519CtxParameters { _private: () };
+
+impl Ed25519CtxParameters {
+    /// Returns the signature of the message `msg` under `context`.
+    ///
+    /// Fails if `context` is longer than 255 bytes.
+    pub fn sign(
+        &self,
+        key_pair: &Ed25519KeyPair,
+        context: &[u8],
+        msg: &[u8],
+    ) -> Result<signature::Signature, error::Unspecified> {
+        let dom2 = Dom2::new(context)?;
+        Ok(key_pair.sign_(Some(&dom2), msg))
+    }
+
+    /// Verifies the signature `signature` of message `msg` under `context`
+    /// with the public key `public_key`.
+    ///
+    /// Fails if `context` is longer than 255 bytes.
+    pub fn verify(
+        &self,
+        public_key: &[u8],
+        context: &[u8],
+        msg: &[u8],
+        signature: &[u8],
+    ) -> Result<(), error::Unspecified> {
+        let dom2 = Dom2::new(context)?;
+        verify_(
+            false,
+            Some(&dom2),
+            untrusted::Input::from(public_key),
+            untrusted::Input::from(msg),
+            untrusted::Input::from(signature),
+        )
+    }
+}
+
+fn verify_(
+    strict: bool,
+    dom2: Option<&Dom2>,
+    public_key: untrusted::Input,
+    msg: untrusted::Input,
+    signature: untrusted::Input,
+) -> Result<(), error::Unspecified> {
+    let public_key: &[u8; ELEM_LEN] = public_key.as_slice_less_safe().try_into()?;
+    let (signature_r, signature_s) = signature.read_all(error::Unspecified, |input| {
+        let signature_r: &[u8; ELEM_LEN] = input
+            .read_bytes(ELEM_LEN)?
+            .as_slice_less_safe()
+            .try_into()?;
+        let signature_s: &[u8; SCALAR_LEN] = input
+            .read_bytes(SCALAR_LEN)?
+            .as_slice_less_safe()
+            .try_into()?;
+        Ok((signature_r, signature_s))
+    })?;
+
+    if strict && (!is_canonical_encoding(public_key) || !is_canonical_encoding(signature_r)) {
+        return Err(error::Unspecified);
+    }
+
+    let signature_s = Scalar::from_bytes_checked(*signature_s)?;
+
+    let mut a = ExtPoint::from_encoded_point_vartime(public_key)?;
+    a.invert_vartime();
+
+    let h_digest = eddsa_digest(dom2, signature_r, public_key, msg.as_slice_less_safe());
+    let h = Scalar::from_sha512_digest_reduced(h_digest);
+
+    let mut r = Point::new_at_infinity();
+    unsafe { GFp_x25519_ge_double_scalarmult_vartime(&mut r, &h, &a, &signature_s) };
+    let r_check = r.into_encoded_point();
+    if *signature_r != r_check {
+        return Err(error::Unspecified);
+    }
+    Ok(())
+}
+
+/// Returns `true` if `encoded` is the canonical encoding of the point it
+/// represents, if any. `encoded` is public so this doesn't need to be
+/// constant-time.
+fn is_canonical_encoding(encoded: &EncodedPoint) -> bool {
+    let sign = encoded[ELEM_LEN - 1] >> 7;
+    let (y_low, y_high) = encoded.split_at(1);
+    let (y_middle, y_top) = y_high.split_at(ELEM_LEN - 2);
+    let y_low = y_low[0];
+    let y_top = y_top[0] & 0x7f;
+
+    // The y coordinate must be less than p = 2**255 - 19, which is encoded as
+    // ed ff ff ... ff 7f.
+    let middle_all_ones = y_middle.iter().all(|&b| b == 0xff);
+    if y_top == 0x7f && middle_all_ones && y_low >= 0xed {
+        return false;
+    }
+
+    // x is zero if and only if y is 1 or -1, in which case there is no
+    // negative x and so the sign bit must be clear.
+    if sign == 1 {
+        let middle_all_zeros = y_middle.iter().all(|&b| b == 0);
+        let y_is_one = y_top == 0 && middle_all_zeros && y_low == 1;
+        let y_is_minus_one = y_top == 0x7f && middle_all_ones && y_low == 0xec;
+        if y_is_one || y_is_minus_one {
+            return false;
+        }
+    }
+
+    true
+}
+
 extern "C" {
     fn GFp_x25519_ge_double_scalarmult_vartime(
         r: &mut Point,
@@ -15,7 +15,7 @@
 //! X25519 Key agreement.
 
 use super::{ops, scalar::SCALAR_LEN};
-use crate::{agreement, constant_time, cpu, ec, error, rand};
+use crate::{agreement, constant_time, cpu, ec, error, io::der, pkcs8, rand};
 use core::convert::TryInto;
 
 static CURVE25519: ec::Curve = ec::Curve {
@@ -38,6 +38,42 @@ static CURVE25519: ec::Curve = ec::Curve {
 pub static X25519: agreement::Algorithm = agreement::Algorithm {
     curve: &CURVE25519,
     ecdh: x25519_ecdh,
+    private_key_from_pkcs8: x25519_private_key_from_pkcs8,
+};
+
+// The format is specified in [RFC 8410]. Both v1 and v2 documents are
+// accepted; if the public key is present then it must match the private key.
+//
+// [RFC 8410]: https://tools.ietf.org/html/rfc8410
+fn x25519_private_key_from_pkcs8(
+    input: untrusted::Input,
+    cpu_features: cpu::Features,
+) -> Result<ec::Seed, error::KeyRejected> {
+    let (private_key, public_key) =
+        pkcs8::unwrap_key(&PKCS8_TEMPLATE, pkcs8::Version::V1OrV2, input)?;
+    let private_key = private_key
+        .read_all(error::Unspecified, |input| {
+            der::expect_tag_and_get_value(input, der::Tag::OctetString)
+        })
+        .map_err(|error::Unspecified| error::KeyRejected::invalid_encoding())?;
+    let seed = ec::Seed::from_bytes(&CURVE25519, private_key, cpu_features)
+        .map_err(|error::Unspecified| error::KeyRejected::invalid_component())?;
+    if let Some(public_key) = public_key {
+        let computed = seed
+            .compute_public_key()
+            .map_err(|error::Unspecified| error::KeyRejected::unexpected_error())?;
+        if public_key != *computed.as_ref() {
+            return Err(error::KeyRejected::inconsistent_components());
+        }
+    }
+    Ok(seed)
+}
+
+static PKCS8_TEMPLATE: pkcs8::Template = pkcs8::Template {
+    bytes: include_bytes!("x25519_pkcs8_v2_template.der"),
+    alg_id_range: core::ops::Range { start: 7, end: 12 },
+    curve_id_index: 0,
+    private_key_index: 0x10,
 };
 
 fn x25519_check_private_key_bytes(bytes: &[u8]) -> Result<(), error::Unspecified> {
@@ -135,8 +171,7 @@ fn x25519_ecdh(
         cpu_features,
     );
 
-    let zeros: SharedSecret = [0; SHARED_SECRET_LEN];
-    if constant_time::verify_slices_are_equal(out, &zeros).is_ok() {
+    if constant_time::is_zero(out) {
         // All-zero output results when the input is a point of small order.
         return Err(error::Unspecified);
     }
@@ -164,7 +199,3 @@ const PRIVATE_KEY_LEN: usize = ELEM_AND_SCALAR_LEN;
 // An X25519 public key as an encoded Curve25519 point.
 type PublicKey = [u8; PUBLIC_KEY_LEN];
 const PUBLIC_KEY_LEN: usize = ELEM_AND_SCALAR_LEN;
-
-// An X25519 shared secret as an encoded Curve25519 point.
-type SharedSecret = [u8; SHARED_SECRET_LEN];
-const SHARED_SECRET_LEN: usize = ELEM_AND_SCALAR_LEN;
@@ -15,7 +15,13 @@
 //! Elliptic curve operations on P-256 & P-384.
 
 use self::ops::*;
-use crate::{arithmetic::montgomery::*, cpu, ec, error, io::der, limb::LimbMask, pkcs8};
+use crate::{
+    arithmetic::montgomery::*,
+    cpu, ec, error,
+    io::der,
+    limb::{self, LimbMask},
+    pkcs8,
+};
 
 // NIST SP 800-56A Step 3: "If q is an odd prime p, verify that
 // yQ**2 = xQ**3 + axQ + b in GF(p), where the arithmetic is performed modulo
@@ -227,6 +233,28 @@ pub(crate) fn key_pair_from_bytes(
     Ok(r)
 }
 
+/// Reduces `bytes`, the big-endian encoding of a value that may be up to
+/// twice as long as a scalar, modulo the group order of `curve`, writing the
+/// fixed-length big-endian encoding of the result to `out`.
+pub(crate) fn reduce_wide_scalar(
+    curve: &'static ec::Curve,
+    bytes: untrusted::Input,
+    out: &mut [u8],
+) -> Result<(), error::Unspecified> {
+    let ops = match curve.id {
+        ec::CurveID::P256 => &p256::PRIVATE_SCALAR_OPS,
+        ec::CurveID::P384 => &p384::PRIVATE_SCALAR_OPS,
+        ec::CurveID::Secp256k1 => &secp256k1::PRIVATE_SCALAR_OPS,
+        ec::CurveID::Curve25519 => {
+            return Err(error::Unspecified);
+        }
+    };
+    let num_limbs = ops.scalar_ops.common.num_limbs;
+    let scalar = scalar_reduce_wide_big_endian_consttime(ops, bytes)?;
+    limb::big_endian_from_limbs(&scalar.limbs[..num_limbs], out);
+    Ok(())
+}
+
 pub mod curve;
 pub mod ecdh;
 pub mod ecdsa;
@@ -81,3 +81,13 @@ suite_b_curve!(
     p384_generate_private_key,
     p384_public_from_private
 );
+
+suite_b_curve!(
+    SECP256K1,
+    256,
+    &ec::suite_b::ops::secp256k1::PRIVATE_KEY_OPS,
+    ec::CurveID::Secp256k1,
+    secp256k1_check_private_key_bytes,
+    secp256k1_generate_private_key,
+    secp256k1_public_from_private
+);
@@ -14,13 +14,14 @@
 
 //! ECDH key agreement using the P-256 and P-384 curves.
 
-use super::{ops::*, private_key::*, public_key::*};
-use crate::{agreement, ec, error};
+use super::{ecdsa, ops::*, private_key::*, public_key::*};
+use crate::{agreement, cpu, ec, error, pkcs8};
 
 /// A key agreement algorithm.
 macro_rules! ecdh {
     ( $NAME:ident, $curve:expr, $name_str:expr, $private_key_ops:expr,
-      $public_key_ops:expr, $ecdh:ident ) => {
+      $public_key_ops:expr, $pkcs8_template:expr, $ecdh:ident,
+      $private_key_from_pkcs8:ident ) => {
         #[doc = "ECDH using the NSA Suite B"]
         #[doc=$name_str]
         #[doc = "curve."]
@@ -41,6 +42,7 @@ macro_rules! ecdh {
         pub static $NAME: agreement::Algorithm = agreement::Algorithm {
             curve: $curve,
             ecdh: $ecdh,
+            private_key_from_pkcs8: $private_key_from_pkcs8,
         };
 
         fn $ecdh(
@@ -56,6 +58,13 @@ macro_rules! ecdh {
                 peer_public_key,
             )
         }
+
+        fn $private_key_from_pkcs8(
+            input: untrusted::Input,
+            cpu_features: cpu::Features,
+        ) -> Result<ec::Seed, error::KeyRejected> {
+            private_key_from_pkcs8($curve, $pkcs8_template, input, cpu_features)
+        }
     };
 }
 
@@ -65,7 +74,9 @@ ecdh!(
     "P-256 (secp256r1)",
     &p256::PRIVATE_KEY_OPS,
     &p256::PUBLIC_KEY_OPS,
-    p256_ecdh
+    &ecdsa::signing::EC_PUBLIC_KEY_P256_PKCS8_V1_TEMPLATE,
+    p256_ecdh,
+    p256_private_key_from_pkcs8
 );
 
 ecdh!(
@@ -74,9 +85,24 @@ ecdh!(
     "P-384 (secp384r1)",
     &p384::PRIVATE_KEY_OPS,
     &p384::PUBLIC_KEY_OPS,
-    p384_ecdh
+    &ecdsa::signing::EC_PUBLIC_KEY_P384_PKCS8_V1_TEMPLATE,
+    p384_ecdh,
+    p384_private_key_from_pkcs8
 );
 
+// The format is the same one used for ECDSA keys; see
+// `ecdsa::signing::EcdsaKeyPair::from_pkcs8()`.
+fn private_key_from_pkcs8(
+    curve: &'static ec::Curve,
+    template: &pkcs8::Template,
+    input: untrusted::Input,
+    cpu_features: cpu::Features,
+) -> Result<ec::Seed, error::KeyRejected> {
+    let key_pair = ec::suite_b::key_pair_from_pkcs8(curve, template, input, cpu_features)?;
+    let (seed, _) = key_pair.split();
+    Ok(seed)
+}
+
 fn ecdh(
     private_key_ops: &PrivateKeyOps,
     public_key_ops: &PublicKeyOps,
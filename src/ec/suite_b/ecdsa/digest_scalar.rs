@@ -50,7 +50,8 @@ pub fn digest_scalar(ops: &ScalarOps, msg: digest::Digest) -> Scalar {
     digest_scalar_(ops, msg.as_ref())
 }
 
-#[cfg(test)]
+/// Like `digest_scalar`, but for a digest that was computed elsewhere, e.g.
+/// by the caller of `recover_public_key`. `digest` must not be empty.
 pub(crate) fn digest_bytes_scalar(ops: &ScalarOps, digest: &[u8]) -> Scalar {
     digest_scalar_(ops, digest)
 }
@@ -34,6 +34,7 @@ pub struct EcdsaSigningAlgorithm {
     digest_alg: &'static digest::Algorithm,
     pkcs8_template: &'static pkcs8::Template,
     format_rs: fn(ops: &'static ScalarOps, r: &Scalar, s: &Scalar, out: &mut [u8]) -> usize,
+    normalize_s: bool,
     id: AlgorithmID,
 }
 
@@ -43,6 +44,8 @@ enum AlgorithmID {
     ECDSA_P384_SHA384_FIXED_SIGNING,
     ECDSA_P256_SHA256_ASN1_SIGNING,
     ECDSA_P384_SHA384_ASN1_SIGNING,
+    ECDSA_SECP256K1_SHA256_FIXED_SIGNING,
+    ECDSA_SECP256K1_SHA256_ASN1_SIGNING,
 }
 
 derive_debug_via_id!(EcdsaSigningAlgorithm);
@@ -116,6 +119,39 @@ impl EcdsaKeyPair {
         Self::new(alg, key_pair, &rng)
     }
 
+    /// Constructs an ECDSA key pair by parsing a PEM-encoded unencrypted
+    /// PKCS#8 key, i.e. a `PRIVATE KEY` block.
+    ///
+    /// The first PEM block in `pem` is decoded with `pem::parse()` and then
+    /// parsed like `from_pkcs8()`.
+    #[cfg(feature = "alloc")]
+    pub fn from_pkcs8_pem(
+        alg: &'static EcdsaSigningAlgorithm,
+        pem: &str,
+    ) -> Result<Self, error::KeyRejected> {
+        let pkcs8 = crate::pem::parse_pkcs8(pem)?;
+        Self::from_pkcs8(alg, &pkcs8)
+    }
+
+    /// Constructs an ECDSA key pair by parsing a password-protected PKCS#8
+    /// `EncryptedPrivateKeyInfo`.
+    ///
+    /// The key is decrypted with `pkcs8::decrypt()`, which documents the
+    /// supported encryption algorithms, and then parsed like `from_pkcs8()`.
+    /// Every failure, including a failure to parse the decrypted key, is
+    /// reported as the same error, so that the result doesn't reveal anything
+    /// about the decrypted bytes when the password is wrong.
+    #[cfg(feature = "alloc")]
+    pub fn from_pkcs8_encrypted(
+        alg: &'static EcdsaSigningAlgorithm,
+        encrypted: &[u8],
+        password: &[u8],
+    ) -> Result<Self, error::KeyRejected> {
+        let pkcs8 = pkcs8::decrypt(encrypted, password)
+            .map_err(|error::Unspecified| error::KeyRejected::decryption_failed())?;
+        Self::from_pkcs8(alg, &pkcs8).map_err(|_| error::KeyRejected::decryption_failed())
+    }
+
     /// Constructs an ECDSA key pair from the private key and public key bytes
     ///
     /// The private key must encoded as a big-endian fixed-length integer. For
@@ -272,6 +308,14 @@ impl EcdsaKeyPair {
                 continue;
             }
 
+            // Replace `s` with `n - s` when `s` is in the upper half of the
+            // range, as some protocols require. This is a public value.
+            let s = if self.alg.normalize_s && scalar_is_high_vartime(cops, &s) {
+                scalar_negated(cops, &s)
+            } else {
+                s
+            };
+
             // Step 7 with encoding.
             return Ok(signature::Signature::new(|sig_bytes| {
                 (self.alg.format_rs)(scalar_ops, &r, &s, sig_bytes)
@@ -371,7 +415,7 @@ impl signature::KeyPair for EcdsaKeyPair {
 }
 
 #[derive(Clone, Copy)]
-pub struct PublicKey(ec::PublicKey);
+pub struct PublicKey(pub(super) ec::PublicKey);
 
 derive_debug_self_as_ref_hex_bytes!(PublicKey);
 
@@ -381,7 +425,12 @@ impl AsRef<[u8]> for PublicKey {
     }
 }
 
-fn format_rs_fixed(ops: &'static ScalarOps, r: &Scalar, s: &Scalar, out: &mut [u8]) -> usize {
+pub(super) fn format_rs_fixed(
+    ops: &'static ScalarOps,
+    r: &Scalar,
+    s: &Scalar,
+    out: &mut [u8],
+) -> usize {
     let scalar_len = ops.scalar_bytes_len();
 
     let (r_out, rest) = out.split_at_mut(scalar_len);
@@ -393,7 +442,12 @@ fn format_rs_fixed(ops: &'static ScalarOps, r: &Scalar, s: &Scalar, out: &mut [u
     2 * scalar_len
 }
 
-fn format_rs_asn1(ops: &'static ScalarOps, r: &Scalar, s: &Scalar, out: &mut [u8]) -> usize {
+pub(super) fn format_rs_asn1(
+    ops: &'static ScalarOps,
+    r: &Scalar,
+    s: &Scalar,
+    out: &mut [u8],
+) -> usize {
     // This assumes `a` is not zero since neither `r` or `s` is allowed to be
     // zero.
     fn format_integer_tlv(ops: &ScalarOps, a: &Scalar, out: &mut [u8]) -> usize {
@@ -451,6 +505,7 @@ pub static ECDSA_P256_SHA256_FIXED_SIGNING: EcdsaSigningAlgorithm = EcdsaSigning
     digest_alg: &digest::SHA256,
     pkcs8_template: &EC_PUBLIC_KEY_P256_PKCS8_V1_TEMPLATE,
     format_rs: format_rs_fixed,
+    normalize_s: false,
     id: AlgorithmID::ECDSA_P256_SHA256_FIXED_SIGNING,
 };
 
@@ -466,6 +521,7 @@ pub static ECDSA_P384_SHA384_FIXED_SIGNING: EcdsaSigningAlgorithm = EcdsaSigning
     digest_alg: &digest::SHA384,
     pkcs8_template: &EC_PUBLIC_KEY_P384_PKCS8_V1_TEMPLATE,
     format_rs: format_rs_fixed,
+    normalize_s: false,
     id: AlgorithmID::ECDSA_P384_SHA384_FIXED_SIGNING,
 };
 
@@ -481,6 +537,7 @@ pub static ECDSA_P256_SHA256_ASN1_SIGNING: EcdsaSigningAlgorithm = EcdsaSigningA
     digest_alg: &digest::SHA256,
     pkcs8_template: &EC_PUBLIC_KEY_P256_PKCS8_V1_TEMPLATE,
     format_rs: format_rs_asn1,
+    normalize_s: false,
     id: AlgorithmID::ECDSA_P256_SHA256_ASN1_SIGNING,
 };
 
@@ -496,23 +553,67 @@ pub static ECDSA_P384_SHA384_ASN1_SIGNING: EcdsaSigningAlgorithm = EcdsaSigningA
     digest_alg: &digest::SHA384,
     pkcs8_template: &EC_PUBLIC_KEY_P384_PKCS8_V1_TEMPLATE,
     format_rs: format_rs_asn1,
+    normalize_s: false,
     id: AlgorithmID::ECDSA_P384_SHA384_ASN1_SIGNING,
 };
 
-static EC_PUBLIC_KEY_P256_PKCS8_V1_TEMPLATE: pkcs8::Template = pkcs8::Template {
+/// Signing of fixed-length (PKCS#11 style) ECDSA signatures using the
+/// secp256k1 curve and SHA-256.
+///
+/// The signatures are always normalized to the "low S" form, where `s` is at
+/// most half the order of the curve, as required by e.g. Bitcoin and
+/// Ethereum. See "`ECDSA_*_FIXED` Details" in `ring::signature`'s module-level
+/// documentation for more details.
+pub static ECDSA_SECP256K1_SHA256_FIXED_SIGNING: EcdsaSigningAlgorithm = EcdsaSigningAlgorithm {
+    curve: &ec::suite_b::curve::SECP256K1,
+    private_scalar_ops: &secp256k1::PRIVATE_SCALAR_OPS,
+    private_key_ops: &secp256k1::PRIVATE_KEY_OPS,
+    digest_alg: &digest::SHA256,
+    pkcs8_template: &EC_PUBLIC_KEY_SECP256K1_PKCS8_V1_TEMPLATE,
+    format_rs: format_rs_fixed,
+    normalize_s: true,
+    id: AlgorithmID::ECDSA_SECP256K1_SHA256_FIXED_SIGNING,
+};
+
+/// Signing of ASN.1 DER-encoded ECDSA signatures using the secp256k1 curve
+/// and SHA-256.
+///
+/// The signatures are always normalized to the "low S" form, where `s` is at
+/// most half the order of the curve, as required by e.g. Bitcoin and
+/// Ethereum. See "`ECDSA_*_ASN1` Details" in `ring::signature`'s module-level
+/// documentation for more details.
+pub static ECDSA_SECP256K1_SHA256_ASN1_SIGNING: EcdsaSigningAlgorithm = EcdsaSigningAlgorithm {
+    curve: &ec::suite_b::curve::SECP256K1,
+    private_scalar_ops: &secp256k1::PRIVATE_SCALAR_OPS,
+    private_key_ops: &secp256k1::PRIVATE_KEY_OPS,
+    digest_alg: &digest::SHA256,
+    pkcs8_template: &EC_PUBLIC_KEY_SECP256K1_PKCS8_V1_TEMPLATE,
+    format_rs: format_rs_asn1,
+    normalize_s: true,
+    id: AlgorithmID::ECDSA_SECP256K1_SHA256_ASN1_SIGNING,
+};
+
+pub(crate) static EC_PUBLIC_KEY_P256_PKCS8_V1_TEMPLATE: pkcs8::Template = pkcs8::Template {
     bytes: include_bytes!("ecPublicKey_p256_pkcs8_v1_template.der"),
     alg_id_range: core::ops::Range { start: 8, end: 27 },
     curve_id_index: 9,
     private_key_index: 0x24,
 };
 
-static EC_PUBLIC_KEY_P384_PKCS8_V1_TEMPLATE: pkcs8::Template = pkcs8::Template {
+pub(crate) static EC_PUBLIC_KEY_P384_PKCS8_V1_TEMPLATE: pkcs8::Template = pkcs8::Template {
     bytes: include_bytes!("ecPublicKey_p384_pkcs8_v1_template.der"),
     alg_id_range: core::ops::Range { start: 8, end: 24 },
     curve_id_index: 9,
     private_key_index: 0x23,
 };
 
+pub(crate) static EC_PUBLIC_KEY_SECP256K1_PKCS8_V1_TEMPLATE: pkcs8::Template = pkcs8::Template {
+    bytes: include_bytes!("ecPublicKey_secp256k1_pkcs8_v1_template.der"),
+    alg_id_range: core::ops::Range { start: 8, end: 24 },
+    curve_id_index: 9,
+    private_key_index: 0x21,
+};
+
 #[cfg(test)]
 mod tests {
     use crate::{signature, test};
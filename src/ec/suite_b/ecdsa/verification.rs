@@ -14,16 +14,25 @@
 
 //! ECDSA Signatures using the P-256 and P-384 curves.
 
-use super::digest_scalar::digest_scalar;
+use super::{
+    digest_scalar::{digest_bytes_scalar, digest_scalar},
+    signing,
+};
 use crate::{
     arithmetic::montgomery::*,
     digest,
-    ec::suite_b::{ops::*, public_key::*, verify_jacobian_point_is_on_the_curve},
+    ec::{
+        self,
+        suite_b::{ops::*, private_key, public_key::*, verify_jacobian_point_is_on_the_curve},
+    },
     error,
     io::der,
     limb, sealed, signature,
 };
 
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
 /// An ECDSA verification algorithm.
 pub struct EcdsaVerificationAlgorithm {
     ops: &'static PublicScalarOps,
@@ -34,6 +43,8 @@ pub struct EcdsaVerificationAlgorithm {
             input: &mut untrusted::Reader<'a>,
         )
             -> Result<(untrusted::Input<'a>, untrusted::Input<'a>), error::Unspecified>,
+    format_rs: fn(ops: &'static ScalarOps, r: &Scalar, s: &Scalar, out: &mut [u8]) -> usize,
+    reject_high_s: bool,
     id: AlgorithmID,
 }
 
@@ -45,6 +56,10 @@ enum AlgorithmID {
     ECDSA_P384_SHA256_ASN1,
     ECDSA_P384_SHA384_ASN1,
     ECDSA_P384_SHA384_FIXED,
+    ECDSA_SECP256K1_SHA256_ASN1,
+    ECDSA_SECP256K1_SHA256_ASN1_LOW_S,
+    ECDSA_SECP256K1_SHA256_FIXED,
+    ECDSA_SECP256K1_SHA256_FIXED_LOW_S,
 }
 
 derive_debug_via_id!(EcdsaVerificationAlgorithm);
@@ -71,6 +86,116 @@ impl signature::VerificationAlgorithm for EcdsaVerificationAlgorithm {
 }
 
 impl EcdsaVerificationAlgorithm {
+    /// Recovers the public key that produced `signature` over the message
+    /// whose hash is `msg_hash`.
+    ///
+    /// `msg_hash` is used as-is, so it may be computed with any hash
+    /// function, not only this algorithm's; if it is longer than the curve's
+    /// order, only its leftmost bits are used, as in signing. `signature`
+    /// must be in this algorithm's signature format. The
+    /// `recovery_id` is in the range 0 to 3: its low bit is the parity of the
+    /// Y coordinate of the signature's ephemeral point, and its second bit
+    /// indicates that the X coordinate of that point is `r + n` instead of
+    /// `r`. The result is the same type as the public key of an
+    /// `EcdsaKeyPair`, in uncompressed form.
+    ///
+    /// Fails if `recovery_id` is out of range, if `msg_hash` is empty, if the
+    /// signature is malformed, or if no point on the curve corresponds to
+    /// `recovery_id`. Different
+    /// recovery IDs may yield different valid public keys, so the caller
+    /// must already know the right one or must otherwise authenticate the
+    /// recovered key.
+    pub fn recover_public_key(
+        &self,
+        msg_hash: &[u8],
+        signature: &[u8],
+        recovery_id: u8,
+    ) -> Result<signing::PublicKey, error::Unspecified> {
+        // SEC 1 Version 2.0 Section 4.1.6.
+        if recovery_id > 3 || msg_hash.is_empty() {
+            return Err(error::Unspecified);
+        }
+
+        let public_key_ops = self.ops.public_key_ops;
+        let scalar_ops = self.ops.scalar_ops;
+        let cops = public_key_ops.common;
+
+        let (r, s) = untrusted::Input::from(signature).read_all(error::Unspecified, |input| {
+            (self.split_rs)(scalar_ops, input)
+        })?;
+        let r = scalar_parse_big_endian_variable(cops, limb::AllowZero::No, r)?;
+        let s = scalar_parse_big_endian_variable(cops, limb::AllowZero::No, s)?;
+
+        // Step 1.1 and 1.2: x = r + j*n, which must be less than q.
+        let mut x = self.ops.scalar_as_elem(&r);
+        if recovery_id & 2 != 0 {
+            if !self.ops.elem_less_than(&x, &self.ops.q_minus_n) {
+                return Err(error::Unspecified);
+            }
+            x = self.ops.elem_sum(&x, &cops.n);
+        }
+
+        // Step 1.3: Decompress the point R = (x, y).
+        let r_point = public_key_ops.point_from_x_vartime(&x, recovery_id & 1 != 0)?;
+
+        // Step 1.5: Compute e from the message hash.
+        let e = digest_bytes_scalar(scalar_ops, msg_hash);
+
+        // Step 1.6: Q = r**-1 * (s*R - e*G) = (-e * r**-1)*G + (s * r**-1)*R.
+        let r_inv = scalar_ops.scalar_inv_to_mont(&r);
+        let u1 = scalar_ops.scalar_product(&scalar_negated(cops, &e), &r_inv);
+        let u2 = scalar_ops.scalar_product(&s, &r_inv);
+        let product = twin_mul(self.ops.private_key_ops, &u1, &u2, &r_point);
+
+        // This also rejects the point at infinity, which
+        // `big_endian_affine_from_jacobian` would otherwise panic on.
+        let _ = verify_jacobian_point_is_on_the_curve(cops, &product)?;
+
+        let elem_bytes = cops.num_limbs * limb::LIMB_BYTES;
+        let public_key = ec::PublicKey::new(1 + (2 * elem_bytes), |out| {
+            out[0] = 4; // Uncompressed encoding.
+            let (x_out, y_out) = out[1..].split_at_mut(elem_bytes);
+            private_key::big_endian_affine_from_jacobian(
+                self.ops.private_key_ops,
+                Some(x_out),
+                Some(y_out),
+                &product,
+            )
+        })?;
+        Ok(signing::PublicKey(public_key))
+    }
+
+    /// Returns `signature`, which must be in this algorithm's signature
+    /// format, with `s` replaced by `n - s` if `s` is greater than half the
+    /// order of the curve. This is the "low S" form that some protocols
+    /// require. A signature that is already in that form is returned
+    /// unchanged, so normalizing twice is the same as normalizing once.
+    ///
+    /// Fails if `signature` is malformed, e.g. if it isn't valid DER for the
+    /// `ECDSA_*_ASN1` algorithms, or if `r` or `s` isn't in the range
+    /// [1, n - 1]. The signature isn't otherwise verified.
+    #[cfg(feature = "alloc")]
+    pub fn normalize_s(&self, signature: &[u8]) -> Result<Vec<u8>, error::Unspecified> {
+        let scalar_ops = self.ops.scalar_ops;
+        let cops = scalar_ops.common;
+
+        let (r, s) = untrusted::Input::from(signature).read_all(error::Unspecified, |input| {
+            (self.split_rs)(scalar_ops, input)
+        })?;
+        let r = scalar_parse_big_endian_variable(cops, limb::AllowZero::No, r)?;
+        let s = scalar_parse_big_endian_variable(cops, limb::AllowZero::No, s)?;
+
+        // `s` is public, so this needn't be constant-time.
+        let s = if scalar_is_high_vartime(cops, &s) {
+            scalar_negated(cops, &s)
+        } else {
+            s
+        };
+
+        let normalized = signature::Signature::new(|out| (self.format_rs)(scalar_ops, &r, &s, out));
+        Ok(normalized.as_ref().to_vec())
+    }
+
     /// This is intentionally not public.
     fn verify_digest(
         &self,
@@ -111,6 +236,12 @@ impl EcdsaVerificationAlgorithm {
         let r = scalar_parse_big_endian_variable(public_key_ops.common, limb::AllowZero::No, r)?;
         let s = scalar_parse_big_endian_variable(public_key_ops.common, limb::AllowZero::No, s)?;
 
+        // Reject malleated signatures where `s` has been replaced by `n - s`,
+        // for protocols that require the "low S" form.
+        if self.reject_high_s && scalar_is_high_vartime(public_key_ops.common, &s) {
+            return Err(error::Unspecified);
+        }
+
         // NSA Guide Step 4: "Compute w = s**−1 mod n, using the routine in
         // Appendix B.1."
         let w = scalar_ops.scalar_inv_to_mont(&s);
@@ -211,6 +342,8 @@ pub static ECDSA_P256_SHA256_FIXED: EcdsaVerificationAlgorithm = EcdsaVerificati
     ops: &p256::PUBLIC_SCALAR_OPS,
     digest_alg: &digest::SHA256,
     split_rs: split_rs_fixed,
+    format_rs: signing::format_rs_fixed,
+    reject_high_s: false,
     id: AlgorithmID::ECDSA_P256_SHA256_FIXED,
 };
 
@@ -223,6 +356,8 @@ pub static ECDSA_P384_SHA384_FIXED: EcdsaVerificationAlgorithm = EcdsaVerificati
     ops: &p384::PUBLIC_SCALAR_OPS,
     digest_alg: &digest::SHA384,
     split_rs: split_rs_fixed,
+    format_rs: signing::format_rs_fixed,
+    reject_high_s: false,
     id: AlgorithmID::ECDSA_P384_SHA384_FIXED,
 };
 
@@ -235,6 +370,8 @@ pub static ECDSA_P256_SHA256_ASN1: EcdsaVerificationAlgorithm = EcdsaVerificatio
     ops: &p256::PUBLIC_SCALAR_OPS,
     digest_alg: &digest::SHA256,
     split_rs: split_rs_asn1,
+    format_rs: signing::format_rs_asn1,
+    reject_high_s: false,
     id: AlgorithmID::ECDSA_P256_SHA256_ASN1,
 };
 
@@ -252,6 +389,8 @@ pub static ECDSA_P256_SHA384_ASN1: EcdsaVerificationAlgorithm = EcdsaVerificatio
     ops: &p256::PUBLIC_SCALAR_OPS,
     digest_alg: &digest::SHA384,
     split_rs: split_rs_asn1,
+    format_rs: signing::format_rs_asn1,
+    reject_high_s: false,
     id: AlgorithmID::ECDSA_P256_SHA384_ASN1,
 };
 
@@ -269,6 +408,8 @@ pub static ECDSA_P384_SHA256_ASN1: EcdsaVerificationAlgorithm = EcdsaVerificatio
     ops: &p384::PUBLIC_SCALAR_OPS,
     digest_alg: &digest::SHA256,
     split_rs: split_rs_asn1,
+    format_rs: signing::format_rs_asn1,
+    reject_high_s: false,
     id: AlgorithmID::ECDSA_P384_SHA256_ASN1,
 };
 
@@ -281,9 +422,75 @@ pub static ECDSA_P384_SHA384_ASN1: EcdsaVerificationAlgorithm = EcdsaVerificatio
     ops: &p384::PUBLIC_SCALAR_OPS,
     digest_alg: &digest::SHA384,
     split_rs: split_rs_asn1,
+    format_rs: signing::format_rs_asn1,
+    reject_high_s: false,
     id: AlgorithmID::ECDSA_P384_SHA384_ASN1,
 };
 
+/// Verification of fixed-length (PKCS#11 style) ECDSA signatures using the
+/// secp256k1 curve and SHA-256.
+///
+/// Signatures in the "high S" form are accepted; use
+/// `ECDSA_SECP256K1_SHA256_FIXED_LOW_S` to reject them. See "`ECDSA_*_FIXED`
+/// Details" in `ring::signature`'s module-level documentation for more
+/// details.
+pub static ECDSA_SECP256K1_SHA256_FIXED: EcdsaVerificationAlgorithm = EcdsaVerificationAlgorithm {
+    ops: &secp256k1::PUBLIC_SCALAR_OPS,
+    digest_alg: &digest::SHA256,
+    split_rs: split_rs_fixed,
+    format_rs: signing::format_rs_fixed,
+    reject_high_s: false,
+    id: AlgorithmID::ECDSA_SECP256K1_SHA256_FIXED,
+};
+
+/// Verification of fixed-length (PKCS#11 style) ECDSA signatures using the
+/// secp256k1 curve and SHA-256, rejecting signatures whose `s` is greater
+/// than half the order of the curve, as Bitcoin and Ethereum do.
+///
+/// See "`ECDSA_*_FIXED` Details" in `ring::signature`'s module-level
+/// documentation for more details.
+pub static ECDSA_SECP256K1_SHA256_FIXED_LOW_S: EcdsaVerificationAlgorithm =
+    EcdsaVerificationAlgorithm {
+        ops: &secp256k1::PUBLIC_SCALAR_OPS,
+        digest_alg: &digest::SHA256,
+        split_rs: split_rs_fixed,
+        format_rs: signing::format_rs_fixed,
+        reject_high_s: true,
+        id: AlgorithmID::ECDSA_SECP256K1_SHA256_FIXED_LOW_S,
+    };
+
+/// Verification of ASN.1 DER-encoded ECDSA signatures using the secp256k1
+/// curve and SHA-256.
+///
+/// Signatures in the "high S" form are accepted; use
+/// `ECDSA_SECP256K1_SHA256_ASN1_LOW_S` to reject them. See "`ECDSA_*_ASN1`
+/// Details" in `ring::signature`'s module-level documentation for more
+/// details.
+pub static ECDSA_SECP256K1_SHA256_ASN1: EcdsaVerificationAlgorithm = EcdsaVerificationAlgorithm {
+    ops: &secp256k1::PUBLIC_SCALAR_OPS,
+    digest_alg: &digest::SHA256,
+    split_rs: split_rs_asn1,
+    format_rs: signing::format_rs_asn1,
+    reject_high_s: false,
+    id: AlgorithmID::ECDSA_SECP256K1_SHA256_ASN1,
+};
+
+/// Verification of ASN.1 DER-encoded ECDSA signatures using the secp256k1
+/// curve and SHA-256, rejecting signatures whose `s` is greater than half the
+/// order of the curve, as Bitcoin and Ethereum do.
+///
+/// See "`ECDSA_*_ASN1` Details" in `ring::signature`'s module-level
+/// documentation for more details.
+pub static ECDSA_SECP256K1_SHA256_ASN1_LOW_S: EcdsaVerificationAlgorithm =
+    EcdsaVerificationAlgorithm {
+        ops: &secp256k1::PUBLIC_SCALAR_OPS,
+        digest_alg: &digest::SHA256,
+        split_rs: split_rs_asn1,
+        format_rs: signing::format_rs_asn1,
+        reject_high_s: true,
+        id: AlgorithmID::ECDSA_SECP256K1_SHA256_ASN1_LOW_S,
+    };
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -326,10 +533,7 @@ mod tests {
                     }
                 };
 
-                let digest = super::super::digest_scalar::digest_bytes_scalar(
-                    &alg.ops.scalar_ops,
-                    &digest[..],
-                );
+                let digest = digest_bytes_scalar(&alg.ops.scalar_ops, &digest[..]);
                 let actual_result = alg.verify_digest(
                     untrusted::Input::from(&public_key[..]),
                     digest,
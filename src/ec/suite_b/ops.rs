@@ -62,7 +62,7 @@ pub struct CommonOps {
     q: Modulus,
     pub n: Elem<Unencoded>,
 
-    pub a: Elem<R>, // Must be -3 mod q
+    pub a: Elem<R>, // Must be -3 mod q, except for secp256k1 where it is 0.
     pub b: Elem<R>,
 
     // In all cases, `r`, `a`, and `b` may all alias each other.
@@ -228,6 +228,97 @@ impl PublicKeyOps {
         }
         Ok(r)
     }
+
+    /// Returns the Montgomery-encoded affine coordinates of the point whose X
+    /// coordinate is `x` and whose Y coordinate is odd if and only if
+    /// `y_is_odd`. Fails if `x` isn't the X coordinate of any point on the
+    /// curve.
+    ///
+    /// This is not constant-time, so it must only be used with public values.
+    /// It relies on q ≡ 3 (mod 4), which is true for P-256, P-384, and
+    /// secp256k1, so that a square root can be computed as a single
+    /// exponentiation. Any curve added later must satisfy this too.
+    pub fn point_from_x_vartime(
+        &self,
+        x: &Elem<Unencoded>,
+        y_is_odd: bool,
+    ) -> Result<(Elem<R>, Elem<R>), error::Unspecified> {
+        let cops = self.common;
+        let num_limbs = cops.num_limbs;
+        let q = &cops.q.p[..num_limbs];
+        if !limbs_less_than_limbs_vartime(&x.limbs[..num_limbs], q) {
+            return Err(error::Unspecified);
+        }
+
+        let rr: Elem<RR> = Elem {
+            limbs: cops.q.rr,
+            m: PhantomData,
+            encoding: PhantomData,
+        };
+        let x = cops.elem_product(x, &rr);
+
+        // rhs = (x**2 + a)*x + b.
+        let mut rhs = cops.elem_squared(&x);
+        cops.elem_add(&mut rhs, &cops.a);
+        cops.elem_mul(&mut rhs, &x);
+        cops.elem_add(&mut rhs, &cops.b);
+
+        // y = rhs**((q + 1) / 4). Since q is odd and isn't all ones, adding
+        // one doesn't overflow.
+        let mut exponent = [0; MAX_LIMBS];
+        let mut carry = 1;
+        for (e, &q_limb) in exponent[..num_limbs].iter_mut().zip(q) {
+            let (sum, overflowed) = q_limb.overflowing_add(carry);
+            *e = sum;
+            carry = if overflowed { 1 } else { 0 };
+        }
+        debug_assert_eq!(carry, 0);
+        for i in 0..num_limbs {
+            let next = if i + 1 < num_limbs {
+                exponent[i + 1]
+            } else {
+                0
+            };
+            exponent[i] = (exponent[i] >> 2) | (next << (LIMB_BITS - 2));
+        }
+        let mut y = cops.elem_product(&ONE, &rr);
+        for &limb in exponent[..num_limbs].iter().rev() {
+            for bit in (0..LIMB_BITS).rev() {
+                cops.elem_square(&mut y);
+                if (limb >> bit) & 1 == 1 {
+                    cops.elem_mul(&mut y, &rhs);
+                }
+            }
+        }
+
+        // If `rhs` isn't a square then `x` isn't on the curve.
+        if cops.elems_are_equal(&cops.elem_squared(&y), &rhs) != LimbMask::True {
+            return Err(error::Unspecified);
+        }
+
+        // Negation commutes with the Montgomery encoding, so negate `y`
+        // directly when the parity of its unencoded value is wrong.
+        let y_unencoded = cops.elem_unencoded(&y);
+        if (y_unencoded.limbs[0] & 1 == 1) != y_is_odd {
+            if cops.is_zero(&y) {
+                return Err(error::Unspecified);
+            }
+            let zero: Elem<R> = Elem::zero();
+            let mut neg_y = Elem::zero();
+            unsafe {
+                LIMBS_sub_mod(
+                    neg_y.limbs.as_mut_ptr(),
+                    zero.limbs.as_ptr(),
+                    y.limbs.as_ptr(),
+                    q.as_ptr(),
+                    num_limbs,
+                )
+            }
+            y = neg_y;
+        }
+
+        Ok((x, y))
+    }
 }
 
 // Operations used by both ECDSA signing and ECDSA verification. In general
@@ -340,6 +431,67 @@ pub fn scalar_sum(ops: &CommonOps, a: &Scalar, b: &Scalar) -> Scalar {
     r
 }
 
+/// Returns -`a` (mod *n*).
+pub fn scalar_negated(ops: &CommonOps, a: &Scalar) -> Scalar {
+    let zero: Scalar = Scalar::zero();
+    let mut r = Scalar::zero();
+    unsafe {
+        LIMBS_sub_mod(
+            r.limbs.as_mut_ptr(),
+            zero.limbs.as_ptr(),
+            a.limbs.as_ptr(),
+            ops.n.limbs.as_ptr(),
+            ops.num_limbs,
+        )
+    }
+    r
+}
+
+/// Returns true if `a` is greater than *n*/2, i.e. if -`a` (mod *n*) is less
+/// than `a`. This is not constant-time.
+pub fn scalar_is_high_vartime(ops: &CommonOps, a: &Scalar) -> bool {
+    let num_limbs = ops.num_limbs;
+    let neg_a = scalar_negated(ops, a);
+    limbs_less_than_limbs_vartime(&neg_a.limbs[..num_limbs], &a.limbs[..num_limbs])
+}
+
+/// Reduces `bytes`, the big-endian encoding of a value that may be up to
+/// twice as long as a scalar, modulo *n*. This is constant-time with respect
+/// to the value but not with respect to its length.
+pub fn scalar_reduce_wide_big_endian_consttime(
+    ops: &PrivateScalarOps,
+    bytes: untrusted::Input,
+) -> Result<Scalar, error::Unspecified> {
+    let cops = ops.scalar_ops.common;
+    let num_limbs = cops.num_limbs;
+    let n = &cops.n.limbs[..num_limbs];
+
+    let mut wide = [0; 2 * MAX_LIMBS];
+    parse_big_endian_and_pad_consttime(bytes, &mut wide[..(2 * num_limbs)])?;
+    let (lo_limbs, hi_limbs) = wide[..(2 * num_limbs)].split_at(num_limbs);
+
+    // Both halves are less than R, and R < 2n for P-256, P-384, and secp256k1,
+    // so a single conditional subtraction fully reduces each of them. Any
+    // curve added later must satisfy this too.
+    let mut lo = Scalar::zero();
+    lo.limbs[..num_limbs].copy_from_slice(lo_limbs);
+    limbs_reduce_once_constant_time(&mut lo.limbs[..num_limbs], n);
+    let mut hi: Scalar = Scalar::zero();
+    hi.limbs[..num_limbs].copy_from_slice(hi_limbs);
+    limbs_reduce_once_constant_time(&mut hi.limbs[..num_limbs], n);
+
+    // hi * RR / R == hi * R (mod n). The result is the value hi * R itself,
+    // not the Montgomery encoding of `hi`.
+    let hi_times_r: Scalar<R> = ops.scalar_ops.scalar_product(&hi, &ops.oneRR_mod_n);
+    let hi_times_r = Scalar {
+        limbs: hi_times_r.limbs,
+        m: PhantomData,
+        encoding: PhantomData, // Unencoded
+    };
+
+    Ok(scalar_sum(cops, &hi_times_r, &lo))
+}
+
 // Returns (`a` squared `squarings` times) * `b`.
 fn elem_sqr_mul(ops: &CommonOps, a: &Elem<R>, squarings: usize, b: &Elem<R>) -> Elem<R> {
     debug_assert!(squarings >= 1);
@@ -433,6 +585,13 @@ extern "C" {
         m: *const Limb,
         num_limbs: c::size_t,
     );
+    fn LIMBS_sub_mod(
+        r: *mut Limb,
+        a: *const Limb,
+        b: *const Limb,
+        m: *const Limb,
+        num_limbs: c::size_t,
+    );
 }
 
 #[cfg(test)]
@@ -1179,3 +1338,4 @@ mod tests {
 mod elem;
 pub mod p256;
 pub mod p384;
+pub mod secp256k1;
@@ -70,6 +70,14 @@ extern crate std;
 /// cause of a failure. Users of *ring* are encouraged to report such cases so
 /// that they can be addressed individually.
 ///
+/// In particular, `Unspecified` doesn't capture a backtrace, not even
+/// optionally: it is constructed by value throughout *ring* and by users of
+/// *ring*, and it is `Copy`, so giving it any contents under a Cargo feature
+/// would break crates that don't enable that feature. To find out which of
+/// several operations failed, map each result to a distinct error of your own
+/// at the call site, e.g. using `map_err`, and capture a backtrace there if
+/// needed.
+///
 /// [`std::error::Error`]: https://doc.rust-lang.org/std/error/trait.Error.html
 /// [“Error Handling” in the Rust Book]:
 ///     https://doc.rust-lang.org/book/first-edition/error-handling.html#the-from-trait
@@ -142,6 +150,9 @@ impl From<core::array::TryFromSliceError> for Unspecified {
 ///  * Wrong algorithm: The key is not valid for the algorithm in which it was
 ///    being used.
 ///
+///  * Decryption failed: An encrypted key couldn't be decrypted, e.g. because
+///    the password is wrong or the encryption algorithm isn't supported.
+///
 ///  * Unexpected errors: Report this as a bug.
 #[derive(Copy, Clone, Debug)]
 pub struct KeyRejected(&'static str);
@@ -174,6 +185,11 @@ impl KeyRejected {
         KeyRejected("PublicKeyIsMissing")
     }
 
+    #[cfg(feature = "alloc")]
+    pub(crate) fn decryption_failed() -> Self {
+        KeyRejected("DecryptionFailed")
+    }
+
     #[cfg(feature = "alloc")]
     pub(crate) fn too_small() -> Self {
         KeyRejected("TooSmall")
@@ -20,6 +20,15 @@
 
 use crate::{error, hmac};
 
+#[cfg(feature = "alloc")]
+use {alloc::vec::Vec, core::convert::TryFrom};
+
+#[cfg(feature = "std")]
+use crate::digest;
+
+#[cfg(feature = "std")]
+extern crate std;
+
 /// An HKDF algorithm.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub struct Algorithm(hmac::Algorithm);
@@ -45,6 +54,19 @@ pub static HKDF_SHA384: Algorithm = Algorithm(hmac::HMAC_SHA384);
 /// HKDF using HMAC-SHA-512.
 pub static HKDF_SHA512: Algorithm = Algorithm(hmac::HMAC_SHA512);
 
+/// HKDF using the given HMAC algorithm, e.g. `hmac::HMAC_SM3`.
+///
+/// Nothing in HKDF is specific to a particular digest algorithm, so HKDF can
+/// be used with any HMAC algorithm, not just the ones that have `HKDF_*`
+/// constants. The output length of HKDF-Expand is limited to 255 times the
+/// digest algorithm's output length.
+impl From<hmac::Algorithm> for Algorithm {
+    #[inline]
+    fn from(hmac_algorithm: hmac::Algorithm) -> Self {
+        Self(hmac_algorithm)
+    }
+}
+
 impl KeyType for Algorithm {
     fn len(&self) -> usize {
         self.0.digest_algorithm().output_len
@@ -113,11 +135,25 @@ impl Prk {
     ///
     /// Usually one can avoid using this. It is useful when the application
     /// intentionally wants to leak the PRK secret, e.g. to implement
-    /// `SSLKEYLOGFILE` functionality.
+    /// `SSLKEYLOGFILE` functionality, or when the PRK was derived or stored
+    /// elsewhere. To use the output of `expand()` as the PRK for the next step
+    /// of a chain of KDFs, e.g. in a ratchet, prefer `Prk::from(okm)`, which
+    /// doesn't expose the bytes at all.
+    ///
+    /// `value` must already be a uniformly random key of at least
+    /// `algorithm.hmac_algorithm().digest_algorithm().output_len` bytes, such
+    /// as the output of HKDF-Extract or HKDF-Expand. Skipping HKDF-Extract
+    /// for input keying material that isn't uniformly random, e.g. a
+    /// Diffie-Hellman shared secret, is insecure.
     pub fn new_less_safe(algorithm: Algorithm, value: &[u8]) -> Self {
         Self(hmac::Key::new(algorithm.hmac_algorithm(), value))
     }
 
+    /// Uses an existing HMAC key directly as the PRK.
+    pub(crate) fn from_hmac_key(key: hmac::Key) -> Self {
+        Self(key)
+    }
+
     /// The [HKDF-Expand] operation.
     ///
     /// [HKDF-Expand]: https://tools.ietf.org/html/rfc5869#section-2.3
@@ -185,6 +221,227 @@ impl<L: KeyType> Okm<'_, L> {
     }
 }
 
+/// Fills each buffer in `outs`, in order, with consecutive portions of a
+/// single HKDF-Expand output stream.
+///
+/// This is equivalent to expanding `prk` with `info` to the total of
+/// `lengths` and splitting the result, which is how e.g. TLS derives its key
+/// block (encryption keys, MAC keys, and IVs) from a single secret.
+///
+/// `lengths[i]` is the length of `outs[i]`; it is given explicitly to guard
+/// against passing buffers of the wrong size. Fails if `lengths` and `outs`
+/// don't match, or if the total length is too large (see `Okm::fill()`).
+pub fn expand_multi(
+    prk: &Prk,
+    info: &[&[u8]],
+    lengths: &[usize],
+    outs: &mut [&mut [u8]],
+) -> Result<(), error::Unspecified> {
+    if lengths.len() != outs.len() {
+        return Err(error::Unspecified);
+    }
+    let mut total_len: usize = 0;
+    for (len, out) in lengths.iter().zip(outs.iter()) {
+        if out.len() != *len {
+            return Err(error::Unspecified);
+        }
+        total_len = total_len.checked_add(*len).ok_or(error::Unspecified)?;
+    }
+    if total_len > 255 * prk.0.algorithm().digest_algorithm().output_len {
+        return Err(error::Unspecified);
+    }
+    expand_into(prk, info, outs, total_len);
+    Ok(())
+}
+
+/// Builds an HKDF `info` value out of fixed-width integers and
+/// length-prefixed byte strings.
+///
+/// Protocols like TLS 1.3 and HPKE don't pass their labels and contexts to
+/// HKDF-Expand directly, but as a structure in which each variable-length
+/// field is preceded by its big-endian length. `InfoBuilder` produces that
+/// encoding, e.g. the `HkdfLabel` of TLS 1.3's [HKDF-Expand-Label]:
+///
+/// ```
+/// use ring::{aead, hkdf};
+///
+/// # fn main() -> Result<(), ring::error::Unspecified> {
+/// # let secret = hkdf::Prk::new_less_safe(hkdf::HKDF_SHA256, &[0; 32]);
+/// let algorithm = &aead::AES_128_GCM;
+/// let info = hkdf::InfoBuilder::new()
+///     .push_u16(algorithm.key_len() as u16)
+///     .push_u8_len_prefixed(b"tls13 key")
+///     .push_u8_len_prefixed(b"")
+///     .build()?;
+/// assert_eq!(&info[..], &b"\x00\x10\x09tls13 key\x00"[..]);
+///
+/// let key: aead::UnboundKey = secret.expand(&[&info], algorithm)?.into();
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [HKDF-Expand-Label]: https://tools.ietf.org/html/rfc8446#section-7.1
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, Default)]
+pub struct InfoBuilder {
+    bytes: Vec<u8>,
+
+    /// Set when a length-prefixed value was too long for its prefix.
+    too_long: bool,
+}
+
+#[cfg(feature = "alloc")]
+impl InfoBuilder {
+    /// Constructs a new, empty, `InfoBuilder`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends `value` as-is.
+    pub fn push(mut self, value: &[u8]) -> Self {
+        self.bytes.extend_from_slice(value);
+        self
+    }
+
+    /// Appends `value` as a single byte.
+    pub fn push_u8(self, value: u8) -> Self {
+        self.push(&[value])
+    }
+
+    /// Appends `value` as two big-endian bytes.
+    pub fn push_u16(self, value: u16) -> Self {
+        self.push(&value.to_be_bytes())
+    }
+
+    /// Appends the length of `value` as a single byte, followed by `value`.
+    ///
+    /// If `value` is longer than 255 bytes then `build()` will fail.
+    pub fn push_u8_len_prefixed(mut self, value: &[u8]) -> Self {
+        match u8::try_from(value.len()) {
+            Ok(len) => self.push_u8(len).push(value),
+            Err(_) => {
+                self.too_long = true;
+                self
+            }
+        }
+    }
+
+    /// Appends the length of `value` as two big-endian bytes, followed by
+    /// `value`.
+    ///
+    /// If `value` is longer than 65535 bytes then `build()` will fail.
+    pub fn push_u16_len_prefixed(mut self, value: &[u8]) -> Self {
+        match u16::try_from(value.len()) {
+            Ok(len) => self.push_u16(len).push(value),
+            Err(_) => {
+                self.too_long = true;
+                self
+            }
+        }
+    }
+
+    /// Returns the encoded `info`.
+    ///
+    /// Fails if any length-prefixed value was too long for its length prefix.
+    pub fn build(self) -> Result<Vec<u8>, error::Unspecified> {
+        if self.too_long {
+            return Err(error::Unspecified);
+        }
+        Ok(self.bytes)
+    }
+}
+
+/// Lazily produces the output of HKDF-Expand as it is read.
+///
+/// Reading N bytes from an `ExpandReader` produces the same bytes as
+/// `prk.expand(info, N)`, so an arbitrarily long key stream, up to the
+/// HKDF-Expand limit of 255 times the size of the digest algorithm's output,
+/// can be consumed without computing it up front. Reads fail once that limit
+/// has been reached.
+#[cfg(feature = "std")]
+pub struct ExpandReader<'a> {
+    prk: &'a Prk,
+    info: &'a [&'a [u8]],
+
+    /// T(n - 1), or empty if `n` is 1.
+    t: [u8; digest::MAX_OUTPUT_LEN],
+    t_len: usize,
+
+    /// The number of bytes of `t` that have already been read.
+    t_read: usize,
+
+    /// The counter for the next block, or `None` once all 255 blocks have
+    /// been produced.
+    n: Option<u8>,
+}
+
+#[cfg(feature = "std")]
+impl<'a> ExpandReader<'a> {
+    /// Constructs a new `ExpandReader` that produces the HKDF-Expand output
+    /// for `prk` and `info`.
+    pub fn new(prk: &'a Prk, info: &'a [&'a [u8]]) -> Self {
+        Self {
+            prk,
+            info,
+            t: [0; digest::MAX_OUTPUT_LEN],
+            t_len: 0,
+            t_read: 0,
+            n: Some(1),
+        }
+    }
+
+    fn next_block(&mut self) -> Result<(), error::Unspecified> {
+        let n = self.n.ok_or(error::Unspecified)?;
+
+        let mut ctx = hmac::Context::with_key(&self.prk.0);
+        ctx.update(&self.t[..self.t_len]);
+        for info in self.info {
+            ctx.update(info);
+        }
+        ctx.update(&[n]);
+        let t = ctx.sign();
+        let t = t.as_ref();
+
+        self.t[..t.len()].copy_from_slice(t);
+        self.t_len = t.len();
+        self.t_read = 0;
+        self.n = n.checked_add(1);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::io::Read for ExpandReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let mut written = 0;
+        while written < buf.len() {
+            if self.t_read == self.t_len {
+                if let Err(e) = self.next_block() {
+                    if written > 0 {
+                        break;
+                    }
+                    return Err(std::io::Error::new(std::io::ErrorKind::Other, e));
+                }
+            }
+            let available = &self.t[self.t_read..self.t_len];
+            let len = core::cmp::min(available.len(), buf.len() - written);
+            buf[written..(written + len)].copy_from_slice(&available[..len]);
+            self.t_read += len;
+            written += len;
+        }
+        Ok(written)
+    }
+}
+
+#[cfg(feature = "std")]
+impl core::fmt::Debug for ExpandReader<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_struct("ExpandReader")
+            .field("prk", self.prk)
+            .finish()
+    }
+}
+
 fn fill_okm(
     prk: &Prk,
     info: &[&[u8]],
@@ -194,14 +451,22 @@ fn fill_okm(
     if out.len() != len {
         return Err(error::Unspecified);
     }
+    expand_into(prk, info, &mut [out], len);
+    Ok(())
+}
 
+// `total_len` must be the sum of the lengths of `outs`.
+fn expand_into(prk: &Prk, info: &[&[u8]], outs: &mut [&mut [u8]], total_len: usize) {
     let digest_alg = prk.0.algorithm().digest_algorithm();
     assert!(digest_alg.block_len >= digest_alg.output_len);
 
     let mut ctx = hmac::Context::with_key(&prk.0);
 
+    let mut remaining = total_len;
+    let mut outs = outs.iter_mut();
+    let mut out: &mut [u8] = &mut [];
+
     let mut n = 1u8;
-    let mut out = out;
     loop {
         for info in info {
             ctx.update(info);
@@ -211,19 +476,24 @@ fn fill_okm(
         let t = ctx.sign();
         let t = t.as_ref();
 
-        // Append `t` to the output.
-        out = if out.len() < digest_alg.output_len {
-            let len = out.len();
-            out.copy_from_slice(&t[..len]);
-            &mut []
-        } else {
-            let (this_chunk, rest) = out.split_at_mut(digest_alg.output_len);
-            this_chunk.copy_from_slice(t);
-            rest
-        };
-
-        if out.is_empty() {
-            return Ok(());
+        // Append `t` to the outputs, moving on to the next output whenever
+        // the current one is full.
+        let mut t_remaining = t;
+        while !t_remaining.is_empty() && remaining > 0 {
+            if out.is_empty() {
+                out = outs.next().unwrap();
+                continue;
+            }
+            let len = core::cmp::min(out.len(), t_remaining.len());
+            let (this_chunk, rest) = core::mem::replace(&mut out, &mut []).split_at_mut(len);
+            this_chunk.copy_from_slice(&t_remaining[..len]);
+            out = rest;
+            t_remaining = &t_remaining[len..];
+            remaining -= len;
+        }
+
+        if remaining == 0 {
+            return;
         }
 
         ctx = hmac::Context::with_key(&prk.0);
@@ -109,7 +109,11 @@
 //! [code for `ring::hkdf`]:
 //!     https://github.com/briansmith/ring/blob/main/src/hkdf.rs
 
-use crate::{constant_time, digest, error, hkdf, rand};
+use crate::{constant_time, digest, error, hkdf, pbkdf2, rand};
+use core::num::NonZeroU32;
+
+#[cfg(feature = "dangerous")]
+use alloc::vec::Vec;
 
 /// An HMAC algorithm.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -135,6 +139,9 @@ pub static HMAC_SHA384: Algorithm = Algorithm(&digest::SHA384);
 /// HMAC using SHA-512.
 pub static HMAC_SHA512: Algorithm = Algorithm(&digest::SHA512);
 
+/// HMAC using SM3.
+pub static HMAC_SM3: Algorithm = Algorithm(&digest::SM3);
+
 /// A deprecated alias for `Tag`.
 #[deprecated(note = "`Signature` was renamed to `Tag`. This alias will be removed soon.")]
 pub type Signature = Tag;
@@ -152,6 +159,23 @@ impl AsRef<[u8]> for Tag {
     }
 }
 
+/// An HMAC tag truncated to a prefix of its full length, as returned by
+/// `sign_truncated`.
+///
+/// For a given tag `t`, use `t.as_ref()` to get the tag value as a byte slice.
+#[derive(Clone, Copy, Debug)]
+pub struct TruncatedTag {
+    tag: Tag,
+    len: usize,
+}
+
+impl AsRef<[u8]> for TruncatedTag {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        &self.tag.as_ref()[..self.len]
+    }
+}
+
 /// A key to use for HMAC signing.
 #[derive(Clone)]
 pub struct Key {
@@ -192,6 +216,50 @@ impl Key {
         Self::construct(algorithm, |buf| rng.fill(buf))
     }
 
+    /// Like `generate()`, but also writes the key value to `key_bytes` so that
+    /// the key can be persisted and later reconstructed with `Key::new()`.
+    ///
+    /// `key_bytes` must be exactly `algorithm.digest_algorithm().output_len`
+    /// bytes long. The contents of `key_bytes` are secret and should be
+    /// protected accordingly.
+    pub fn generate_serializable(
+        algorithm: Algorithm,
+        rng: &dyn rand::SecureRandom,
+        key_bytes: &mut [u8],
+    ) -> Result<Self, error::Unspecified> {
+        if key_bytes.len() != algorithm.0.output_len {
+            return Err(error::Unspecified);
+        }
+        rng.fill(key_bytes)?;
+        Ok(Self::new(algorithm, key_bytes))
+    }
+
+    /// Derive an HMAC signing key from a password using PBKDF2 with the given
+    /// digest algorithm, iteration count, and salt.
+    ///
+    /// The key will be `digest_alg.output_len` bytes long, like the keys
+    /// produced by `generate()`. This is equivalent to filling a buffer of
+    /// that length using `pbkdf2::derive()` with the corresponding
+    /// `pbkdf2::Algorithm` and passing it to `Key::new()`. See the `pbkdf2`
+    /// module's documentation for advice on choosing `iterations` and `salt`.
+    pub fn from_password(
+        algorithm: Algorithm,
+        iterations: NonZeroU32,
+        salt: &[u8],
+        password: &[u8],
+    ) -> Self {
+        let mut key_bytes = [0; digest::MAX_OUTPUT_LEN];
+        let key_bytes = &mut key_bytes[..algorithm.0.output_len];
+        pbkdf2::derive(
+            pbkdf2::Algorithm(algorithm),
+            iterations,
+            salt,
+            password,
+            key_bytes,
+        );
+        Self::new(algorithm, key_bytes)
+    }
+
     fn construct<F>(algorithm: Algorithm, fill: F) -> Result<Self, error::Unspecified>
     where
         F: FnOnce(&mut [u8]) -> Result<(), error::Unspecified>,
@@ -206,8 +274,8 @@ impl Key {
     /// value.
     ///
     /// `key_value` should be a value generated using a secure random number
-    /// generator (e.g. the `key_value` output by
-    /// `SealingKey::generate_serializable()`) or derived from a random key by
+    /// generator (e.g. the `key_bytes` output by
+    /// `Key::generate_serializable()`) or derived from a random key by
     /// a key derivation function (e.g. `ring::hkdf`). In particular,
     /// `key_value` shouldn't be a password.
     ///
@@ -332,6 +400,37 @@ impl Context {
         pending[..num_pending].copy_from_slice(self.inner.finish().as_ref());
         Tag(self.outer.finish(pending, num_pending))
     }
+
+    /// Serializes the intermediate state of the calculation so that it can
+    /// be resumed later, e.g. in another process, with `restore_state`.
+    ///
+    /// The state includes the inner hash's chaining value, which is derived
+    /// from the key; it must be protected as carefully as the key itself.
+    /// The key itself isn't included, so the same key must be passed to
+    /// `restore_state`.
+    ///
+    /// Requires the `dangerous` feature.
+    #[cfg(feature = "dangerous")]
+    pub fn save_state(&self) -> Vec<u8> {
+        self.inner.save_state()
+    }
+
+    /// Reconstructs a context from the output of `save_state` and the key
+    /// that the saved context was constructed with. The restored context
+    /// produces the same tag as the saved one would have.
+    ///
+    /// Fails if `state` is malformed or was saved by a context for a
+    /// different algorithm. Passing a different key of the same algorithm
+    /// can't be detected and results in a wrong tag.
+    ///
+    /// Requires the `dangerous` feature.
+    #[cfg(feature = "dangerous")]
+    pub fn restore_state(key: &Key, state: &[u8]) -> Result<Self, error::Unspecified> {
+        Ok(Self {
+            inner: digest::Context::restore_state(key.inner.algorithm, state)?,
+            outer: key.outer.clone(),
+        })
+    }
 }
 
 /// Calculates the HMAC of `data` using the key `key` in one step.
@@ -357,6 +456,58 @@ pub fn verify(key: &Key, data: &[u8], tag: &[u8]) -> Result<(), error::Unspecifi
     constant_time::verify_slices_are_equal(sign(key, data).as_ref(), tag)
 }
 
+/// Calculates the HMAC of `data` using the key `key` and truncates it to its
+/// first `tag_len` bytes, as is done in e.g. IPsec's `AUTH_HMAC_SHA2_256_128`
+/// ([RFC 4868]).
+///
+/// Fails if `tag_len` is zero or larger than the output length of the digest
+/// algorithm. Choosing a safe `tag_len` is the caller's responsibility; see
+/// [RFC 2104 Section 5].
+///
+/// It is generally not safe to implement HMAC verification by comparing the
+/// return value of `sign_truncated` to a tag. Use `verify_truncated` for
+/// verification instead.
+///
+/// [RFC 4868]: https://tools.ietf.org/html/rfc4868
+/// [RFC 2104 Section 5]: https://tools.ietf.org/html/rfc2104#section-5
+pub fn sign_truncated(
+    key: &Key,
+    data: &[u8],
+    tag_len: usize,
+) -> Result<TruncatedTag, error::Unspecified> {
+    check_truncated_tag_len(key, tag_len)?;
+    Ok(TruncatedTag {
+        tag: sign(key, data),
+        len: tag_len,
+    })
+}
+
+/// Calculates the HMAC of `data` using the key `key`, truncates it to its
+/// first `tag_len` bytes, and verifies whether the resultant value equals
+/// `tag`, in one step.
+///
+/// Fails if `tag_len` is zero or larger than the output length of the digest
+/// algorithm, or if `tag` isn't exactly `tag_len` bytes long.
+///
+/// The comparison is done in constant time over exactly `tag_len` bytes to
+/// prevent timing attacks.
+pub fn verify_truncated(
+    key: &Key,
+    data: &[u8],
+    tag: &[u8],
+    tag_len: usize,
+) -> Result<(), error::Unspecified> {
+    let expected = sign_truncated(key, data, tag_len)?;
+    constant_time::verify_slices_are_equal(expected.as_ref(), tag)
+}
+
+fn check_truncated_tag_len(key: &Key, tag_len: usize) -> Result<(), error::Unspecified> {
+    if tag_len == 0 || tag_len > key.algorithm().digest_algorithm().output_len {
+        return Err(error::Unspecified);
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{hmac, rand};
@@ -30,9 +30,28 @@
 //!         <code>dev_urandom_fallback</code> feature is disabled, such
 //!         fallbacks will not occur. See the documentation for
 //!         <code>rand::SystemRandom</code> for more details.
+//! <tr><td><code>dangerous</code>
+//!     <td>Enable APIs that expose internal cryptographic state, such as
+//!         <code>hmac::Context::save_state</code>, or that defeat the
+//!         guarantees of a type, such as
+//!         <code>agreement::EphemeralPrivateKey::from_bytes</code>. The exposed
+//!         state is as sensitive as the key it was derived from. Implies
+//!         `alloc`.
 //! <tr><td><code>std</code>
 //!     <td>Enable features that use libstd, in particular
 //!         <code>std::error::Error</code> integration. Implies `alloc`.
+//! <tr><td><code>nonce_reuse_check</code>
+//!     <td>Makes sealing with an <code>aead::SealingKey</code> fail if its
+//!         <code>NonceSequence</code> produces the same nonce twice. This is a
+//!         development aid that uses memory for every nonce used; don't enable
+//!         it in production. Implies <code>std</code>.
+//! <tr><td><code>serde</code>
+//!     <td>Implements <code>serde::Serialize</code> and
+//!         <code>serde::Deserialize</code> for <code>digest::Digest</code>.
+//! <tr><td><code>flate2</code>
+//!     <td>Together with <code>std</code>, enables support for gzip-compressed
+//!         test vector files in <code>ring::test</code>. This is only useful
+//!         for testing.
 //! <tr><td><code>wasm32_c</code>
 //!     <td>Enables features that require a C compiler on wasm32 targets, such as
 //!        the <code>constant_time</code> module, HMAC verification, and PBKDF2
@@ -92,6 +111,7 @@ mod bssl;
 mod polyfill;
 
 pub mod aead;
+pub mod aes;
 pub mod agreement;
 
 mod bits;
@@ -109,7 +129,12 @@ pub mod error;
 pub mod hkdf;
 pub mod hmac;
 mod limb;
+pub mod otp;
 pub mod pbkdf2;
+
+#[cfg(feature = "alloc")]
+pub mod pem;
+
 pub mod pkcs8;
 pub mod rand;
 
@@ -0,0 +1,75 @@
+//! HMAC-based one-time passwords (HOTP) and time-based one-time passwords
+//! (TOTP).
+//!
+//! HOTP is specified in [RFC 4226] and TOTP is specified in [RFC 6238].
+//!
+//! # Examples
+//!
+//! ```
+//! use ring::{hmac, otp};
+//!
+//! let key = hmac::Key::new(hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY, b"12345678901234567890");
+//!
+//! assert_eq!(otp::hotp(&key, 0, 6)?, 755224);
+//! assert_eq!(otp::totp(&key, 59, 30, 0, 8)?, 94287082);
+//!
+//! # Ok::<(), ring::error::Unspecified>(())
+//! ```
+//!
+//! Note that the result is a number; when displaying it, pad it with leading
+//! zeros to `digits` digits, e.g. with `format!("{:06}", otp)`.
+//!
+//! [RFC 4226]: https://tools.ietf.org/html/rfc4226
+//! [RFC 6238]: https://tools.ietf.org/html/rfc6238
+
+use crate::{error, hmac};
+
+/// Calculates the HOTP value for `counter`, with `digits` decimal digits.
+///
+/// This is HMAC(`key`, `counter`) followed by the dynamic truncation of
+/// [RFC 4226 Section 5.3]. RFC 4226 requires `digits` to be at least 6;
+/// values of 6 to 8 are common.
+///
+/// Fails if `digits` is zero or greater than 9.
+///
+/// [RFC 4226 Section 5.3]: https://tools.ietf.org/html/rfc4226#section-5.3
+pub fn hotp(key: &hmac::Key, counter: u64, digits: u8) -> Result<u32, error::Unspecified> {
+    if !(1..=9).contains(&digits) {
+        return Err(error::Unspecified);
+    }
+
+    let tag = hmac::sign(key, &counter.to_be_bytes());
+    let tag = tag.as_ref();
+
+    // Dynamic truncation. Every supported HMAC algorithm's output is at least
+    // 20 bytes, so `offset + 4` is always in range.
+    let offset = usize::from(tag[tag.len() - 1] & 0xf);
+    let mut code = [0u8; 4];
+    code.copy_from_slice(&tag[offset..][..4]);
+    let code = u32::from_be_bytes(code) & 0x7fff_ffff;
+
+    Ok(code % 10u32.pow(u32::from(digits)))
+}
+
+/// Calculates the TOTP value for the Unix time `unix_time`, with `digits`
+/// decimal digits.
+///
+/// `step` is the time step and `t0` is the Unix time at which counting time
+/// steps starts, both in seconds; [RFC 6238] recommends a `step` of 30 and a
+/// `t0` of 0. The result is `hotp(key, (unix_time - t0) / step, digits)`.
+///
+/// Fails if `step` is zero, if `unix_time` is before `t0`, or if `digits` is
+/// zero or greater than 9.
+///
+/// [RFC 6238]: https://tools.ietf.org/html/rfc6238#section-4
+pub fn totp(
+    key: &hmac::Key,
+    unix_time: u64,
+    step: u64,
+    t0: u64,
+    digits: u8,
+) -> Result<u32, error::Unspecified> {
+    let elapsed = unix_time.checked_sub(t0).ok_or(error::Unspecified)?;
+    let counter = elapsed.checked_div(step).ok_or(error::Unspecified)?;
+    hotp(key, counter, digits)
+}
@@ -117,9 +117,13 @@ use core::num::NonZeroU32;
 
 /// A PBKDF2 algorithm.
 #[derive(Clone, Copy, PartialEq, Eq)]
-pub struct Algorithm(hmac::Algorithm);
+pub struct Algorithm(pub(crate) hmac::Algorithm);
 
 /// PBKDF2 using HMAC-SHA1.
+///
+/// This is provided only for compatibility with legacy systems, e.g. for
+/// verifying existing password hashes. New applications should use
+/// `PBKDF2_HMAC_SHA256` or stronger.
 pub static PBKDF2_HMAC_SHA1: Algorithm = Algorithm(hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY);
 
 /// PBKDF2 using HMAC-SHA256.
@@ -18,6 +18,15 @@
 
 use crate::{ec, error, io::der};
 
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+#[cfg(feature = "alloc")]
+mod aes_cbc;
+
+#[cfg(feature = "alloc")]
+mod pbes2;
+
 pub(crate) enum Version {
     V1Only,
     V1OrV2,
@@ -160,6 +169,26 @@ fn unwrap_key__<'a>(
     Ok((private_key, public_key))
 }
 
+/// Decrypts a DER-encoded PKCS#8 `EncryptedPrivateKeyInfo` with `password`,
+/// returning the DER-encoded unencrypted PKCS#8 `PrivateKeyInfo`.
+///
+/// Only PBES2 encryption with PBKDF2-HMAC-SHA256 and AES-256-CBC is
+/// supported, e.g. as produced by `openssl pkcs8 -topk8 -v2 aes-256-cbc
+/// -v2prf hmacWithSHA256`. The PBKDF2 iteration count is taken from the
+/// input; inputs with more than 10,000,000 iterations are rejected so that
+/// decrypting an untrusted input can't take arbitrarily long.
+///
+/// Fails if the input isn't a supported `EncryptedPrivateKeyInfo` or if the
+/// decrypted key's padding is invalid, which usually means that `password` is
+/// wrong. All failures are reported the same way. A wrong password isn't
+/// always detected, so the result must still be parsed with one of the
+/// `from_pkcs8()` functions. See also the `from_pkcs8_encrypted()` functions,
+/// which do both.
+#[cfg(feature = "alloc")]
+pub fn decrypt(encrypted: &[u8], password: &[u8]) -> Result<Vec<u8>, error::Unspecified> {
+    pbes2::decrypt(untrusted::Input::from(encrypted), password)
+}
+
 /// A generated PKCS#8 document.
 pub struct Document {
     bytes: [u8; ec::PKCS8_DOCUMENT_MAX_LEN],
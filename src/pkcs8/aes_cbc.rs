@@ -0,0 +1,181 @@
+//! AES-256 decryption in CBC mode, for decrypting encrypted PKCS#8 keys.
+//!
+//! This uses the portable bitsliced implementation of the AES inverse cipher,
+//! which is slow, but it is only used to decrypt a private key once, after an
+//! expensive PBKDF2 derivation.
+
+use crate::{
+    aead::{
+        aes::{DecryptKey, Variant},
+        block::Block,
+    },
+    error,
+};
+
+pub use crate::aead::block::BLOCK_LEN;
+
+pub const KEY_LEN: usize = 32;
+
+pub struct Key(DecryptKey);
+
+impl Key {
+    pub fn new(key: &[u8; KEY_LEN]) -> Result<Self, error::Unspecified> {
+        DecryptKey::new(key, Variant::AES_256).map(Key)
+    }
+}
+
+/// Decrypts `in_out` in place using AES-256-CBC and removes the PKCS#7
+/// padding, returning the length of the plaintext.
+///
+/// Fails if `in_out` isn't a nonzero multiple of the block length or if the
+/// padding is invalid, which usually means the key is wrong.
+pub fn decrypt_cbc_pkcs7(
+    key: &Key,
+    iv: &[u8; BLOCK_LEN],
+    in_out: &mut [u8],
+) -> Result<usize, error::Unspecified> {
+    if in_out.is_empty() || in_out.len() % BLOCK_LEN != 0 {
+        return Err(error::Unspecified);
+    }
+
+    let mut prev = *iv;
+    for chunk in in_out.chunks_mut(BLOCK_LEN) {
+        let mut ciphertext = [0u8; BLOCK_LEN];
+        ciphertext.copy_from_slice(chunk);
+        let block = key.0.decrypt_block(Block::from(&ciphertext));
+        for ((out, b), p) in chunk.iter_mut().zip(block.as_ref()).zip(prev.iter()) {
+            *out = b ^ p;
+        }
+        prev = ciphertext;
+    }
+
+    // Check the padding without branching on it, so that the time taken
+    // doesn't reveal which part of it is wrong.
+    let last_block = &in_out[(in_out.len() - BLOCK_LEN)..];
+    let pad_len = last_block[BLOCK_LEN - 1];
+    let mut bad = !(lt_mask(0, pad_len) & lt_mask(pad_len, (BLOCK_LEN as u8) + 1));
+    for (i, &b) in last_block.iter().rev().enumerate() {
+        bad |= lt_mask(i as u8, pad_len) & (b ^ pad_len);
+    }
+    if bad != 0 {
+        return Err(error::Unspecified);
+    }
+    Ok(in_out.len() - usize::from(pad_len))
+}
+
+// Returns 0xff if `a < b` and 0 otherwise, without branching.
+fn lt_mask(a: u8, b: u8) -> u8 {
+    (u32::from(a).wrapping_sub(u32::from(b)) >> 8) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test;
+
+    // NIST SP 800-38A F.2.6 (CBC-AES256.Decrypt), with one block of PKCS#7
+    // padding appended by encrypting it with the same key.
+    #[test]
+    fn test_decrypt_cbc_pkcs7() {
+        let mut key = [0u8; KEY_LEN];
+        key.copy_from_slice(
+            &test::from_hex("603deb1015ca71be2b73aef0857d77811f352c073b6108d72d9810a30914dff4")
+                .unwrap(),
+        );
+        let key = Key::new(&key).unwrap();
+        let mut iv = [0u8; BLOCK_LEN];
+        iv.copy_from_slice(&test::from_hex("000102030405060708090a0b0c0d0e0f").unwrap());
+
+        let mut in_out = test::from_hex(
+            "f58c4c04d6e5f1ba779eabfb5f7bfbd6\
+             9cfc4e967edb808d679f777bc6702c7d\
+             39f23369a9d9bacfa530e26304231461\
+             b2eb05e2c39be9fcda6c19078c6a9d1b",
+        )
+        .unwrap();
+        let plaintext = test::from_hex(
+            "6bc1bee22e409f96e93d7e117393172a\
+             ae2d8a571e03ac9c9eb76fac45af8e51\
+             30c81c46a35ce411e5fbc1191a0a52ef\
+             f69f2445df4f9b17ad2b417be66c3710",
+        )
+        .unwrap();
+        // Without padding.
+        assert_eq!(
+            decrypt_cbc_pkcs7(&key, &iv, &mut in_out.clone()),
+            Err(error::Unspecified)
+        );
+
+        in_out.extend_from_slice(&test::from_hex("3f461796d6b0d6b2e0c2a72b4d80e644").unwrap());
+        let len = decrypt_cbc_pkcs7(&key, &iv, &mut in_out).unwrap();
+        assert_eq!(&in_out[..len], &plaintext[..]);
+
+        assert_eq!(
+            decrypt_cbc_pkcs7(&key, &iv, &mut []),
+            Err(error::Unspecified)
+        );
+        assert_eq!(
+            decrypt_cbc_pkcs7(&key, &iv, &mut [0; 15]),
+            Err(error::Unspecified)
+        );
+    }
+
+    #[test]
+    fn test_padding() {
+        let key = Key::new(&[0x42; KEY_LEN]).unwrap();
+        let ciphertext = [0x24; BLOCK_LEN];
+        let decrypted = key.0.decrypt_block(Block::from(&ciphertext));
+
+        let mut padded = [0x55; BLOCK_LEN];
+        for &(pad_len, expected) in &[
+            (0, None),
+            (1, Some(15)),
+            (2, Some(14)),
+            (15, Some(1)),
+            (16, Some(0)),
+            (17, None),
+            (0xff, None),
+        ] {
+            let n = core::cmp::min(usize::from(pad_len), BLOCK_LEN);
+            for b in padded[(BLOCK_LEN - n)..].iter_mut() {
+                *b = pad_len;
+            }
+            padded[BLOCK_LEN - 1] = pad_len;
+            assert_eq!(
+                decrypt_padded(&key, &decrypted, &ciphertext, &padded),
+                expected
+            );
+
+            // Every byte of the padding is checked.
+            for i in (BLOCK_LEN - n)..BLOCK_LEN {
+                let mut bad = padded;
+                bad[i] ^= 1;
+                assert_eq!(decrypt_padded(&key, &decrypted, &ciphertext, &bad), None);
+            }
+        }
+    }
+
+    // Decrypts `ciphertext` with the IV that makes it decrypt to `padded`.
+    fn decrypt_padded(
+        key: &Key,
+        decrypted: &Block,
+        ciphertext: &[u8; BLOCK_LEN],
+        padded: &[u8; BLOCK_LEN],
+    ) -> Option<usize> {
+        let mut iv = [0u8; BLOCK_LEN];
+        for ((iv, d), p) in iv.iter_mut().zip(decrypted.as_ref()).zip(padded.iter()) {
+            *iv = d ^ p;
+        }
+        let mut in_out = *ciphertext;
+        decrypt_cbc_pkcs7(key, &iv, &mut in_out).ok()
+    }
+
+    #[test]
+    fn test_lt_mask() {
+        for a in 0..=255u8 {
+            for b in 0..=255u8 {
+                assert_eq!(lt_mask(a, b), if a < b { 0xff } else { 0 });
+            }
+        }
+    }
+}
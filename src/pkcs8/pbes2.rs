@@ -0,0 +1,164 @@
+//! Decryption of PKCS#8 `EncryptedPrivateKeyInfo`s encrypted with PBES2.
+//!
+//! PBES2 is specified in [RFC 8018 Section 6.2]. Only PBKDF2 with
+//! HMAC-SHA256 as the key derivation function and AES-256-CBC as the
+//! encryption scheme are supported.
+//!
+//! [RFC 8018 Section 6.2]: https://tools.ietf.org/html/rfc8018#section-6.2
+
+use super::aes_cbc;
+use crate::{error, io::der, pbkdf2};
+use alloc::vec::Vec;
+use core::num::NonZeroU32;
+
+// 1.2.840.113549.1.5.13
+const PBES2_OID: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x05, 0x0d];
+
+// 1.2.840.113549.1.5.12
+const PBKDF2_OID: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x05, 0x0c];
+
+// 1.2.840.113549.2.9
+const HMAC_WITH_SHA256_OID: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x02, 0x09];
+
+// 2.16.840.1.101.3.4.1.42
+const AES256_CBC_OID: &[u8] = &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x01, 0x2a];
+
+// The maximum PBKDF2 iteration count, which bounds the time it takes to
+// decrypt an untrusted input. Keys are typically encrypted with between 2048
+// (OpenSSL's default) and a few hundred thousand iterations.
+const MAX_ITERATIONS: u32 = 10_000_000;
+
+struct Params<'a> {
+    salt: &'a [u8],
+    iterations: NonZeroU32,
+    iv: [u8; aes_cbc::BLOCK_LEN],
+}
+
+pub(super) fn decrypt(
+    input: untrusted::Input,
+    password: &[u8],
+) -> Result<Vec<u8>, error::Unspecified> {
+    let (params, ciphertext) = input.read_all(error::Unspecified, |input| {
+        der::nested(input, der::Tag::Sequence, error::Unspecified, |input| {
+            let params = der::nested(input, der::Tag::Sequence, error::Unspecified, |input| {
+                expect_oid(input, PBES2_OID)?;
+                der::nested(input, der::Tag::Sequence, error::Unspecified, pbes2_params)
+            })?;
+            let ciphertext = der::expect_tag_and_get_value(input, der::Tag::OctetString)?;
+            Ok((params, ciphertext))
+        })
+    })?;
+
+    let mut key = [0u8; aes_cbc::KEY_LEN];
+    pbkdf2::derive(
+        pbkdf2::PBKDF2_HMAC_SHA256,
+        params.iterations,
+        params.salt,
+        password,
+        &mut key,
+    );
+    let key = aes_cbc::Key::new(&key)?;
+
+    let mut in_out = ciphertext.as_slice_less_safe().to_vec();
+    let len = aes_cbc::decrypt_cbc_pkcs7(&key, &params.iv, &mut in_out)?;
+    in_out.truncate(len);
+    Ok(in_out)
+}
+
+fn pbes2_params<'a>(input: &mut untrusted::Reader<'a>) -> Result<Params<'a>, error::Unspecified> {
+    let (salt, iterations) = der::nested(input, der::Tag::Sequence, error::Unspecified, |input| {
+        expect_oid(input, PBKDF2_OID)?;
+        der::nested(input, der::Tag::Sequence, error::Unspecified, pbkdf2_params)
+    })?;
+
+    let iv = der::nested(input, der::Tag::Sequence, error::Unspecified, |input| {
+        expect_oid(input, AES256_CBC_OID)?;
+        let iv = der::expect_tag_and_get_value(input, der::Tag::OctetString)?;
+        let mut result = [0u8; aes_cbc::BLOCK_LEN];
+        if iv.len() != result.len() {
+            return Err(error::Unspecified);
+        }
+        result.copy_from_slice(iv.as_slice_less_safe());
+        Ok(result)
+    })?;
+
+    Ok(Params {
+        salt,
+        iterations,
+        iv,
+    })
+}
+
+fn pbkdf2_params<'a>(
+    input: &mut untrusted::Reader<'a>,
+) -> Result<(&'a [u8], NonZeroU32), error::Unspecified> {
+    let salt = der::expect_tag_and_get_value(input, der::Tag::OctetString)?;
+
+    let iterations = der::positive_integer(input)?.big_endian_without_leading_zero();
+    if iterations.len() > 4 {
+        return Err(error::Unspecified);
+    }
+    let iterations = iterations
+        .iter()
+        .fold(0u32, |acc, &b| (acc << 8) | u32::from(b));
+    if iterations > MAX_ITERATIONS {
+        return Err(error::Unspecified);
+    }
+    let iterations = NonZeroU32::new(iterations).ok_or(error::Unspecified)?;
+
+    if input.peek(der::Tag::Integer as u8) {
+        let key_len = der::small_nonnegative_integer(input)?;
+        if usize::from(key_len) != aes_cbc::KEY_LEN {
+            return Err(error::Unspecified);
+        }
+    }
+
+    // The default PRF is HMAC-SHA1, which isn't supported. The parameters of
+    // the PRF are NULL, but some encoders omit them.
+    der::nested(input, der::Tag::Sequence, error::Unspecified, |input| {
+        expect_oid(input, HMAC_WITH_SHA256_OID)?;
+        if !input.at_end() {
+            let null = der::expect_tag_and_get_value(input, der::Tag::Null)?;
+            if !null.is_empty() {
+                return Err(error::Unspecified);
+            }
+        }
+        Ok(())
+    })?;
+
+    Ok((salt.as_slice_less_safe(), iterations))
+}
+
+fn expect_oid(input: &mut untrusted::Reader, oid: &[u8]) -> Result<(), error::Unspecified> {
+    let actual = der::expect_tag_and_get_value(input, der::Tag::OID)?;
+    if actual.as_slice_less_safe() != oid {
+        return Err(error::Unspecified);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn test_pbkdf2_params_max_iterations() {
+        let parse = |iterations: &[u8]| {
+            let mut params = vec![0x04, 0x08, 0, 1, 2, 3, 4, 5, 6, 7];
+            params.extend_from_slice(&[0x02, iterations.len() as u8]);
+            params.extend_from_slice(iterations);
+            params.extend_from_slice(&[0x30, 0x0c, 0x06, 0x08]);
+            params.extend_from_slice(HMAC_WITH_SHA256_OID);
+            params.extend_from_slice(&[0x05, 0x00]);
+            untrusted::Input::from(&params)
+                .read_all(error::Unspecified, pbkdf2_params)
+                .map(|(_, iterations)| iterations.get())
+        };
+
+        assert_eq!(parse(&[0x08, 0x00]), Ok(2048));
+        assert_eq!(parse(&[0x00, 0x98, 0x96, 0x80]), Ok(MAX_ITERATIONS));
+        assert_eq!(parse(&[0x00, 0x98, 0x96, 0x81]), Err(error::Unspecified));
+        assert_eq!(parse(&[0x7f, 0xff, 0xff, 0xff]), Err(error::Unspecified));
+    }
+}
@@ -28,9 +28,28 @@
 use crate::error;
 
 /// A secure random number generator.
+///
+/// `SecureRandom` is object-safe, and *ring*'s APIs take a
+/// `&dyn SecureRandom`, so the implementation can be chosen at runtime.
+/// References to, and (with the `alloc` feature) boxes of, a `SecureRandom`
+/// are also `SecureRandom`s, so e.g. a `&Box<dyn SecureRandom>` can be passed
+/// wherever a `&dyn SecureRandom` is expected.
 pub trait SecureRandom: sealed::SecureRandom {
     /// Fills `dest` with random bytes.
     fn fill(&self, dest: &mut [u8]) -> Result<(), error::Unspecified>;
+
+    /// Fills each of `bufs` with random bytes, in order.
+    ///
+    /// This is equivalent to calling `fill` on each buffer in turn, so a
+    /// deterministic `SecureRandom` produces the same output as it would for
+    /// those separate calls. If any `fill` fails then `fill_all` fails
+    /// immediately and the contents of all of `bufs` are unspecified.
+    fn fill_all(&self, bufs: &mut [&mut [u8]]) -> Result<(), error::Unspecified> {
+        for buf in bufs.iter_mut() {
+            self.fill(buf)?;
+        }
+        Ok(())
+    }
 }
 
 impl<T> SecureRandom for T
@@ -43,6 +62,21 @@ where
     }
 }
 
+impl<T: SecureRandom + ?Sized> sealed::SecureRandom for &T {
+    #[inline]
+    fn fill_impl(&self, dest: &mut [u8]) -> Result<(), error::Unspecified> {
+        (**self).fill(dest)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: SecureRandom + ?Sized> sealed::SecureRandom for alloc::boxed::Box<T> {
+    #[inline]
+    fn fill_impl(&self, dest: &mut [u8]) -> Result<(), error::Unspecified> {
+        (**self).fill(dest)
+    }
+}
+
 /// A random value constructed from a `SecureRandom` that hasn't been exposed
 /// through any safe Rust interface.
 ///
@@ -70,6 +104,275 @@ where
     Ok(Random(r))
 }
 
+/// Returns a uniformly random integer in `0..n`, using `rng`.
+///
+/// This uses rejection sampling, so unlike reducing a random integer modulo
+/// `n`, the result isn't biased towards smaller values. Each attempt draws the
+/// fewest bytes that can represent `n - 1`, interpreted as a big-endian
+/// integer with the bits above the highest bit of `n - 1` cleared, and
+/// retries if the result isn't less than `n`; at most half of the attempts
+/// are retried on average. When `n` is 1, no bytes are drawn.
+///
+/// Fails if `n` is zero or if `rng` fails.
+pub fn uniform_usize_below(rng: &dyn SecureRandom, n: usize) -> Result<usize, error::Unspecified> {
+    let mut bytes = 0usize.to_be_bytes();
+    let usize_bits = bytes.len() * 8;
+
+    let max = n.checked_sub(1).ok_or(error::Unspecified)?;
+    let bits = usize_bits - (max.leading_zeros() as usize);
+    if bits == 0 {
+        return Ok(0);
+    }
+    let mask = usize::max_value() >> (usize_bits - bits);
+
+    let bytes = &mut bytes[..((bits + 7) / 8)];
+    loop {
+        rng.fill(bytes)?;
+        let candidate = bytes.iter().fold(0, |acc, &b| (acc << 8) | usize::from(b)) & mask;
+        if candidate <= max {
+            return Ok(candidate);
+        }
+    }
+}
+
+/// Checks that `rng` isn't obviously broken, e.g. stuck returning the same
+/// bytes, as a startup health check.
+///
+/// This draws 20,000 bits from `rng` in 160-bit blocks, one `fill` call per
+/// block, and fails if any block is equal to the previous one (the
+/// continuous random number generator test of FIPS 140-2) or if the number
+/// of one bits is outside the range 9,654 to 10,346 (the monobit test of
+/// FIPS 140-1). Fails if `rng` fails.
+///
+/// This is a smoke test, not a statistical proof of randomness: it catches
+/// stuck or badly biased sources, but passing it says nothing about
+/// unpredictability. A working RNG fails the monobit test with a
+/// probability of about one in a million. The random bytes drawn are
+/// discarded.
+pub fn self_test(rng: &dyn SecureRandom) -> Result<(), error::Unspecified> {
+    const BLOCK_LEN: usize = 160 / 8;
+    const SAMPLE_LEN: usize = 20_000 / 8;
+
+    let mut sample = [0u8; SAMPLE_LEN];
+    let mut previous: Option<&[u8]> = None;
+    for block in sample.chunks_mut(BLOCK_LEN) {
+        rng.fill(block)?;
+        let block: &[u8] = block;
+        if previous == Some(block) {
+            return Err(error::Unspecified);
+        }
+        previous = Some(block);
+    }
+
+    let ones: u32 = sample.iter().map(|b| b.count_ones()).sum();
+    if ones <= 9_654 || ones >= 10_346 {
+        return Err(error::Unspecified);
+    }
+
+    Ok(())
+}
+
+/// A source of entropy, such as an operating system API.
+///
+/// `SystemRandom` is composed of an ordered list of `EntropySource`s, which
+/// are tried in turn. This isn't public, since any `EntropySource` could then
+/// be used as a `SecureRandom`.
+pub(crate) trait EntropySource: core::fmt::Debug + Sync {
+    /// Fills all of `dest` with random bytes, or fails.
+    ///
+    /// On failure the contents of `dest` are unspecified.
+    fn fill(&self, dest: &mut [u8]) -> Result<(), error::Unspecified>;
+
+    /// Returns `true` if the source is seeded and can be expected to `fill`
+    /// without blocking, or `false` if it isn't (yet) usable.
+    ///
+    /// This must not block. The default implementation returns `true`, which
+    /// is appropriate for sources that are always ready.
+    fn is_ready(&self) -> bool {
+        true
+    }
+}
+
+/// A secure random number generator that XORs the outputs of two others.
+///
+/// A `XorRandom` is at least as unpredictable as the more unpredictable of
+/// its two sources, as long as the two are independent, so it can be used to
+/// combine e.g. `SystemRandom` with a hardware RNG for defense in depth. If
+/// either source fails then the `XorRandom` fails.
+#[derive(Clone, Copy, Debug)]
+pub struct XorRandom<A: SecureRandom, B: SecureRandom> {
+    a: A,
+    b: B,
+}
+
+impl<A: SecureRandom, B: SecureRandom> XorRandom<A, B> {
+    /// Constructs a new `XorRandom` that combines `a` and `b`.
+    #[inline]
+    pub fn new(a: A, b: B) -> Self {
+        Self { a, b }
+    }
+}
+
+impl<A: SecureRandom, B: SecureRandom> sealed::SecureRandom for XorRandom<A, B> {
+    fn fill_impl(&self, dest: &mut [u8]) -> Result<(), error::Unspecified> {
+        self.a.fill(dest)?;
+
+        let mut scratch = [0u8; 256];
+        for dest in dest.chunks_mut(scratch.len()) {
+            let scratch = &mut scratch[..dest.len()];
+            self.b.fill(scratch)?;
+            dest.iter_mut()
+                .zip(scratch.iter())
+                .for_each(|(d, s)| *d ^= s);
+        }
+        Ok(())
+    }
+}
+
+impl<A: SecureRandom, B: SecureRandom> crate::sealed::Sealed for XorRandom<A, B> {}
+
+/// A secure random number generator that refuses to fill more than a fixed
+/// number of bytes at a time.
+///
+/// A `fill` of more than `max_per_fill` bytes fails without calling the
+/// inner `SecureRandom`; otherwise the inner `SecureRandom` is used as-is.
+/// This turns a bug that requests an unexpectedly enormous amount of
+/// randomness, e.g. due to an attacker-controlled length, into an error.
+#[derive(Clone, Copy, Debug)]
+pub struct LimitedRandom<R: SecureRandom> {
+    inner: R,
+    max_per_fill: usize,
+}
+
+impl<R: SecureRandom> LimitedRandom<R> {
+    /// Constructs a new `LimitedRandom` that wraps `inner`.
+    #[inline]
+    pub fn new(inner: R, max_per_fill: usize) -> Self {
+        Self {
+            inner,
+            max_per_fill,
+        }
+    }
+
+    /// The maximum number of bytes that a single `fill` may request.
+    #[inline]
+    pub fn max_per_fill(&self) -> usize {
+        self.max_per_fill
+    }
+}
+
+impl<R: SecureRandom> sealed::SecureRandom for LimitedRandom<R> {
+    #[inline]
+    fn fill_impl(&self, dest: &mut [u8]) -> Result<(), error::Unspecified> {
+        if dest.len() > self.max_per_fill {
+            return Err(error::Unspecified);
+        }
+        self.inner.fill(dest)
+    }
+}
+
+impl<R: SecureRandom> crate::sealed::Sealed for LimitedRandom<R> {}
+
+/// A secure random number generator that uses the CPU's `RDRAND` instruction
+/// directly, instead of asking the operating system.
+///
+/// Each word is retried up to ten times if `RDRAND` reports failure, as
+/// recommended by Intel's Digital Random Number Generator (DRNG) Software
+/// Implementation Guide. A word of all one bits is treated as a failure too,
+/// since some AMD CPUs return all ones, while reporting success, after
+/// resuming from suspend. `fill` fails if the CPU doesn't support `RDRAND` or
+/// if any word can't be generated within the retry limit.
+///
+/// Trusting the CPU's random number generator is a policy decision. To avoid
+/// relying on it alone, combine it with `SystemRandom` using `XorRandom`.
+///
+/// Only available on x86 and x86-64.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[derive(Clone, Copy, Debug)]
+pub struct RdRand(());
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+impl RdRand {
+    /// Constructs a new `RdRand`.
+    #[inline]
+    pub fn new() -> Self {
+        Self(())
+    }
+
+    /// Returns `true` if the CPU supports `RDRAND`.
+    #[inline]
+    pub fn is_available(&self) -> bool {
+        crate::cpu::intel::RDRAND.available(crate::cpu::features())
+    }
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+impl sealed::SecureRandom for RdRand {
+    fn fill_impl(&self, dest: &mut [u8]) -> Result<(), error::Unspecified> {
+        if !self.is_available() {
+            return Err(error::Unspecified);
+        }
+        for dest in dest.chunks_mut(rdrand::WORD_LEN) {
+            let word = rdrand::next_word()?.to_ne_bytes();
+            dest.copy_from_slice(&word[..dest.len()]);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+impl crate::sealed::Sealed for RdRand {}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod rdrand {
+    use crate::error;
+
+    #[cfg(target_arch = "x86")]
+    use core::arch::x86::_rdrand32_step as rdrand_step;
+    #[cfg(target_arch = "x86_64")]
+    use core::arch::x86_64::_rdrand64_step as rdrand_step;
+
+    #[cfg(target_arch = "x86")]
+    type Word = u32;
+    #[cfg(target_arch = "x86_64")]
+    type Word = u64;
+
+    #[cfg(target_arch = "x86")]
+    pub(super) const WORD_LEN: usize = 4;
+    #[cfg(target_arch = "x86_64")]
+    pub(super) const WORD_LEN: usize = 8;
+
+    const RETRY_LIMIT: usize = 10;
+
+    /// The caller must have verified that the CPU supports `RDRAND`.
+    pub(super) fn next_word() -> Result<Word, error::Unspecified> {
+        for _ in 0..RETRY_LIMIT {
+            let mut word: Word = 0;
+            if unsafe { step(&mut word) } == 1 && word != Word::max_value() {
+                return Ok(word);
+            }
+        }
+        Err(error::Unspecified)
+    }
+
+    #[target_feature(enable = "rdrand")]
+    unsafe fn step(word: &mut Word) -> i32 {
+        rdrand_step(word)
+    }
+}
+
+fn fill_from_sources(
+    sources: &[&dyn EntropySource],
+    dest: &mut [u8],
+) -> Result<(), error::Unspecified> {
+    for source in sources {
+        if source.fill(dest).is_ok() {
+            return Ok(());
+        }
+    }
+    Err(error::Unspecified)
+}
+
 pub(crate) mod sealed {
     use crate::error;
 
@@ -116,10 +419,15 @@ impl<T> RandomlyConstructable for T where T: self::sealed::RandomlyConstructable
 /// `fill()` once at a non-latency-sensitive time to minimize latency for
 /// future calls.
 ///
+/// `SystemRandom` is composed of an ordered list of the platform's entropy
+/// sources; `fill()` tries each source in turn and uses the first one that
+/// succeeds. On most platforms there is exactly one source.
+///
 /// On Linux (including Android), `fill()` will use the [`getrandom`] syscall.
 /// If the kernel is too old to support `getrandom` then by default `fill()`
-/// falls back to reading from `/dev/urandom`. This decision is made the first
-/// time `fill` *succeeds*. The fallback to `/dev/urandom` can be disabled by
+/// falls back to reading from `/dev/urandom`. Whether `getrandom` is supported
+/// is determined the first time `fill` is called; `fill()` also falls back to
+/// `/dev/urandom` if `getrandom` fails. The fallback to `/dev/urandom` can be disabled by
 /// disabling the `dev_urandom_fallback` default feature; this should be done
 /// whenever the target system is known to support `getrandom`. When
 /// `/dev/urandom` is used, a file handle for `/dev/urandom` won't be opened
@@ -147,6 +455,14 @@ impl<T> RandomlyConstructable for T where T: self::sealed::RandomlyConstructable
 /// On UEFI, `fill` is implemented using `CRYPTO_rdrand`
 /// & `CRYPTO_rdrand_multiple8_buf` which provided by BoringSSL.
 ///
+/// `SystemRandom` never buffers or caches random bytes in userspace; every
+/// call to `fill()` requests fresh bytes from the operating system. (The only
+/// state that may be retained across calls is the `/dev/urandom` file handle
+/// described above, which doesn't hold any random bytes itself.) Consequently
+/// `SystemRandom` is safe to use across `fork()`: the parent and the child
+/// will never be handed the same bytes, and no reseeding is needed after a
+/// fork.
+///
 #[derive(Clone, Debug)]
 pub struct SystemRandom(());
 
@@ -156,12 +472,136 @@ impl SystemRandom {
     pub fn new() -> Self {
         Self(())
     }
+
+    /// Returns `true` if the operating system's random number generator has
+    /// been seeded, without blocking.
+    ///
+    /// Early in the boot process, e.g. in an init system or in a UEFI
+    /// application, the system entropy pool may not be initialized yet. Until
+    /// it is, `fill()` may block (on Linux, `getrandom` waits for the pool to
+    /// be initialized) or fail (on UEFI, if `RDRAND` is unavailable).
+    ///
+    /// On Linux and Android this probes `getrandom` with `GRND_NONBLOCK`. If
+    /// `getrandom` isn't supported and `fill()` would fall back to
+    /// `/dev/urandom`, this returns whether `/dev/urandom` can be opened,
+    /// since it never blocks, even though it can't tell whether the pool has
+    /// been seeded. The same goes for the BSDs, which only use
+    /// `/dev/urandom`. On UEFI and other bare-metal targets this checks for
+    /// the availability of `RDRAND`. On other platforms the random number
+    /// generator is always seeded once user code runs, so this always returns
+    /// `true`.
+    pub fn is_ready(&self) -> bool {
+        is_ready_impl()
+    }
+}
+
+// `fill()` only falls back to `/dev/urandom` if `getrandom` isn't supported
+// at all; while the pool is being initialized it blocks in `getrandom`
+// instead. So `/dev/urandom` only decides readiness in the former case.
+#[cfg(all(
+    any(target_os = "android", target_os = "linux"),
+    feature = "dev_urandom_fallback"
+))]
+fn is_ready_impl() -> bool {
+    is_ready_given(sysrand_chunk::probe(), || urandom::DevURandom.is_ready())
+}
+
+#[cfg(all(
+    any(target_os = "android", target_os = "linux"),
+    feature = "dev_urandom_fallback"
+))]
+fn is_ready_given(
+    probe: sysrand_chunk::Probe,
+    dev_urandom_is_ready: impl FnOnce() -> bool,
+) -> bool {
+    match probe {
+        sysrand_chunk::Probe::Ready => true,
+        sysrand_chunk::Probe::NotReady => false,
+        sysrand_chunk::Probe::Unsupported => dev_urandom_is_ready(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fill_from_sources, EntropySource};
+    use crate::error;
+
+    #[derive(Debug)]
+    struct FailingSource;
+
+    impl EntropySource for FailingSource {
+        fn fill(&self, dest: &mut [u8]) -> Result<(), error::Unspecified> {
+            // Partially write `dest` to verify the next source overwrites it.
+            for b in dest.iter_mut().take(1) {
+                *b = 0xff;
+            }
+            Err(error::Unspecified)
+        }
+    }
+
+    #[derive(Debug)]
+    struct ByteSource(u8);
+
+    impl EntropySource for ByteSource {
+        fn fill(&self, dest: &mut [u8]) -> Result<(), error::Unspecified> {
+            for b in dest.iter_mut() {
+                *b = self.0;
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn fill_from_sources_test() {
+        let mut buf = [0u8; 4];
+
+        // The first source that succeeds is used.
+        let sources: [&dyn EntropySource; 3] = [&FailingSource, &ByteSource(1), &ByteSource(2)];
+        assert!(fill_from_sources(&sources, &mut buf).is_ok());
+        assert_eq!(buf, [1, 1, 1, 1]);
+
+        // Fails only when every source fails.
+        let sources: [&dyn EntropySource; 2] = [&FailingSource, &FailingSource];
+        assert_eq!(
+            fill_from_sources(&sources, &mut buf),
+            Err(error::Unspecified)
+        );
+        assert_eq!(fill_from_sources(&[], &mut buf), Err(error::Unspecified));
+
+        // Sources are ready by default.
+        assert!(ByteSource(0).is_ready());
+    }
+
+    #[cfg(all(
+        any(target_os = "android", target_os = "linux"),
+        feature = "dev_urandom_fallback"
+    ))]
+    #[test]
+    fn is_ready_given_test() {
+        use super::{is_ready_given, sysrand_chunk::Probe};
+
+        assert!(is_ready_given(Probe::Ready, || unreachable!()));
+        // `/dev/urandom` doesn't mask an uninitialized pool, since `fill()`
+        // would block in `getrandom` instead of falling back to it.
+        assert!(!is_ready_given(Probe::NotReady, || unreachable!()));
+        assert!(is_ready_given(Probe::Unsupported, || true));
+        assert!(!is_ready_given(Probe::Unsupported, || false));
+    }
+}
+
+// Every other configuration has exactly one source.
+#[cfg(not(all(
+    any(target_os = "android", target_os = "linux"),
+    feature = "dev_urandom_fallback"
+)))]
+fn is_ready_impl() -> bool {
+    SOURCES.iter().all(|source| source.is_ready())
 }
 
 impl sealed::SecureRandom for SystemRandom {
     #[inline(always)]
     fn fill_impl(&self, dest: &mut [u8]) -> Result<(), error::Unspecified> {
-        fill_impl(dest)
+        fill_from_sources(SOURCES, dest)
     }
 }
 
@@ -175,13 +615,13 @@ impl crate::sealed::Sealed for SystemRandom {}
     target_arch = "wasm32",
     windows
 ))]
-use self::sysrand::fill as fill_impl;
+static SOURCES: &[&dyn EntropySource] = &[&sysrand::Sysrand];
 
 #[cfg(all(
     any(target_os = "android", target_os = "linux"),
     feature = "dev_urandom_fallback"
 ))]
-use self::sysrand_or_urandom::fill as fill_impl;
+static SOURCES: &[&dyn EntropySource] = &[&sysrand::Sysrand, &urandom::DevURandom];
 
 #[cfg(any(
     target_os = "dragonfly",
@@ -191,66 +631,138 @@ use self::sysrand_or_urandom::fill as fill_impl;
     target_os = "openbsd",
     target_os = "solaris",
 ))]
-use self::urandom::fill as fill_impl;
+static SOURCES: &[&dyn EntropySource] = &[&urandom::DevURandom];
 
 #[cfg(any(target_os = "macos", target_os = "ios"))]
-use self::darwin::fill as fill_impl;
+static SOURCES: &[&dyn EntropySource] = &[&darwin::SecRandomCopyBytes];
 
 #[cfg(any(target_os = "fuchsia"))]
-use self::fuchsia::fill as fill_impl;
+static SOURCES: &[&dyn EntropySource] = &[&fuchsia::ZxCprngDraw];
 
 #[cfg(any(target_os = "uefi", target_os = "none"))]
-use self::no_std::fill as fill_impl;
+static SOURCES: &[&dyn EntropySource] = &[&no_std::RdRand];
 
 #[cfg(any(target_os = "android", target_os = "linux"))]
 mod sysrand_chunk {
     use crate::{c, error};
+    use libc::c_long;
 
-    #[inline]
-    pub fn chunk(dest: &mut [u8]) -> Result<usize, error::Unspecified> {
-        use libc::c_long;
+    // See `SYS_getrandom` in #include <sys/syscall.h>.
 
-        // See `SYS_getrandom` in #include <sys/syscall.h>.
+    #[cfg(target_arch = "aarch64")]
+    const SYS_GETRANDOM: c_long = 278;
 
-        #[cfg(target_arch = "aarch64")]
-        const SYS_GETRANDOM: c_long = 278;
+    #[cfg(target_arch = "arm")]
+    const SYS_GETRANDOM: c_long = 384;
 
-        #[cfg(target_arch = "arm")]
-        const SYS_GETRANDOM: c_long = 384;
+    #[cfg(target_arch = "x86")]
+    const SYS_GETRANDOM: c_long = 355;
 
-        #[cfg(target_arch = "x86")]
-        const SYS_GETRANDOM: c_long = 355;
+    #[cfg(target_arch = "x86_64")]
+    const SYS_GETRANDOM: c_long = 318;
 
-        #[cfg(target_arch = "x86_64")]
-        const SYS_GETRANDOM: c_long = 318;
+    #[cfg(target_arch = "riscv64")]
+    const SYS_GETRANDOM: c_long = 278;
 
-        #[cfg(target_arch = "riscv64")]
-        const SYS_GETRANDOM: c_long = 278;
+    // See `GRND_NONBLOCK` in #include <sys/random.h>.
+    const GRND_NONBLOCK: c::uint = 1;
 
+    fn getrandom(dest: &mut [u8], flags: c::uint) -> c_long {
         let chunk_len: c::size_t = dest.len();
-        let r = unsafe { libc::syscall(SYS_GETRANDOM, dest.as_mut_ptr(), chunk_len, 0) };
-        if r < 0 {
-            let errno;
+        unsafe { libc::syscall(SYS_GETRANDOM, dest.as_mut_ptr(), chunk_len, flags) }
+    }
 
-            #[cfg(target_os = "linux")]
-            {
-                errno = unsafe { *libc::__errno_location() };
+    fn errno() -> c::int {
+        #[cfg(target_os = "linux")]
+        {
+            unsafe { *libc::__errno_location() }
+        }
+
+        #[cfg(target_os = "android")]
+        {
+            unsafe { *libc::__errno() }
+        }
+    }
+
+    /// The result of probing `getrandom` without blocking.
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    pub enum Probe {
+        /// The entropy pool has been initialized.
+        Ready,
+
+        /// The entropy pool hasn't been initialized yet, so `chunk` would
+        /// block.
+        NotReady,
+
+        /// `getrandom` isn't supported (or failed for another reason), so
+        /// `chunk` would fail.
+        Unsupported,
+    }
+
+    /// Probes `getrandom` with `GRND_NONBLOCK`.
+    pub fn probe() -> Probe {
+        probe_with(|dest| {
+            if getrandom(dest, GRND_NONBLOCK) >= 0 {
+                Ok(())
+            } else {
+                Err(errno())
             }
+        })
+    }
 
-            #[cfg(target_os = "android")]
-            {
-                errno = unsafe { *libc::__errno() };
+    fn probe_with(mut getrandom_nonblock: impl FnMut(&mut [u8]) -> Result<(), c::int>) -> Probe {
+        let mut dummy = [0u8; 1];
+        loop {
+            match getrandom_nonblock(&mut dummy) {
+                Ok(()) => return Probe::Ready,
+                Err(libc::EINTR) => {}
+                Err(libc::EAGAIN) => return Probe::NotReady,
+                Err(_) => return Probe::Unsupported,
             }
+        }
+    }
 
-            if errno == libc::EINTR {
-                // If an interrupt occurs while getrandom() is blocking to wait
-                // for the entropy pool, then EINTR is returned. Returning 0
-                // will cause the caller to try again.
-                return Ok(0);
+    /// Fills a prefix of `dest`, returning its length. The call is retried
+    /// if it's interrupted by a signal, so an error is always genuine.
+    #[inline]
+    pub fn chunk(dest: &mut [u8]) -> Result<usize, error::Unspecified> {
+        loop {
+            let r = getrandom(dest, 0);
+            if r >= 0 {
+                return Ok(r as usize);
             }
-            return Err(error::Unspecified);
+            // If an interrupt occurs while getrandom() is blocking to wait
+            // for the entropy pool, or while it's generating a large output,
+            // then EINTR is returned and nothing was written.
+            if errno() != libc::EINTR {
+                return Err(error::Unspecified);
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::{probe_with, Probe};
+
+        #[test]
+        fn probe_with_test() {
+            assert_eq!(probe_with(|_| Ok(())), Probe::Ready);
+            assert_eq!(probe_with(|_| Err(libc::EAGAIN)), Probe::NotReady);
+            assert_eq!(probe_with(|_| Err(libc::ENOSYS)), Probe::Unsupported);
+
+            // Interrupted calls are retried.
+            let mut calls = 0;
+            let probe = probe_with(|_| {
+                calls += 1;
+                if calls < 3 {
+                    Err(libc::EINTR)
+                } else {
+                    Err(libc::EAGAIN)
+                }
+            });
+            assert_eq!(probe, Probe::NotReady);
+            assert_eq!(calls, 3);
         }
-        Ok(r as usize)
     }
 }
 
@@ -316,7 +828,46 @@ mod sysrand {
     use super::sysrand_chunk::chunk;
     use crate::error;
 
-    pub fn fill(dest: &mut [u8]) -> Result<(), error::Unspecified> {
+    /// The operating system's preferred random number API.
+    #[derive(Debug)]
+    pub struct Sysrand;
+
+    impl super::EntropySource for Sysrand {
+        #[inline]
+        fn fill(&self, dest: &mut [u8]) -> Result<(), error::Unspecified> {
+            // Keep the `cfg` conditions in sync with the conditions in lib.rs.
+            #[cfg(all(
+                any(target_os = "android", target_os = "linux"),
+                feature = "dev_urandom_fallback"
+            ))]
+            {
+                use once_cell::sync::Lazy;
+                static SUPPORTED: Lazy<bool> = Lazy::new(|| {
+                    let mut dummy = [0u8; 1];
+                    chunk(&mut dummy[..]).is_ok()
+                });
+                if !*SUPPORTED {
+                    return Err(error::Unspecified);
+                }
+            }
+
+            fill_from_chunks(dest, chunk)
+        }
+
+        #[cfg(any(target_os = "android", target_os = "linux"))]
+        fn is_ready(&self) -> bool {
+            super::sysrand_chunk::probe() == super::sysrand_chunk::Probe::Ready
+        }
+    }
+
+    // `chunk` may fill less than all of the buffer it's given (e.g. because
+    // `getrandom` returns at most 32MiB per call, or because it was
+    // interrupted after writing some output), so keep calling it until `dest`
+    // is full or it fails.
+    fn fill_from_chunks(
+        dest: &mut [u8],
+        mut chunk: impl FnMut(&mut [u8]) -> Result<usize, error::Unspecified>,
+    ) -> Result<(), error::Unspecified> {
         let mut read_len = 0;
         while read_len < dest.len() {
             let chunk_len = chunk(&mut dest[read_len..])?;
@@ -324,36 +875,57 @@ mod sysrand {
         }
         Ok(())
     }
-}
 
-// Keep the `cfg` conditions in sync with the conditions in lib.rs.
-#[cfg(all(
-    any(target_os = "android", target_os = "linux"),
-    feature = "dev_urandom_fallback"
-))]
-mod sysrand_or_urandom {
-    use crate::error;
-
-    enum Mechanism {
-        Sysrand,
-        DevURandom,
-    }
+    #[cfg(test)]
+    mod tests {
+        use crate::error;
+
+        #[test]
+        fn fill_from_chunks_partial() {
+            // Returns at most 3 bytes per call, and nothing every other call.
+            let mut calls = 0;
+            let mut next = 0u8;
+            let mut dest = [0u8; 10];
+            super::fill_from_chunks(&mut dest, |chunk| {
+                calls += 1;
+                if calls % 2 == 0 {
+                    return Ok(0);
+                }
+                let len = core::cmp::min(chunk.len(), 3);
+                for b in &mut chunk[..len] {
+                    next += 1;
+                    *b = next;
+                }
+                Ok(len)
+            })
+            .unwrap();
+            assert_eq!(dest, [1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
+            assert_eq!(calls, 7);
+        }
 
-    #[inline]
-    pub fn fill(dest: &mut [u8]) -> Result<(), error::Unspecified> {
-        use once_cell::sync::Lazy;
-        static MECHANISM: Lazy<Mechanism> = Lazy::new(|| {
-            let mut dummy = [0u8; 1];
-            if super::sysrand_chunk::chunk(&mut dummy[..]).is_err() {
-                Mechanism::DevURandom
-            } else {
-                Mechanism::Sysrand
-            }
-        });
+        #[test]
+        fn fill_from_chunks_error() {
+            let mut calls = 0;
+            let mut dest = [0u8; 10];
+            let result = super::fill_from_chunks(&mut dest, |chunk| {
+                calls += 1;
+                if calls == 1 {
+                    chunk[0] = 1;
+                    return Ok(1);
+                }
+                Err(error::Unspecified)
+            });
+            assert_eq!(result, Err(error::Unspecified));
+            assert_eq!(calls, 2);
+        }
 
-        match *MECHANISM {
-            Mechanism::Sysrand => super::sysrand::fill(dest),
-            Mechanism::DevURandom => super::urandom::fill(dest),
+        #[cfg(all(any(target_os = "android", target_os = "linux"), feature = "alloc"))]
+        #[test]
+        fn fill_from_chunks_large() {
+            // Larger than the 32MiB that `getrandom` returns per call.
+            let mut dest = alloc::vec![0u8; (32 << 20) + 16];
+            super::fill_from_chunks(&mut dest, super::chunk).unwrap();
+            assert_ne!(dest[(32 << 20)..], [0u8; 16][..]);
         }
     }
 }
@@ -373,15 +945,32 @@ mod sysrand_or_urandom {
 mod urandom {
     use crate::error;
 
-    #[cfg_attr(any(target_os = "android", target_os = "linux"), cold, inline(never))]
-    pub fn fill(dest: &mut [u8]) -> Result<(), error::Unspecified> {
-        extern crate std;
+    /// `/dev/urandom`.
+    #[derive(Debug)]
+    pub struct DevURandom;
+
+    impl super::EntropySource for DevURandom {
+        #[inline]
+        fn fill(&self, dest: &mut [u8]) -> Result<(), error::Unspecified> {
+            fill(dest)
+        }
+
+        // `/dev/urandom` never blocks, so it's ready as long as it can be
+        // opened.
+        fn is_ready(&self) -> bool {
+            FILE.is_ok()
+        }
+    }
 
-        use once_cell::sync::Lazy;
+    extern crate std;
 
-        static FILE: Lazy<Result<std::fs::File, std::io::Error>> =
-            Lazy::new(|| std::fs::File::open("/dev/urandom"));
+    use once_cell::sync::Lazy;
 
+    static FILE: Lazy<Result<std::fs::File, std::io::Error>> =
+        Lazy::new(|| std::fs::File::open("/dev/urandom"));
+
+    #[cfg_attr(any(target_os = "android", target_os = "linux"), cold, inline(never))]
+    fn fill(dest: &mut [u8]) -> Result<(), error::Unspecified> {
         match *FILE {
             Ok(ref file) => {
                 use std::io::Read;
@@ -396,11 +985,17 @@ mod urandom {
 mod darwin {
     use crate::{c, error};
 
-    pub fn fill(dest: &mut [u8]) -> Result<(), error::Unspecified> {
-        let r = unsafe { SecRandomCopyBytes(kSecRandomDefault, dest.len(), dest.as_mut_ptr()) };
-        match r {
-            0 => Ok(()),
-            _ => Err(error::Unspecified),
+    /// `SecRandomCopyBytes`.
+    #[derive(Debug)]
+    pub struct SecRandomCopyBytes;
+
+    impl super::EntropySource for SecRandomCopyBytes {
+        fn fill(&self, dest: &mut [u8]) -> Result<(), error::Unspecified> {
+            let r = unsafe { SecRandomCopyBytes(kSecRandomDefault, dest.len(), dest.as_mut_ptr()) };
+            match r {
+                0 => Ok(()),
+                _ => Err(error::Unspecified),
+            }
         }
     }
 
@@ -429,11 +1024,17 @@ mod darwin {
 mod fuchsia {
     use crate::error;
 
-    pub fn fill(dest: &mut [u8]) -> Result<(), error::Unspecified> {
-        unsafe {
-            zx_cprng_draw(dest.as_mut_ptr(), dest.len());
+    /// `zx_cprng_draw`.
+    #[derive(Debug)]
+    pub struct ZxCprngDraw;
+
+    impl super::EntropySource for ZxCprngDraw {
+        fn fill(&self, dest: &mut [u8]) -> Result<(), error::Unspecified> {
+            unsafe {
+                zx_cprng_draw(dest.as_mut_ptr(), dest.len());
+            }
+            Ok(())
         }
-        Ok(())
     }
 
     #[link(name = "zircon")]
@@ -446,8 +1047,23 @@ mod fuchsia {
 mod no_std {
     use crate::error;
 
-    pub fn fill(dest: &mut [u8]) -> Result<(), error::Unspecified> {
-        fill_impl(dest)
+    /// The `RDRAND` instruction, where available.
+    #[derive(Debug)]
+    pub struct RdRand;
+
+    impl super::EntropySource for RdRand {
+        fn fill(&self, dest: &mut [u8]) -> Result<(), error::Unspecified> {
+            fill_impl(dest)
+        }
+
+        fn is_ready(&self) -> bool {
+            is_avaiable()
+        }
+    }
+
+    #[cfg(not(any(target_arch = "x86_64")))]
+    fn is_avaiable() -> bool {
+        false
     }
 
     #[cfg(not(any(target_arch = "x86_64")))]
@@ -456,20 +1072,14 @@ mod no_std {
     }
 
     #[cfg(any(target_arch = "x86_64"))]
-    fn fill_impl(dest: &mut [u8]) -> Result<(), error::Unspecified> {
-        fn is_avaiable() -> bool {
-            // TODO(xiaoyuxlu): use cpu::intel::RDRAND.avaiable when cpu.rs updated
-            // https://github.com/briansmith/ring/pull/1406#discussion_r720394928
-            // Current implementation may cause problem on AMD cpu. REF:
-            // https://github.com/nagisa/rust_rdrand/blob/f2fdd528a6103c946a2e9d0961c0592498b36493/src/lib.rs#L161
-            extern "C" {
-                static mut GFp_ia32cap_P: [u32; 4];
-            }
-            const FLAG: u32 = 1 << 30;
-            unsafe { GFp_ia32cap_P[1] & FLAG == FLAG }
-        }
+    fn is_avaiable() -> bool {
+        // Current implementation may cause problem on AMD cpu. REF:
+        // https://github.com/nagisa/rust_rdrand/blob/f2fdd528a6103c946a2e9d0961c0592498b36493/src/lib.rs#L161
+        crate::cpu::intel::RDRAND.available(crate::cpu::features())
+    }
 
-        let _ = crate::cpu::features();
+    #[cfg(any(target_arch = "x86_64"))]
+    fn fill_impl(dest: &mut [u8]) -> Result<(), error::Unspecified> {
         // We must make sure current cpu support `rdrand`
         if !is_avaiable() {
             return Err(error::Unspecified);
@@ -146,6 +146,30 @@ impl RsaKeyPair {
         Self::from_der(der.as_slice_less_safe())
     }
 
+    /// Parses a PEM-encoded unencrypted PKCS#8 RSA private key, i.e. a
+    /// `PRIVATE KEY` block as generated by `openssl genpkey`.
+    ///
+    /// The first PEM block in `pem` is decoded with `pem::parse()` and then
+    /// parsed like `from_pkcs8()`.
+    pub fn from_pkcs8_pem(pem: &str) -> Result<Self, KeyRejected> {
+        let pkcs8 = crate::pem::parse_pkcs8(pem)?;
+        Self::from_pkcs8(&pkcs8)
+    }
+
+    /// Parses an RSA private key in a password-protected PKCS#8
+    /// `EncryptedPrivateKeyInfo`.
+    ///
+    /// The key is decrypted with `pkcs8::decrypt()`, which documents the
+    /// supported encryption algorithms, and then parsed like `from_pkcs8()`.
+    /// Every failure, including a failure to parse the decrypted key, is
+    /// reported as the same error, so that the result doesn't reveal anything
+    /// about the decrypted bytes when the password is wrong.
+    pub fn from_pkcs8_encrypted(encrypted: &[u8], password: &[u8]) -> Result<Self, KeyRejected> {
+        let pkcs8 = pkcs8::decrypt(encrypted, password)
+            .map_err(|error::Unspecified| KeyRejected::decryption_failed())?;
+        Self::from_pkcs8(&pkcs8).map_err(|_| KeyRejected::decryption_failed())
+    }
+
     /// Parses an RSA private key that is not inside a PKCS#8 wrapper.
     ///
     /// The private key must be encoded as a binary DER-encoded ASN.1
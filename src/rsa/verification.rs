@@ -21,6 +21,7 @@ use crate::{
     limb::LIMB_BYTES,
     sealed, signature,
 };
+use alloc::boxed::Box;
 
 #[derive(Debug)]
 pub struct Key {
@@ -275,6 +276,74 @@ where
     }
 }
 
+/// A parsed RSA public key.
+///
+/// Parsing only checks that the key is well-formed; it doesn't check the key
+/// against any particular `RsaParameters`. This makes it possible to find out
+/// the size of the key, e.g. with `modulus_bits()`, before (or instead of)
+/// verifying a signature with it, e.g. to log why a key was rejected by a
+/// policy. `verify()` applies the same checks to the key as
+/// `ring::signature::verify()` with the same parameters.
+#[derive(Clone)]
+pub struct RsaPublicKey {
+    der: Box<[u8]>,
+    n_bits: bits::BitLength,
+}
+
+impl AsRef<[u8]> for RsaPublicKey {
+    fn as_ref(&self) -> &[u8] {
+        self.der.as_ref()
+    }
+}
+
+derive_debug_self_as_ref_hex_bytes!(RsaPublicKey);
+
+impl RsaPublicKey {
+    /// Parses a public key in the DER-encoded PKCS#1 `RSAPublicKey` format,
+    /// the same format that `ring::signature::verify()` accepts for RSA.
+    ///
+    /// No minimum or maximum modulus size is enforced here.
+    pub fn parse(der: &[u8]) -> Result<Self, error::KeyRejected> {
+        let (n, _e) = parse_public_key(untrusted::Input::from(der))
+            .map_err(|error::Unspecified| error::KeyRejected::invalid_encoding())?;
+        let n_bytes = n.big_endian_without_leading_zero();
+        let n_bits = ((n_bytes.len() - 1) * 8) + (8 - (n.first_byte().leading_zeros() as usize));
+        Ok(Self {
+            der: Box::from(der),
+            n_bits: bits::BitLength::from_usize_bits(n_bits),
+        })
+    }
+
+    /// The length of the public modulus (n), in bits.
+    pub fn modulus_bits(&self) -> usize {
+        self.n_bits.as_usize_bits()
+    }
+
+    /// Verifies that `signature` is a valid signature of `message` using `self`
+    /// as the public key. `params` determine what algorithm parameters
+    /// (padding, digest algorithm, key length range, etc.) are used in the
+    /// verification.
+    pub fn verify(
+        &self,
+        params: &RsaParameters,
+        message: &[u8],
+        signature: &[u8],
+    ) -> Result<(), error::Unspecified> {
+        let _ = cpu::features();
+        // Parsing won't fail because it succeeded in `parse()`.
+        let (n, e) = parse_public_key(untrusted::Input::from(self.as_ref()))?;
+        verify_rsa_(
+            params,
+            (
+                n.big_endian_without_leading_zero_as_input(),
+                e.big_endian_without_leading_zero_as_input(),
+            ),
+            untrusted::Input::from(message),
+            untrusted::Input::from(signature),
+        )
+    }
+}
+
 pub(crate) fn verify_rsa_(
     params: &RsaParameters,
     (n, e): (untrusted::Input, untrusted::Input),
@@ -254,24 +254,33 @@
 //! # }
 //! ```
 
-use crate::{cpu, ec, error, sealed};
+use crate::{cpu, debug, ec, error, sealed};
+
+#[cfg(feature = "alloc")]
+use crate::rand;
 
 pub use crate::ec::{
     curve25519::ed25519::{
         signing::Ed25519KeyPair,
-        verification::{EdDSAParameters, ED25519},
+        verification::{
+            Ed25519CtxParameters, Ed25519StrictParameters, EdDSAParameters, ED25519, ED25519CTX,
+            ED25519_STRICT,
+        },
         ED25519_PUBLIC_KEY_LEN,
     },
     suite_b::ecdsa::{
         signing::{
             EcdsaKeyPair, EcdsaSigningAlgorithm, ECDSA_P256_SHA256_ASN1_SIGNING,
             ECDSA_P256_SHA256_FIXED_SIGNING, ECDSA_P384_SHA384_ASN1_SIGNING,
-            ECDSA_P384_SHA384_FIXED_SIGNING,
+            ECDSA_P384_SHA384_FIXED_SIGNING, ECDSA_SECP256K1_SHA256_ASN1_SIGNING,
+            ECDSA_SECP256K1_SHA256_FIXED_SIGNING,
         },
         verification::{
             EcdsaVerificationAlgorithm, ECDSA_P256_SHA256_ASN1, ECDSA_P256_SHA256_FIXED,
             ECDSA_P256_SHA384_ASN1, ECDSA_P384_SHA256_ASN1, ECDSA_P384_SHA384_ASN1,
-            ECDSA_P384_SHA384_FIXED,
+            ECDSA_P384_SHA384_FIXED, ECDSA_SECP256K1_SHA256_ASN1,
+            ECDSA_SECP256K1_SHA256_ASN1_LOW_S, ECDSA_SECP256K1_SHA256_FIXED,
+            ECDSA_SECP256K1_SHA256_FIXED_LOW_S,
         },
     },
 };
@@ -282,7 +291,7 @@ pub use crate::rsa::{
     signing::RsaSubjectPublicKey,
 
     verification::{
-        RsaPublicKeyComponents, RSA_PKCS1_1024_8192_SHA1_FOR_LEGACY_USE_ONLY,
+        RsaPublicKey, RsaPublicKeyComponents, RSA_PKCS1_1024_8192_SHA1_FOR_LEGACY_USE_ONLY,
         RSA_PKCS1_1024_8192_SHA256_FOR_LEGACY_USE_ONLY,
         RSA_PKCS1_1024_8192_SHA512_FOR_LEGACY_USE_ONLY,
         RSA_PKCS1_2048_8192_SHA1_FOR_LEGACY_USE_ONLY, RSA_PKCS1_2048_8192_SHA256,
@@ -307,6 +316,11 @@ pub use crate::rsa::{
 };
 
 /// A public key signature returned from a signing operation.
+///
+/// Use `as_ref` to get the encoded signature as a `&[u8]`. The length of the
+/// encoding isn't necessarily fixed for a given algorithm; e.g. ASN.1-encoded
+/// ECDSA signatures vary in length, so callers must use the length of the
+/// slice returned by `as_ref` rather than assuming a fixed length.
 #[derive(Clone, Copy)]
 pub struct Signature {
     value: [u8; MAX_LEN],
@@ -326,6 +340,15 @@ impl Signature {
         r.len = fill(&mut r.value);
         r
     }
+
+    /// Returns a newly-allocated copy of the encoded signature.
+    ///
+    /// The returned vector has exactly the length of the encoded signature,
+    /// i.e. `self.as_ref().len()` bytes.
+    #[cfg(feature = "alloc")]
+    pub fn to_vec(&self) -> alloc::vec::Vec<u8> {
+        self.as_ref().to_vec()
+    }
 }
 
 impl AsRef<[u8]> for Signature {
@@ -343,6 +366,71 @@ pub trait KeyPair: core::fmt::Debug + Send + Sized + Sync {
     fn public_key(&self) -> &Self::PublicKey;
 }
 
+/// A key pair that `sign` can sign with: an `Ed25519KeyPair` or an
+/// `EcdsaKeyPair`.
+///
+/// This trait is sealed; it can't be implemented outside of *ring*.
+#[cfg(feature = "alloc")]
+pub trait SigningKeyPair: KeyPair + signing_sealed::SigningKeyPair {}
+
+#[cfg(feature = "alloc")]
+impl<T> SigningKeyPair for T where T: KeyPair + signing_sealed::SigningKeyPair {}
+
+#[cfg(feature = "alloc")]
+pub(crate) mod signing_sealed {
+    use super::Signature;
+    use crate::{error, rand};
+
+    pub trait SigningKeyPair {
+        fn sign_impl(
+            &self,
+            rng: &dyn rand::SecureRandom,
+            msg: &[u8],
+        ) -> Result<Signature, error::Unspecified>;
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl signing_sealed::SigningKeyPair for Ed25519KeyPair {
+    fn sign_impl(
+        &self,
+        _rng: &dyn rand::SecureRandom,
+        msg: &[u8],
+    ) -> Result<Signature, error::Unspecified> {
+        Ok(self.sign(msg))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl signing_sealed::SigningKeyPair for EcdsaKeyPair {
+    fn sign_impl(
+        &self,
+        rng: &dyn rand::SecureRandom,
+        msg: &[u8],
+    ) -> Result<Signature, error::Unspecified> {
+        self.sign(rng, msg)
+    }
+}
+
+/// Returns a newly-allocated signature of `msg` made with `key_pair`.
+///
+/// `rng` is used for ECDSA signing, which is randomized. Ed25519 signing is
+/// deterministic, so `rng` is ignored for an `Ed25519KeyPair`. The result is
+/// the same as `key_pair.sign(...).to_vec()`.
+///
+/// RSA isn't supported since `RsaKeyPair::sign` also needs a padding
+/// algorithm; use it directly.
+#[cfg(feature = "alloc")]
+pub fn sign<K: SigningKeyPair>(
+    key_pair: &K,
+    rng: &dyn rand::SecureRandom,
+    msg: &[u8],
+) -> Result<alloc::vec::Vec<u8>, error::Unspecified> {
+    key_pair
+        .sign_impl(rng, msg)
+        .map(|signature| signature.to_vec())
+}
+
 /// The longest signature is an ASN.1 P-384 signature where *r* and *s* are of
 /// maximum length with the leading high bit set on each. Then each component
 /// will have a tag, a one-byte length, and a one-byte “I'm not negative”
@@ -362,6 +450,42 @@ pub trait VerificationAlgorithm: core::fmt::Debug + Sync + sealed::Sealed {
     ) -> Result<(), error::Unspecified>;
 }
 
+/// Verifies `signature` of `msg` with `public_key` using each of `algorithms`
+/// in turn, returning the index of the first one for which the signature is
+/// valid.
+///
+/// This is useful when the algorithm isn't known in advance, e.g. for a token
+/// that may be signed with either ECDSA or RSA. `public_key` is interpreted
+/// according to each algorithm in turn, so it's normal for it to be
+/// malformed for most of them. Fails if the signature is valid for none of
+/// the algorithms, including when `algorithms` is empty.
+///
+/// The algorithms are always tried in the given order and the search stops at
+/// the first match, so the time taken reveals which algorithm matched and
+/// roughly how expensive the failed attempts were. That is fine when, as
+/// usual, the public key, message, and signature are all public; otherwise
+/// verify with a single, known algorithm instead.
+pub fn verify_any(
+    algorithms: &[&dyn VerificationAlgorithm],
+    public_key: &[u8],
+    msg: &[u8],
+    signature: &[u8],
+) -> Result<usize, error::Unspecified> {
+    let _ = cpu::features();
+    algorithms
+        .iter()
+        .position(|algorithm| {
+            algorithm
+                .verify(
+                    untrusted::Input::from(public_key),
+                    untrusted::Input::from(msg),
+                    untrusted::Input::from(signature),
+                )
+                .is_ok()
+        })
+        .ok_or(error::Unspecified)
+}
+
 /// An unparsed, possibly malformed, public key for signature verification.
 pub struct UnparsedPublicKey<B: AsRef<[u8]>> {
     algorithm: &'static dyn VerificationAlgorithm,
@@ -382,6 +506,18 @@ where
     }
 }
 
+impl<B: core::fmt::Debug> core::fmt::Debug for UnparsedPublicKey<B>
+where
+    B: AsRef<[u8]>,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        f.debug_struct("UnparsedPublicKey")
+            .field("algorithm", &self.algorithm)
+            .field("bytes", &debug::HexStr(self.bytes.as_ref()))
+            .finish()
+    }
+}
+
 impl<B: AsRef<[u8]>> UnparsedPublicKey<B> {
     /// Construct a new `UnparsedPublicKey`.
     ///
@@ -394,6 +530,9 @@ impl<B: AsRef<[u8]>> UnparsedPublicKey<B> {
     /// Parses the public key and verifies `signature` is a valid signature of
     /// `message` using it.
     ///
+    /// A malformed public key and an invalid signature are reported the same
+    /// way, as `error::Unspecified`.
+    ///
     /// See the [crate::signature] module-level documentation for examples.
     pub fn verify(&self, message: &[u8], signature: &[u8]) -> Result<(), error::Unspecified> {
         let _ = cpu::features();
@@ -120,8 +120,11 @@
 #[cfg(feature = "alloc")]
 use alloc::{format, string::String, vec::Vec};
 
+#[cfg(feature = "std")]
+use {crate::aead, alloc::vec};
+
 #[cfg(feature = "alloc")]
-use crate::{bits, digest, error};
+use crate::{bits, debug::from_hex_digit, digest, error};
 
 #[cfg(any(feature = "std", feature = "test_logging"))]
 extern crate std;
@@ -183,6 +186,7 @@ impl TestCase {
             "SHA384" => Some(&digest::SHA384),
             "SHA512" => Some(&digest::SHA512),
             "SHA512_256" => Some(&digest::SHA512_256),
+            "SM3" => Some(&digest::SM3),
             _ => panic!("Unsupported digest algorithm: {}", name),
         }
     }
@@ -191,8 +195,16 @@ impl TestCase {
     /// even number of hex digits, or as a double-quoted UTF-8 string. The
     /// empty (zero-length) value is represented as "".
     pub fn consume_bytes(&mut self, key: &str) -> Vec<u8> {
-        let s = self.consume_string(key);
-        if s.starts_with('\"') {
+        self.consume_optional_bytes(key)
+            .unwrap_or_else(|| panic!("No attribute named \"{}\"", key))
+    }
+
+    /// Like `consume_bytes()` except it returns `None` if the test case
+    /// doesn't have the attribute. This is useful for distinguishing an
+    /// omitted value from an empty one.
+    pub fn consume_optional_bytes(&mut self, key: &str) -> Option<Vec<u8>> {
+        let s = self.consume_optional_string(key)?;
+        let bytes = if s.starts_with('\"') {
             // The value is a quoted UTF-8 string.
 
             let mut bytes = Vec::with_capacity(s.as_bytes().len() - 2);
@@ -241,7 +253,8 @@ impl TestCase {
                     panic!("{} in {}", err_str, s);
                 }
             }
-        }
+        };
+        Some(bytes)
     }
 
     /// Returns the value of an attribute that is an integer, in decimal
@@ -260,6 +273,71 @@ impl TestCase {
         bits::BitLength::from_usize_bits(bits)
     }
 
+    /// Returns the value of an attribute that is a big-endian unsigned
+    /// integer of at most 8 bytes, encoded as for `consume_bytes()`, e.g.
+    /// `Counter = 00000001`. The empty value is zero. Panics, naming the
+    /// attribute, if the value is longer than 8 bytes.
+    pub fn consume_u64_be(&mut self, key: &str) -> u64 {
+        let bytes = self.consume_u64_bytes(key);
+        bytes.iter().fold(0, |acc, &b| (acc << 8) | u64::from(b))
+    }
+
+    /// Like `consume_u64_be()` except the value is little-endian.
+    pub fn consume_u64_le(&mut self, key: &str) -> u64 {
+        let bytes = self.consume_u64_bytes(key);
+        bytes
+            .iter()
+            .rev()
+            .fold(0, |acc, &b| (acc << 8) | u64::from(b))
+    }
+
+    fn consume_u64_bytes(&mut self, key: &str) -> Vec<u8> {
+        let bytes = self.consume_bytes(key);
+        if bytes.len() > 8 {
+            panic!(
+                "Value of attribute \"{}\" is too long for a u64: {} bytes",
+                key,
+                bytes.len()
+            );
+        }
+        bytes
+    }
+
+    /// Returns the value of an attribute that is a nonnegative integer, in
+    /// decimal notation, as a duration. The value is a number of seconds,
+    /// optionally followed by the suffix `s`, or a number of milliseconds if
+    /// followed by the suffix `ms`, e.g. `Time = 59`, `Time = 59s`, or
+    /// `Time = 59000ms`. Panics, naming the attribute and its value, if the
+    /// value can't be parsed.
+    ///
+    /// Requires the `std` feature.
+    #[cfg(feature = "std")]
+    pub fn consume_duration(&mut self, key: &str) -> std::time::Duration {
+        use std::time::Duration;
+
+        let s = self.consume_string(key);
+        let (digits, from_u64): (&str, fn(u64) -> Duration) = if s.ends_with("ms") {
+            (&s[..(s.len() - 2)], Duration::from_millis)
+        } else if s.ends_with('s') {
+            (&s[..(s.len() - 1)], Duration::from_secs)
+        } else {
+            (&s[..], Duration::from_secs)
+        };
+        let value = digits
+            .parse::<u64>()
+            .unwrap_or_else(|_| panic!("Invalid value for attribute \"{}\": {}", key, s));
+        from_u64(value)
+    }
+
+    /// Returns the value of an attribute parsed with `T::from_str`, e.g. an
+    /// enum naming one of several modes. Panics, naming the attribute and its
+    /// value, if the value can't be parsed.
+    pub fn consume_enum<T: core::str::FromStr>(&mut self, key: &str) -> T {
+        let s = self.consume_string(key);
+        s.parse::<T>()
+            .unwrap_or_else(|_| panic!("Invalid value for attribute \"{}\": {}", key, s))
+    }
+
     /// Returns the raw value of an attribute, without any unquoting or
     /// other interpretation.
     pub fn consume_string(&mut self, key: &str) -> String {
@@ -281,6 +359,48 @@ impl TestCase {
         }
         None
     }
+
+    /// Returns the raw value of an attribute, if the test case has it,
+    /// without consuming it.
+    ///
+    /// This is mostly useful for the filter given to `run_filtered()`.
+    pub fn peek_string(&self, key: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(name, _, _)| key == name)
+            .map(|(_, value, _)| value.as_ref())
+    }
+
+    /// Returns the name and raw value of the first attribute that hasn't
+    /// been consumed yet, in the order the attributes appear in the file, and
+    /// marks it consumed. Returns `None` when every attribute has been
+    /// consumed.
+    ///
+    /// This is useful when the order of the attributes is significant, e.g.
+    /// for transcript tests where each attribute is a message. It can be
+    /// mixed with the name-based `consume_*` methods.
+    pub fn next_attribute(&mut self) -> Option<(String, String)> {
+        self.attributes
+            .iter_mut()
+            .find(|(_, _, consumed)| !*consumed)
+            .map(|(name, value, consumed)| {
+                *consumed = true;
+                (name.clone(), value.clone())
+            })
+    }
+
+    /// Marks every attribute as not consumed, so that the test case can be
+    /// consumed again, e.g. to run the same test case through two different
+    /// implementations.
+    ///
+    /// The check that every attribute was consumed only considers what was
+    /// consumed after the last `reset()`, so each run must consume every
+    /// attribute.
+    pub fn reset(&mut self) {
+        for (_, _, consumed) in &mut self.attributes {
+            *consumed = false;
+        }
+    }
 }
 
 /// References a test input file.
@@ -305,53 +425,135 @@ pub struct File<'a> {
     pub contents: &'a str,
 }
 
+/// References a gzip-compressed test input file, e.g. a large set of
+/// upstream test vectors.
+///
+/// Requires the `std` and `flate2` features.
+#[cfg(all(feature = "std", feature = "flate2"))]
+#[macro_export]
+macro_rules! test_file_gz {
+    ($file_name:expr) => {
+        crate::test::GzFile {
+            file_name: $file_name,
+            contents: include_bytes!($file_name),
+        }
+    };
+}
+
+/// A gzip-compressed test input file.
+///
+/// Requires the `std` and `flate2` features.
+#[cfg(all(feature = "std", feature = "flate2"))]
+pub struct GzFile<'a> {
+    /// The name (path) of the file.
+    pub file_name: &'a str,
+
+    /// The compressed contents of the file.
+    pub contents: &'a [u8],
+}
+
+#[cfg(all(feature = "std", feature = "flate2"))]
+impl GzFile<'_> {
+    /// Decompresses the contents of the file, which must be UTF-8 text
+    /// after decompression.
+    pub fn decompress(&self) -> String {
+        use std::io::Read;
+
+        let mut contents = String::new();
+        let _ = flate2::read::GzDecoder::new(self.contents)
+            .read_to_string(&mut contents)
+            .unwrap_or_else(|e| panic!("{}: {}", self.file_name, e));
+        contents
+    }
+}
+
+/// Like `run`, but for a gzip-compressed test file. The file is decompressed
+/// in memory and then parsed exactly like an uncompressed file.
+///
+/// Requires the `std` and `flate2` features.
+#[cfg(all(feature = "std", feature = "flate2"))]
+pub fn run_gz<F>(test_file: GzFile, f: F)
+where
+    F: FnMut(&str, &mut TestCase) -> Result<(), error::Unspecified>,
+{
+    let contents = test_file.decompress();
+    run(
+        File {
+            file_name: test_file.file_name,
+            contents: &contents,
+        },
+        f,
+    )
+}
+
+/// Like `run`, but parses the test cases out of an in-memory buffer, e.g.
+/// one embedded with `include_bytes!`, instead of a `File`. `data` must be
+/// UTF-8 text in the same format as a test file.
+///
+/// Requires the `alloc` default feature to be enabled
+#[cfg(feature = "alloc")]
+pub fn from_bytes<F>(data: &[u8], f: F)
+where
+    F: FnMut(&str, &mut TestCase) -> Result<(), error::Unspecified>,
+{
+    let contents = core::str::from_utf8(data)
+        .unwrap_or_else(|e| panic!("Test data is not valid UTF-8: {}", e));
+    run(
+        File {
+            file_name: "<bytes>",
+            contents,
+        },
+        f,
+    )
+}
+
 /// Parses test cases out of the given file, calling `f` on each vector until
 /// `f` fails or until all the test vectors have been read. `f` can indicate
 /// failure either by returning `Err()` or by panicking.
 ///
 /// Requires the `alloc` default feature to be enabled
 #[cfg(feature = "alloc")]
-pub fn run<F>(test_file: File, mut f: F)
+pub fn run<F>(test_file: File, f: F)
+where
+    F: FnMut(&str, &mut TestCase) -> Result<(), error::Unspecified>,
+{
+    run_filtered(test_file, |_| true, f)
+}
+
+/// Like `run`, but only calls `f` on the test cases for which `filter`
+/// returns `true`. This is useful for focusing on one test case in a large
+/// file, e.g. with a filter like
+/// `|test_case| test_case.peek_string("Count") == Some("42")`.
+///
+/// Every test case is still parsed, so syntax errors are still detected, but
+/// the test cases that are filtered out are otherwise ignored; in particular,
+/// their attributes don't need to be consumed.
+///
+/// Requires the `alloc` default feature to be enabled
+#[cfg(feature = "alloc")]
+pub fn run_filtered<P, F>(test_file: File, filter: P, mut f: F)
 where
+    P: Fn(&TestCase) -> bool,
     F: FnMut(&str, &mut TestCase) -> Result<(), error::Unspecified>,
 {
-    let lines = &mut test_file.contents.lines();
+    let lines = &mut test_file.contents.lines().peekable();
 
     let mut current_section = String::from("");
     let mut failed = false;
 
     while let Some(mut test_case) = parse_test_case(&mut current_section, lines) {
-        let result = match f(&current_section, &mut test_case) {
-            Ok(()) => {
-                if !test_case
-                    .attributes
-                    .iter()
-                    .any(|&(_, _, consumed)| !consumed)
-                {
-                    Ok(())
-                } else {
-                    failed = true;
-                    Err("Test didn't consume all attributes.")
-                }
-            }
-            Err(error::Unspecified) => Err("Test returned Err(error::Unspecified)."),
-        };
+        if !filter(&test_case) {
+            continue;
+        }
+
+        let result = f(&current_section, &mut test_case);
+        let result = check_result(result, &test_case);
 
         if result.is_err() {
             failed = true;
         }
 
-        #[cfg(feature = "test_logging")]
-        {
-            if let Err(msg) = result {
-                std::println!("{}: {}", test_file.file_name, msg);
-
-                for (name, value, consumed) in test_case.attributes {
-                    let consumed_str = if consumed { "" } else { " (unconsumed)" };
-                    std::println!("{}{} = {}", name, consumed_str, value);
-                }
-            };
-        }
+        log_result(test_file.file_name, result, test_case);
     }
 
     if failed {
@@ -359,137 +561,862 @@ where
     }
 }
 
-/// Decode an string of hex digits into a sequence of bytes. The input must
-/// have an even number of digits.
+/// Like `run`, but passes `f` the parsed `Section` that each test case is in
+/// instead of just the section's name.
+///
+/// The `Section` is parsed once, when its header is read, and the same value
+/// is passed for every test case in the section.
+///
+/// Requires the `alloc` default feature to be enabled
 #[cfg(feature = "alloc")]
-pub fn from_hex(hex_str: &str) -> Result<Vec<u8>, String> {
-    if hex_str.len() % 2 != 0 {
-        return Err(String::from(
-            "Hex string does not have an even number of digits",
-        ));
+pub fn run_sectioned<F>(test_file: File, mut f: F)
+where
+    F: FnMut(&Section, &mut TestCase) -> Result<(), error::Unspecified>,
+{
+    let lines = &mut test_file.contents.lines().peekable();
+
+    let mut current_section = String::from("");
+    let mut section = Section::new(&current_section);
+    let mut failed = false;
+
+    while let Some(mut test_case) = parse_test_case(&mut current_section, lines) {
+        if section.name != current_section {
+            section = Section::new(&current_section);
+        }
+
+        let result = f(&section, &mut test_case);
+        let result = check_result(result, &test_case);
+
+        if result.is_err() {
+            failed = true;
+        }
+
+        log_result(test_file.file_name, result, test_case);
     }
 
-    let mut result = Vec::with_capacity(hex_str.len() / 2);
-    for digits in hex_str.as_bytes().chunks(2) {
-        let hi = from_hex_digit(digits[0])?;
-        let lo = from_hex_digit(digits[1])?;
-        result.push((hi * 0x10) | lo);
+    if failed {
+        panic!("Test failed.")
     }
-    Ok(result)
 }
 
+/// A section of a test file, as passed to the callback of `run_sectioned`.
+///
+/// A section header like `[L = 20]` or `[mod = 2048, SHA = SHA256]` has the
+/// name `L = 20` or `mod = 2048, SHA = SHA256`. Its name is also parsed as a
+/// comma-separated list of `Key = Value` attributes; parts of the name that
+/// aren't of that form are ignored, but are still part of the name.
 #[cfg(feature = "alloc")]
-fn from_hex_digit(d: u8) -> Result<u8, String> {
-    use core::ops::RangeInclusive;
-    const DECIMAL: (u8, RangeInclusive<u8>) = (0, b'0'..=b'9');
-    const HEX_LOWER: (u8, RangeInclusive<u8>) = (10, b'a'..=b'f');
-    const HEX_UPPER: (u8, RangeInclusive<u8>) = (10, b'A'..=b'F');
-    for (offset, range) in &[DECIMAL, HEX_LOWER, HEX_UPPER] {
-        if range.contains(&d) {
-            return Ok(d - range.start() + offset);
-        }
-    }
-    Err(format!("Invalid hex digit '{}'", d as char))
+#[derive(Debug)]
+pub struct Section {
+    name: String,
+    attributes: Vec<(String, String)>,
 }
 
 #[cfg(feature = "alloc")]
-fn parse_test_case(
-    current_section: &mut String,
-    lines: &mut dyn Iterator<Item = &str>,
-) -> Option<TestCase> {
-    let mut attributes = Vec::new();
+impl Section {
+    fn new(name: &str) -> Self {
+        let attributes = name
+            .split(',')
+            .filter_map(|part| {
+                let parts: Vec<&str> = part.splitn(2, '=').collect();
+                if parts.len() != 2 {
+                    return None;
+                }
+                Some((String::from(parts[0].trim()), String::from(parts[1].trim())))
+            })
+            .collect();
+        Self {
+            name: String::from(name),
+            attributes,
+        }
+    }
 
-    let mut is_first_line = true;
-    loop {
-        let line = lines.next();
+    /// The name of the section, i.e. its header without the brackets. This
+    /// is empty for test cases that precede the first section header.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
 
-        #[cfg(feature = "test_logging")]
-        {
-            if let Some(text) = &line {
-                std::println!("Line: {}", text);
-            }
-        }
+    /// Returns the value of the attribute with the given key, if any.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
 
-        match line {
-            // If we get to EOF when we're not in the middle of a test case,
-            // then we're done.
-            None if is_first_line => {
-                return None;
-            }
+    /// Returns the value of the attribute with the given key as a `usize`,
+    /// panicking if it's missing or isn't a valid `usize`.
+    pub fn get_usize(&self, key: &str) -> usize {
+        let value = self
+            .get(key)
+            .unwrap_or_else(|| panic!("No attribute named \"{}\" in section [{}]", key, self.name));
+        value.parse().unwrap_or_else(|_| {
+            panic!(
+                "Invalid value for attribute \"{}\" in section [{}]",
+                key, self.name
+            )
+        })
+    }
+}
 
-            // End of the file on a non-empty test cases ends the test case.
-            None => {
-                return Some(TestCase { attributes });
-            }
+/// Like `run`, but runs the test cases in parallel on a fixed number of
+/// threads.
+///
+/// All of the test cases are parsed before any of them are run. Each test
+/// case is run exactly once, but the order in which they're run is
+/// unspecified. A test case that panics doesn't stop the other test cases
+/// from running; it's counted as a failure like a test case that returns
+/// `Err()`. Failures are reported in the order the test cases appear in the
+/// file, and then `run_parallel` panics if any test case failed.
+///
+/// Requires the `std` feature.
+#[cfg(feature = "std")]
+pub fn run_parallel<F>(test_file: File, f: F)
+where
+    F: Fn(&str, &mut TestCase) -> Result<(), error::Unspecified> + Send + Sync + 'static,
+{
+    use std::{panic, sync::Arc, thread};
 
-            // A blank line ends a test case if the test case isn't empty.
-            Some(ref line) if line.is_empty() => {
-                if !is_first_line {
-                    return Some(TestCase { attributes });
-                }
-                // Ignore leading blank lines.
-            }
+    const NUM_THREADS: usize = 8;
 
-            // Comments start with '#'; ignore them.
-            Some(ref line) if line.starts_with('#') => (),
+    let lines = &mut test_file.contents.lines().peekable();
+    let mut current_section = String::from("");
+    let mut queues: Vec<Vec<(usize, String, TestCase)>> =
+        (0..NUM_THREADS).map(|_| Vec::new()).collect();
+    let mut num_test_cases = 0;
+    while let Some(test_case) = parse_test_case(&mut current_section, lines) {
+        queues[num_test_cases % NUM_THREADS].push((
+            num_test_cases,
+            current_section.clone(),
+            test_case,
+        ));
+        num_test_cases += 1;
+    }
 
-            Some(ref line) if line.starts_with('[') => {
-                assert!(is_first_line);
-                assert!(line.ends_with(']'));
-                current_section.truncate(0);
-                current_section.push_str(line);
-                let _ = current_section.pop();
-                let _ = current_section.remove(0);
-            }
+    let f = Arc::new(f);
+    let threads: Vec<_> = queues
+        .into_iter()
+        .map(|queue| {
+            let f = Arc::clone(&f);
+            thread::spawn(move || {
+                queue
+                    .into_iter()
+                    .map(|(i, section, mut test_case)| {
+                        let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+                            f(&section, &mut test_case)
+                        }));
+                        let result = match result {
+                            Ok(result) => check_result(result, &test_case),
+                            Err(_) => Err("Test panicked."),
+                        };
+                        (i, result, test_case)
+                    })
+                    .collect::<Vec<_>>()
+            })
+        })
+        .collect();
+
+    let mut results = Vec::with_capacity(num_test_cases);
+    for thread in threads {
+        results.extend(thread.join().unwrap());
+    }
+    results.sort_by_key(|&(i, _, _)| i);
 
-            Some(ref line) => {
-                is_first_line = false;
+    let mut failed = false;
+    for (_, result, test_case) in results {
+        if result.is_err() {
+            failed = true;
+        }
+        log_result(test_file.file_name, result, test_case);
+    }
 
-                let parts: Vec<&str> = line.splitn(2, " = ").collect();
-                if parts.len() != 2 {
-                    panic!("Syntax error: Expected Key = Value.");
-                };
+    if failed {
+        panic!("Test failed.")
+    }
+}
 
-                let key = parts[0].trim();
-                let value = parts[1].trim();
+/// Like `run`, but instead of failing, appends each failing test case to the
+/// file at `fail_out` so that it can be triaged separately, e.g. when
+/// importing a large set of test vectors that includes cases for unsupported
+/// options.
+///
+/// A test case fails in the same situations as in `run`, including when `f`
+/// panics. Each failing test case is written with its original attributes,
+/// preceded by its section header when that differs from the previous
+/// failing test case's, so `fail_out` is itself a valid test file. The file
+/// is created if it doesn't exist. Returns the number of quarantined test
+/// cases.
+///
+/// Requires the `std` feature.
+#[cfg(feature = "std")]
+pub fn run_quarantine<F>(test_file: File, fail_out: &str, mut f: F) -> usize
+where
+    F: FnMut(&str, &mut TestCase) -> Result<(), error::Unspecified>,
+{
+    use std::{fs, io::Write, panic};
 
-                // Don't allow the value to be ommitted. An empty value can be
-                // represented as an empty quoted string.
-                assert_ne!(value.len(), 0);
+    let mut out = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(fail_out)
+        .unwrap_or_else(|e| panic!("{}: {}", fail_out, e));
 
-                // Checking is_none() ensures we don't accept duplicate keys.
-                attributes.push((String::from(key), String::from(value), false));
-            }
+    let lines = &mut test_file.contents.lines().peekable();
+    let mut current_section = String::from("");
+    let mut last_written_section = String::from("");
+    let mut num_quarantined = 0;
+
+    while let Some(mut test_case) = parse_test_case(&mut current_section, lines) {
+        let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+            f(&current_section, &mut test_case)
+        }));
+        let result = match result {
+            Ok(result) => check_result(result, &test_case),
+            Err(_) => Err("Test panicked."),
+        };
+        if result.is_ok() {
+            continue;
+        }
+
+        let mut text = String::new();
+        if current_section != last_written_section {
+            text.push_str(&format!("[{}]\n\n", current_section));
+            last_written_section.clone_from(&current_section);
+        }
+        for (name, value, _) in &test_case.attributes {
+            text.push_str(&format!("{} = {}\n", name, value));
         }
+        text.push('\n');
+        out.write_all(text.as_bytes())
+            .unwrap_or_else(|e| panic!("{}: {}", fail_out, e));
+        num_quarantined += 1;
+
+        log_result(test_file.file_name, result, test_case);
     }
+
+    num_quarantined
 }
 
-/// Deterministic implementations of `ring::rand::SecureRandom`.
+/// The results of running a test file with `from_file_json`.
 ///
-/// These implementations are particularly useful for testing implementations
-/// of randomized algorithms & protocols using known-answer-tests where the
-/// test vectors contain the random seed to use. They are also especially
-/// useful for some types of fuzzing.
-#[doc(hidden)]
-pub mod rand {
-    use crate::{error, polyfill, rand};
+/// Requires the `std` feature.
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, PartialEq)]
+pub struct TestReport {
+    /// The name (path) of the test file.
+    pub file_name: String,
 
-    /// An implementation of `SecureRandom` that always fills the output slice
-    /// with the given byte.
-    #[derive(Debug)]
-    pub struct FixedByteRandom {
-        pub byte: u8,
+    /// The number of test cases in the file.
+    pub total: usize,
+
+    /// The number of test cases that passed.
+    pub passed: usize,
+
+    /// The number of test cases that failed.
+    pub failed: usize,
+
+    /// The number of test cases that were skipped, i.e. for which `f`
+    /// returned `Ok(())` without consuming any attributes.
+    pub skipped: usize,
+
+    /// The test cases that failed, in the order they appear in the file.
+    pub failures: Vec<TestFailure>,
+}
+
+/// A failed test case in a `TestReport`.
+///
+/// Requires the `std` feature.
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, PartialEq)]
+pub struct TestFailure {
+    /// The zero-based index of the test case in the file, counting the test
+    /// cases in all sections.
+    pub index: usize,
+
+    /// The name of the section that the test case is in, or "" if none.
+    pub section: String,
+
+    /// Why the test case failed.
+    pub message: &'static str,
+
+    /// The attributes of the test case, as (name, value) pairs in the order
+    /// they appear in the file.
+    pub attributes: Vec<(String, String)>,
+}
+
+#[cfg(feature = "std")]
+impl TestReport {
+    /// Encodes the report as a JSON object with the keys "file_name",
+    /// "total", "passed", "failed", "skipped", and "failures", mirroring the
+    /// fields of `TestReport`. Each element of "failures" is an object with
+    /// the keys "index", "section", "message", and "attributes"; the
+    /// attributes are an array of `[name, value]` pairs.
+    pub fn to_json(&self) -> String {
+        let mut out = String::from("{\"file_name\":");
+        write_json_string(&mut out, &self.file_name);
+        out.push_str(&format!(
+            ",\"total\":{},\"passed\":{},\"failed\":{},\"skipped\":{},\"failures\":[",
+            self.total, self.passed, self.failed, self.skipped
+        ));
+        for (i, failure) in self.failures.iter().enumerate() {
+            if i != 0 {
+                out.push(',');
+            }
+            out.push_str(&format!("{{\"index\":{},\"section\":", failure.index));
+            write_json_string(&mut out, &failure.section);
+            out.push_str(",\"message\":");
+            write_json_string(&mut out, failure.message);
+            out.push_str(",\"attributes\":[");
+            for (j, (name, value)) in failure.attributes.iter().enumerate() {
+                if j != 0 {
+                    out.push(',');
+                }
+                out.push('[');
+                write_json_string(&mut out, name);
+                out.push(',');
+                write_json_string(&mut out, value);
+                out.push(']');
+            }
+            out.push_str("]}");
+        }
+        out.push_str("]}");
+        out
     }
+}
 
-    impl rand::sealed::SecureRandom for FixedByteRandom {
-        fn fill_impl(&self, dest: &mut [u8]) -> Result<(), error::Unspecified> {
-            polyfill::slice::fill(dest, self.byte);
-            Ok(())
+#[cfg(feature = "std")]
+fn write_json_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
         }
     }
+    out.push('"');
+}
 
-    /// An implementation of `SecureRandom` that always fills the output slice
-    /// with the slice in `bytes`. The length of the slice given to `slice`
+/// Like `run`, but instead of panicking when a test case fails, returns a
+/// `TestReport` of the results so that the caller can decide what to do with
+/// them, e.g. publish `TestReport::to_json()` to a dashboard.
+///
+/// A test case fails in the same situations as in `run`, including when `f`
+/// panics, except that a test case for which `f` returns `Ok(())` without
+/// consuming any attributes is counted as skipped. A syntax error in the file
+/// still panics.
+///
+/// Requires the `std` feature.
+#[cfg(feature = "std")]
+pub fn from_file_json<F>(test_file: File, mut f: F) -> TestReport
+where
+    F: FnMut(&str, &mut TestCase) -> Result<(), error::Unspecified>,
+{
+    use std::panic;
+
+    let mut report = TestReport {
+        file_name: String::from(test_file.file_name),
+        total: 0,
+        passed: 0,
+        failed: 0,
+        skipped: 0,
+        failures: Vec::new(),
+    };
+
+    let lines = &mut test_file.contents.lines().peekable();
+    let mut current_section = String::from("");
+
+    while let Some(mut test_case) = parse_test_case(&mut current_section, lines) {
+        let index = report.total;
+        report.total += 1;
+
+        let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+            f(&current_section, &mut test_case)
+        }));
+        let result = match result {
+            Ok(Ok(()))
+                if test_case
+                    .attributes
+                    .iter()
+                    .all(|&(_, _, consumed)| !consumed) =>
+            {
+                report.skipped += 1;
+                continue;
+            }
+            Ok(result) => check_result(result, &test_case),
+            Err(_) => Err("Test panicked."),
+        };
+        match result {
+            Ok(()) => report.passed += 1,
+            Err(message) => {
+                report.failed += 1;
+                report.failures.push(TestFailure {
+                    index,
+                    section: current_section.clone(),
+                    message,
+                    attributes: test_case
+                        .attributes
+                        .iter()
+                        .map(|(name, value, _)| (name.clone(), value.clone()))
+                        .collect(),
+                });
+            }
+        }
+
+        log_result(test_file.file_name, result, test_case);
+    }
+
+    report
+}
+
+#[cfg(feature = "alloc")]
+fn check_result(
+    result: Result<(), error::Unspecified>,
+    test_case: &TestCase,
+) -> Result<(), &'static str> {
+    match result {
+        Ok(()) => {
+            if !test_case
+                .attributes
+                .iter()
+                .any(|&(_, _, consumed)| !consumed)
+            {
+                Ok(())
+            } else {
+                Err("Test didn't consume all attributes.")
+            }
+        }
+        Err(error::Unspecified) => Err("Test returned Err(error::Unspecified)."),
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[allow(unused_variables)]
+fn log_result(file_name: &str, result: Result<(), &'static str>, test_case: TestCase) {
+    #[cfg(feature = "test_logging")]
+    {
+        if let Err(msg) = result {
+            std::println!("{}: {}", file_name, msg);
+
+            for (name, value, consumed) in test_case.attributes {
+                let consumed_str = if consumed { "" } else { " (unconsumed)" };
+                std::println!("{}{} = {}", name, consumed_str, value);
+            }
+        };
+    }
+}
+
+/// Decode an string of hex digits into a sequence of bytes. ASCII whitespace
+/// is ignored, so grouped hex like "de ad be ef" decodes the same as
+/// "deadbeef". The input must have an even number of digits.
+#[cfg(feature = "alloc")]
+pub fn from_hex(hex_str: &str) -> Result<Vec<u8>, String> {
+    let hex: Vec<u8> = hex_str
+        .bytes()
+        .filter(|b| !b.is_ascii_whitespace())
+        .collect();
+    if hex.len() % 2 != 0 {
+        return Err(String::from(
+            "Hex string does not have an even number of digits",
+        ));
+    }
+
+    let from_hex_digit =
+        |d: u8| from_hex_digit(d).map_err(|_| format!("Invalid hex digit '{}'", d as char));
+
+    let mut result = Vec::with_capacity(hex.len() / 2);
+    for digits in hex.chunks(2) {
+        let hi = from_hex_digit(digits[0])?;
+        let lo = from_hex_digit(digits[1])?;
+        result.push((hi * 0x10) | lo);
+    }
+    Ok(result)
+}
+
+/// Panics if `expected` and `actual` aren't equal, with a message showing the
+/// offset of the first differing byte and a side-by-side hex dump of the
+/// expected and actual values, starting at the row containing that byte.
+///
+/// This is an alternative to `assert_eq!` for comparing outputs to test
+/// vectors, where the `Debug` output of two long `Vec<u8>`s is hard to
+/// compare by eye.
+#[cfg(feature = "alloc")]
+pub fn assert_bytes_eq(expected: &[u8], actual: &[u8]) {
+    if let Some(msg) = describe_bytes_difference(expected, actual) {
+        panic!("{}", msg);
+    }
+}
+
+#[cfg(feature = "alloc")]
+fn describe_bytes_difference(expected: &[u8], actual: &[u8]) -> Option<String> {
+    use core::fmt::Write;
+
+    const BYTES_PER_ROW: usize = 16;
+    const MAX_ROWS: usize = 8;
+
+    let first_difference = match expected.iter().zip(actual.iter()).position(|(e, a)| e != a) {
+        Some(offset) => offset,
+        None if expected.len() == actual.len() => {
+            return None;
+        }
+        None => core::cmp::min(expected.len(), actual.len()),
+    };
+
+    fn write_row(out: &mut String, bytes: &[u8], start: usize) {
+        for i in start..(start + BYTES_PER_ROW) {
+            match bytes.get(i) {
+                Some(b) => write!(out, "{:02x}", b).unwrap(),
+                None => out.push_str("  "),
+            }
+        }
+    }
+
+    let mut msg = format!(
+        "Bytes differ starting at offset {} (expected {} bytes, got {} bytes):\n",
+        first_difference,
+        expected.len(),
+        actual.len()
+    );
+    msg.push_str("offset    expected                          actual\n");
+    let len = core::cmp::max(expected.len(), actual.len());
+    let first_row = first_difference - (first_difference % BYTES_PER_ROW);
+    let mut start = first_row;
+    while start < len && start < first_row + (MAX_ROWS * BYTES_PER_ROW) {
+        write!(msg, "{:08x}  ", start).unwrap();
+        write_row(&mut msg, expected, start);
+        msg.push_str("  ");
+        write_row(&mut msg, actual, start);
+        let end = start + BYTES_PER_ROW;
+        if expected.get(start..core::cmp::min(end, expected.len()))
+            != actual.get(start..core::cmp::min(end, actual.len()))
+        {
+            msg.push_str("  <");
+        }
+        msg.push('\n');
+        start = end;
+    }
+    if start < len {
+        msg.push_str("...\n");
+    }
+    Some(msg)
+}
+
+#[cfg(feature = "alloc")]
+fn parse_test_case(
+    current_section: &mut String,
+    lines: &mut core::iter::Peekable<core::str::Lines>,
+) -> Option<TestCase> {
+    let mut attributes = Vec::new();
+
+    let mut is_first_line = true;
+    loop {
+        // A section header ends the current test case even without a blank
+        // line before it. The header is left for the next call so that the
+        // current test case is still reported in the previous section.
+        if !is_first_line {
+            if let Some(line) = lines.peek() {
+                if line.starts_with('[') {
+                    return Some(TestCase { attributes });
+                }
+            }
+        }
+
+        // `str::lines()` strips "\r\n" but not a bare "\r" at the very end
+        // of the input, which occurs when a file with CRLF line endings lacks
+        // a final newline. Strip it so that the last line is treated like
+        // every other line.
+        let line = lines.next().map(|line| line.trim_end_matches('\r'));
+
+        #[cfg(feature = "test_logging")]
+        {
+            if let Some(text) = &line {
+                std::println!("Line: {}", text);
+            }
+        }
+
+        match line {
+            // If we get to EOF when we're not in the middle of a test case,
+            // then we're done.
+            None if is_first_line => {
+                return None;
+            }
+
+            // End of the file on a non-empty test cases ends the test case,
+            // even if the last line of the file isn't terminated by a newline
+            // or followed by a blank line.
+            None => {
+                return Some(TestCase { attributes });
+            }
+
+            // A blank line ends a test case if the test case isn't empty.
+            Some(ref line) if line.is_empty() => {
+                if !is_first_line {
+                    return Some(TestCase { attributes });
+                }
+                // Ignore leading blank lines.
+            }
+
+            // Comments start with '#'; ignore them.
+            Some(ref line) if line.starts_with('#') => (),
+
+            Some(ref line) if line.starts_with('[') => {
+                assert!(is_first_line);
+                assert!(line.ends_with(']'));
+                current_section.truncate(0);
+                current_section.push_str(line);
+                let _ = current_section.pop();
+                let _ = current_section.remove(0);
+            }
+
+            Some(ref line) => {
+                is_first_line = false;
+
+                let parts: Vec<&str> = line.splitn(2, " = ").collect();
+                if parts.len() != 2 {
+                    panic!("Syntax error: Expected Key = Value.");
+                };
+
+                let key = parts[0].trim();
+                let value = parts[1].trim();
+
+                // Don't allow the value to be ommitted. An empty value can be
+                // represented as an empty quoted string.
+                assert_ne!(value.len(), 0);
+
+                // Checking is_none() ensures we don't accept duplicate keys.
+                attributes.push((String::from(key), String::from(value), false));
+            }
+        }
+    }
+}
+
+/// The throughput of an AEAD algorithm for one input length, as measured by
+/// `bench_aead`.
+///
+/// Requires the `std` feature.
+#[cfg(feature = "std")]
+#[derive(Clone, Copy, Debug)]
+pub struct AeadThroughput {
+    /// The length of each input, in bytes.
+    pub input_len: usize,
+
+    /// Sealing throughput, in bytes per second.
+    pub seal_bytes_per_second: f64,
+
+    /// Opening throughput, in bytes per second.
+    pub open_bytes_per_second: f64,
+}
+
+/// The result of `bench_aead`.
+///
+/// Requires the `std` feature.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct BenchResult {
+    /// The algorithm that was measured.
+    pub algorithm: &'static aead::Algorithm,
+
+    /// The measurements, in the order of the `sizes` given to `bench_aead`.
+    pub throughputs: Vec<AeadThroughput>,
+}
+
+/// Measures the sealing and opening throughput of `algorithm` for inputs of
+/// each of the given sizes.
+///
+/// The key is generated deterministically using `rand::FixedByteRandom` so
+/// that results are reproducible. Each size is processed enough times to
+/// cover at least 1MiB of input (and at least once) so that per-call
+/// overhead doesn't dominate the measurements of small inputs.
+///
+/// Requires the `std` feature.
+#[cfg(feature = "std")]
+pub fn bench_aead(algorithm: &'static aead::Algorithm, sizes: &[usize]) -> BenchResult {
+    use crate::rand::SecureRandom;
+
+    const MIN_TOTAL_LEN: usize = 1 << 20;
+
+    let rng = rand::FixedByteRandom { byte: 0x42 };
+    let mut key_bytes = [0u8; 32];
+    let key_bytes = &mut key_bytes[..algorithm.key_len()];
+    rng.fill(key_bytes).unwrap();
+    let key = aead::LessSafeKey::new(aead::UnboundKey::new(algorithm, key_bytes).unwrap());
+
+    let nonce = |i: u64| {
+        let mut nonce = [0u8; aead::NONCE_LEN];
+        nonce[4..].copy_from_slice(&i.to_be_bytes());
+        aead::Nonce::assume_unique_for_key(nonce)
+    };
+
+    let bytes_per_second = |len: usize, iterations: usize, elapsed: std::time::Duration| {
+        let secs = (elapsed.as_secs() as f64) + f64::from(elapsed.subsec_nanos()) / 1e9;
+        ((len as f64) * (iterations as f64)) / secs.max(core::f64::MIN_POSITIVE)
+    };
+
+    let throughputs = sizes
+        .iter()
+        .map(|&input_len| {
+            let iterations = core::cmp::max(1, MIN_TOTAL_LEN / core::cmp::max(1, input_len));
+            let mut in_out = vec![0u8; input_len];
+
+            let start = std::time::Instant::now();
+            for i in 0..iterations {
+                let _ = key
+                    .seal_in_place_separate_tag(nonce(i as u64), aead::Aad::empty(), &mut in_out)
+                    .unwrap();
+            }
+            let seal_elapsed = start.elapsed();
+
+            let mut sealed = vec![0u8; input_len];
+            key.seal_in_place_append_tag(nonce(0), aead::Aad::empty(), &mut sealed)
+                .unwrap();
+
+            let start = std::time::Instant::now();
+            for _ in 0..iterations {
+                in_out.clear();
+                in_out.extend_from_slice(&sealed);
+                let _ = key
+                    .open_in_place(nonce(0), aead::Aad::empty(), &mut in_out)
+                    .unwrap();
+            }
+            let open_elapsed = start.elapsed();
+
+            AeadThroughput {
+                input_len,
+                seal_bytes_per_second: bytes_per_second(input_len, iterations, seal_elapsed),
+                open_bytes_per_second: bytes_per_second(input_len, iterations, open_elapsed),
+            }
+        })
+        .collect();
+
+    BenchResult {
+        algorithm,
+        throughputs,
+    }
+}
+
+/// Panics if the running time of `f` appears to depend on its input.
+///
+/// `f` is called with `CONSTANT_TIME_INPUT_LEN`-byte inputs from two classes:
+/// all zeros, and random. Typically `f` compares its input with a secret
+/// that differs from all zeros only in its last byte, so that a comparison
+/// that stops at the first difference takes longer for the all-zero inputs
+/// while `f` returns `false` for both classes. (When `f`'s result differs
+/// between the classes, the code that handles the result may cause a timing
+/// difference of its own, especially in debug builds.) The classes are
+/// interleaved randomly over `samples` timing samples, each of which times a
+/// batch of calls, and the slowest tenth of the samples is discarded to
+/// reduce the effect of interrupts and other noise. This panics if Welch's
+/// t-test finds a difference between the classes with
+/// |t| > `CONSTANT_TIME_T_THRESHOLD`.
+///
+/// This is a heuristic in the style of dudect, not a proof: it can only
+/// detect leaks that show up on the machine and build it runs on, and it
+/// says nothing about other inputs, compilers, or CPUs. Results from debug
+/// builds say little about release builds.
+///
+/// Requires the `std` feature.
+#[cfg(feature = "std")]
+pub fn assert_constant_time<F: Fn(&[u8]) -> bool>(f: F, samples: usize) {
+    use crate::rand::{SecureRandom, SystemRandom};
+
+    const BATCH_LEN: usize = 16;
+
+    assert!(samples > 0, "Not enough samples.");
+    let rng = SystemRandom::new();
+    let fixed = [0u8; CONSTANT_TIME_INPUT_LEN];
+    let mut random = [0u8; CONSTANT_TIME_INPUT_LEN];
+    let mut classes = vec![0u8; samples];
+    rng.fill(&mut classes).unwrap();
+
+    let mut timings: [Vec<u64>; 2] = [Vec::with_capacity(samples), Vec::with_capacity(samples)];
+    let mut num_true = 0usize;
+    for class in classes.iter().map(|b| usize::from(b & 1)) {
+        rng.fill(&mut random).unwrap();
+        let input = if class == 0 { &fixed } else { &random };
+
+        let start = std::time::Instant::now();
+        for _ in 0..BATCH_LEN {
+            num_true += usize::from(f(input));
+        }
+        let elapsed = start.elapsed();
+
+        timings[class].push(elapsed.as_secs() * 1_000_000_000 + u64::from(elapsed.subsec_nanos()));
+    }
+    // Keep the results of `f` alive so its calls can't be optimized away.
+    let _ = unsafe { core::ptr::read_volatile(&num_true) };
+
+    // Discard the outliers of both classes, using a common cutoff.
+    let mut all: Vec<u64> = timings.iter().flat_map(|t| t.iter().cloned()).collect();
+    all.sort();
+    let cutoff = all[all.len() * 9 / 10];
+
+    let stats = |timings: &[u64]| {
+        let kept: Vec<f64> = timings
+            .iter()
+            .filter(|&&t| t <= cutoff)
+            .map(|&t| t as f64)
+            .collect();
+        assert!(kept.len() >= 2, "Not enough samples.");
+        let n = kept.len() as f64;
+        let mean = kept.iter().sum::<f64>() / n;
+        let variance = kept.iter().map(|t| (t - mean) * (t - mean)).sum::<f64>() / (n - 1.0);
+        (n, mean, variance)
+    };
+    let (n0, mean0, var0) = stats(&timings[0]);
+    let (n1, mean1, var1) = stats(&timings[1]);
+
+    let se = (var0 / n0 + var1 / n1).sqrt();
+    let t = if se > 0.0 { (mean0 - mean1) / se } else { 0.0 };
+    assert!(
+        t.abs() <= CONSTANT_TIME_T_THRESHOLD,
+        "Timing leak detected: t = {:.2}; mean batch times {:.1}ns (fixed) vs {:.1}ns (random).",
+        t,
+        mean0,
+        mean1
+    );
+}
+
+/// The length of the inputs that `assert_constant_time` passes to the
+/// function being tested.
+#[cfg(feature = "std")]
+pub const CONSTANT_TIME_INPUT_LEN: usize = 32;
+
+/// The |t| statistic above which `assert_constant_time` reports a leak. This
+/// is the threshold dudect uses for "definitely not constant time".
+#[cfg(feature = "std")]
+pub const CONSTANT_TIME_T_THRESHOLD: f64 = 10.0;
+
+/// Deterministic implementations of `ring::rand::SecureRandom`.
+///
+/// These implementations are particularly useful for testing implementations
+/// of randomized algorithms & protocols using known-answer-tests where the
+/// test vectors contain the random seed to use. They are also especially
+/// useful for some types of fuzzing.
+#[doc(hidden)]
+pub mod rand {
+    use crate::{error, polyfill, rand};
+
+    /// An implementation of `SecureRandom` that always fills the output slice
+    /// with the given byte.
+    #[derive(Debug)]
+    pub struct FixedByteRandom {
+        pub byte: u8,
+    }
+
+    impl rand::sealed::SecureRandom for FixedByteRandom {
+        fn fill_impl(&self, dest: &mut [u8]) -> Result<(), error::Unspecified> {
+            polyfill::slice::fill(dest, self.byte);
+            Ok(())
+        }
+    }
+
+    /// An implementation of `SecureRandom` that always fills the output slice
+    /// with the slice in `bytes`. The length of the slice given to `slice`
     /// must match exactly.
     #[derive(Debug)]
     pub struct FixedSliceRandom<'a> {
@@ -538,11 +1465,215 @@ pub mod rand {
             assert_eq!(unsafe { *self.current.get() }, self.bytes.len());
         }
     }
+
+    /// An implementation of `SecureRandom` that hands out the bytes of `bytes`
+    /// sequentially across calls to `fill()`, regardless of the lengths
+    /// requested. *Not thread-safe.*
+    ///
+    /// This is useful for fuzzing, where the fuzzer's input can be turned into
+    /// the random stream so that operations become deterministic functions of
+    /// the input. `pos` is the position of the next byte to hand out and
+    /// should be initialized to zero.
+    ///
+    /// When there are fewer than the requested number of bytes remaining,
+    /// `fill()` fails without consuming anything, unless `wrap` is true, in
+    /// which case it continues from the start of `bytes`. `fill()` always
+    /// fails if `bytes` is empty and the output isn't.
+    #[derive(Debug)]
+    pub struct SliceCursorRandom<'a> {
+        pub bytes: &'a [u8],
+        pub pos: core::cell::Cell<usize>,
+        pub wrap: bool,
+    }
+
+    impl<'a> SliceCursorRandom<'a> {
+        /// Constructs a `SliceCursorRandom` that fails when `bytes` is
+        /// exhausted.
+        pub fn new(bytes: &'a [u8]) -> Self {
+            Self {
+                bytes,
+                pos: core::cell::Cell::new(0),
+                wrap: false,
+            }
+        }
+
+        /// Constructs a `SliceCursorRandom` that starts over from the start of
+        /// `bytes` when it is exhausted.
+        pub fn new_wrapping(bytes: &'a [u8]) -> Self {
+            Self {
+                wrap: true,
+                ..Self::new(bytes)
+            }
+        }
+
+        /// The number of bytes remaining before `bytes` is exhausted (or
+        /// before wrapping around).
+        pub fn remaining(&self) -> usize {
+            self.bytes.len() - self.pos.get()
+        }
+    }
+
+    impl rand::sealed::SecureRandom for SliceCursorRandom<'_> {
+        fn fill_impl(&self, dest: &mut [u8]) -> Result<(), error::Unspecified> {
+            if dest.is_empty() {
+                return Ok(());
+            }
+            if !self.wrap || self.bytes.is_empty() {
+                if dest.len() > self.remaining() {
+                    return Err(error::Unspecified);
+                }
+                let pos = self.pos.get();
+                dest.copy_from_slice(&self.bytes[pos..][..dest.len()]);
+                self.pos.set(pos + dest.len());
+                return Ok(());
+            }
+
+            let mut dest = dest;
+            while !dest.is_empty() {
+                let pos = self.pos.get();
+                let n = core::cmp::min(dest.len(), self.bytes.len() - pos);
+                let (chunk, rest) = dest.split_at_mut(n);
+                chunk.copy_from_slice(&self.bytes[pos..][..n]);
+                self.pos.set((pos + n) % self.bytes.len());
+                dest = rest;
+            }
+            Ok(())
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::{error, test};
+    use alloc::{string::String, vec::Vec};
+
+    #[test]
+    fn slice_cursor_random() {
+        use crate::rand::SecureRandom;
+
+        let rng = test::rand::SliceCursorRandom::new(&[1, 2, 3, 4, 5]);
+        let mut a = [0u8; 2];
+        rng.fill(&mut a).unwrap();
+        assert_eq!(a, [1, 2]);
+        rng.fill(&mut []).unwrap();
+        let mut b = [0u8; 4];
+        assert!(rng.fill(&mut b).is_err());
+        assert_eq!(rng.remaining(), 3);
+        let mut c = [0u8; 3];
+        rng.fill(&mut c).unwrap();
+        assert_eq!(c, [3, 4, 5]);
+        assert_eq!(rng.remaining(), 0);
+        assert!(rng.fill(&mut [0u8; 1]).is_err());
+    }
+
+    #[test]
+    fn slice_cursor_random_wrapping() {
+        use crate::rand::SecureRandom;
+
+        let rng = test::rand::SliceCursorRandom::new_wrapping(&[1, 2, 3]);
+        let mut a = [0u8; 2];
+        rng.fill(&mut a).unwrap();
+        assert_eq!(a, [1, 2]);
+        let mut b = [0u8; 7];
+        rng.fill(&mut b).unwrap();
+        assert_eq!(b, [3, 1, 2, 3, 1, 2, 3]);
+        assert_eq!(rng.remaining(), 3);
+
+        let empty = test::rand::SliceCursorRandom::new_wrapping(&[]);
+        empty.fill(&mut []).unwrap();
+        assert!(empty.fill(&mut [0u8; 1]).is_err());
+    }
+
+    #[test]
+    fn from_hex() {
+        assert!(test::from_hex("").unwrap().is_empty());
+        assert_eq!(
+            test::from_hex("deadBEEF").unwrap(),
+            [0xde, 0xad, 0xbe, 0xef]
+        );
+        assert_eq!(
+            test::from_hex(" de ad\tbe\r\nef\n").unwrap(),
+            [0xde, 0xad, 0xbe, 0xef]
+        );
+        // Whitespace may also split a byte's digits.
+        assert_eq!(test::from_hex("d ea d").unwrap(), [0xde, 0xad]);
+        assert!(test::from_hex(" \t\n").unwrap().is_empty());
+    }
+
+    #[test]
+    fn from_hex_invalid() {
+        assert_eq!(
+            test::from_hex("dea"),
+            Err(String::from(
+                "Hex string does not have an even number of digits"
+            ))
+        );
+        assert_eq!(
+            test::from_hex("de a"),
+            Err(String::from(
+                "Hex string does not have an even number of digits"
+            ))
+        );
+        assert_eq!(
+            test::from_hex("de ag"),
+            Err(String::from("Invalid hex digit 'g'"))
+        );
+        assert_eq!(
+            test::from_hex("de_ad"),
+            Err(String::from(
+                "Hex string does not have an even number of digits"
+            ))
+        );
+        assert_eq!(
+            test::from_hex("de-a"),
+            Err(String::from("Invalid hex digit '-'"))
+        );
+    }
+
+    #[test]
+    fn assert_bytes_eq_equal() {
+        test::assert_bytes_eq(&[], &[]);
+        test::assert_bytes_eq(&[1, 2, 3], &[1, 2, 3]);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Bytes differ starting at offset 17 (expected 20 bytes, got 20 bytes)"
+    )]
+    fn assert_bytes_eq_different() {
+        let expected = [0u8; 20];
+        let mut actual = [0u8; 20];
+        actual[17] = 1;
+        test::assert_bytes_eq(&expected, &actual);
+    }
+
+    #[test]
+    #[should_panic(expected = "Bytes differ starting at offset 3 (expected 3 bytes, got 4 bytes)")]
+    fn assert_bytes_eq_longer() {
+        test::assert_bytes_eq(&[1, 2, 3], &[1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn describe_bytes_difference() {
+        let expected: Vec<u8> = (0..40).collect();
+        let mut actual = expected.clone();
+        actual[20] = 0xff;
+        let _ = actual.pop();
+        assert_eq!(
+            test::describe_bytes_difference(&expected, &actual).unwrap(),
+            "Bytes differ starting at offset 20 (expected 40 bytes, got 39 bytes):\n\
+             offset    expected                          actual\n\
+             00000010  101112131415161718191a1b1c1d1e1f  10111213ff15161718191a1b1c1d1e1f  <\n\
+             00000020  2021222324252627                  20212223242526                    <\n"
+        );
+
+        // Only a limited number of rows are shown.
+        let expected = [0u8; 1000];
+        let actual = [1u8; 1000];
+        let msg = test::describe_bytes_difference(&expected, &actual).unwrap();
+        assert_eq!(msg.lines().count(), 2 + 8 + 1);
+        assert!(msg.ends_with("...\n"));
+    }
 
     #[test]
     fn one_ok() {
@@ -632,9 +1763,706 @@ mod tests {
         });
     }
 
+    #[test]
+    fn run_filtered() {
+        let mut keys = Vec::new();
+        test::run_filtered(
+            test_file!("test_3_tests.txt"),
+            |test_case| test_case.peek_string("Key") != Some("1"),
+            |_, test_case| {
+                keys.push(test_case.consume_usize("Key"));
+                Ok(())
+            },
+        );
+        assert_eq!(keys, [0, 2]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Test failed.")]
+    fn run_filtered_err() {
+        test::run_filtered(
+            test_file!("test_3_tests.txt"),
+            |test_case| test_case.peek_string("Key") == Some("2"),
+            |_, test_case| {
+                assert_eq!(test_case.consume_usize("Key"), 2);
+                Err(error::Unspecified)
+            },
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Syntax error: Expected Key = Value.")]
+    fn run_filtered_syntax_error() {
+        test::run_filtered(
+            test_file!("test_1_syntax_error_tests.txt"),
+            |_| false,
+            |_, _| Ok(()),
+        );
+    }
+
+    #[test]
+    fn reset() {
+        test::run(test_file!("test_1_tests.txt"), |_, test_case| {
+            for _ in 0..2 {
+                assert_eq!(test_case.consume_string("Key"), "Value");
+                assert_eq!(test_case.next_attribute(), None);
+                test_case.reset();
+            }
+            assert_eq!(test_case.consume_string("Key"), "Value");
+            Ok(())
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Test failed.")]
+    fn reset_unconsumed() {
+        test::run(test_file!("test_1_tests.txt"), |_, test_case| {
+            assert_eq!(test_case.consume_string("Key"), "Value");
+            test_case.reset();
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn consume_optional_bytes() {
+        let file = test::File {
+            file_name: "consume_optional_bytes",
+            contents: "Key = ba1e\nEmpty = \"\"\n",
+        };
+        test::run(file, |_, test_case| {
+            assert_eq!(test_case.consume_optional_bytes("Missing"), None);
+            assert_eq!(test_case.consume_optional_bytes("Empty"), Some(Vec::new()));
+            assert_eq!(
+                test_case.consume_optional_bytes("Key"),
+                Some(Vec::from(&[0xba, 0x1e][..]))
+            );
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn peek_string() {
+        test::run(test_file!("test_1_tests.txt"), |_, test_case| {
+            assert_eq!(test_case.peek_string("Key"), Some("Value"));
+            assert_eq!(test_case.peek_string("Missing"), None);
+            // Peeking doesn't consume the attribute.
+            assert_eq!(test_case.consume_string("Key"), "Value");
+            assert_eq!(test_case.peek_string("Key"), Some("Value"));
+            Ok(())
+        });
+    }
+
+    #[cfg(all(feature = "std", feature = "flate2"))]
+    #[test]
+    fn run_gz() {
+        let mut keys = Vec::new();
+        test::run_gz(test_file_gz!("test_3_tests.txt.gz"), |_, test_case| {
+            keys.push(test_case.consume_usize("Key"));
+            Ok(())
+        });
+        assert_eq!(keys, [0, 1, 2]);
+
+        assert_eq!(
+            test_file_gz!("test_3_tests.txt.gz").decompress(),
+            test_file!("test_3_tests.txt").contents
+        );
+    }
+
+    #[cfg(all(feature = "std", feature = "flate2"))]
+    #[test]
+    #[should_panic(expected = "Test failed.")]
+    fn run_gz_err() {
+        test::run_gz(test_file_gz!("test_3_tests.txt.gz"), |_, test_case| {
+            let _ = test_case.consume_usize("Key");
+            Err(error::Unspecified)
+        });
+    }
+
+    #[test]
+    fn from_bytes() {
+        let mut keys = Vec::new();
+        test::from_bytes(include_bytes!("test_3_tests.txt"), |_, test_case| {
+            keys.push(test_case.consume_usize("Key"));
+            Ok(())
+        });
+        assert_eq!(keys, [0, 1, 2]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Test failed.")]
+    fn from_bytes_err() {
+        test::from_bytes(b"Key = 0\n\nKey = 1\n", |_, test_case| {
+            if test_case.consume_usize("Key") == 1 {
+                return Err(error::Unspecified);
+            }
+            Ok(())
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Test data is not valid UTF-8")]
+    fn from_bytes_invalid_utf8() {
+        test::from_bytes(b"Key = \xff\n", |_, _| Ok(()));
+    }
+
+    #[test]
+    fn run_sectioned() {
+        let file = test::File {
+            file_name: "run_sectioned",
+            contents: "A = 0\n\n[L = 20]\n\nA = 1\n\nA = 2\n\n\
+                       [mod = 2048, SHA = SHA256, Extra]\n\nA = 3\n",
+        };
+        let mut cases = Vec::new();
+        test::run_sectioned(file, |section, test_case| {
+            let a = test_case.consume_usize("A");
+            match a {
+                0 => {
+                    assert_eq!(section.name(), "");
+                    assert_eq!(section.get("L"), None);
+                }
+                1 | 2 => {
+                    assert_eq!(section.name(), "L = 20");
+                    assert_eq!(section.get_usize("L"), 20);
+                }
+                3 => {
+                    assert_eq!(section.name(), "mod = 2048, SHA = SHA256, Extra");
+                    assert_eq!(section.get_usize("mod"), 2048);
+                    assert_eq!(section.get("SHA"), Some("SHA256"));
+                    assert_eq!(section.get("Extra"), None);
+                    assert_eq!(section.get("L"), None);
+                }
+                _ => unreachable!(),
+            }
+            cases.push(a);
+            Ok(())
+        });
+        assert_eq!(cases, [0, 1, 2, 3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "No attribute named \"L\" in section []")]
+    fn run_sectioned_missing_attribute() {
+        test::run_sectioned(test_file!("test_1_tests.txt"), |section, test_case| {
+            let _ = test_case.consume_string("Key");
+            let _ = section.get_usize("L");
+            Ok(())
+        });
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn run_parallel_ok() {
+        use core::sync::atomic::{AtomicUsize, Ordering};
+
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+        static SUM: AtomicUsize = AtomicUsize::new(0);
+
+        let contents: String = (0..100)
+            .map(|i| alloc::format!("Key = {}\n\n", i))
+            .collect();
+        test::run_parallel(
+            test::File {
+                file_name: "run_parallel_ok",
+                contents: &contents,
+            },
+            |section, test_case| {
+                assert_eq!(section, "");
+                let key = test_case.consume_usize("Key");
+                let _ = COUNT.fetch_add(1, Ordering::SeqCst);
+                let _ = SUM.fetch_add(key, Ordering::SeqCst);
+                Ok(())
+            },
+        );
+
+        assert_eq!(COUNT.load(Ordering::SeqCst), 100);
+        assert_eq!(SUM.load(Ordering::SeqCst), (0..100).sum());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    #[should_panic(expected = "Test failed.")]
+    fn run_parallel_err() {
+        test::run_parallel(
+            test_file!("test_3_tests.txt"),
+            |_, test_case| match test_case.consume_usize("Key") {
+                1 => Err(error::Unspecified),
+                _ => Ok(()),
+            },
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    #[should_panic(expected = "Test failed.")]
+    fn run_parallel_panic() {
+        test::run_parallel(test_file!("test_3_tests.txt"), |_, test_case| {
+            if test_case.consume_usize("Key") == 2 {
+                panic!("Oh Noes!");
+            }
+            Ok(())
+        });
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn run_quarantine() {
+        extern crate std;
+
+        let fail_out = std::env::temp_dir().join(alloc::format!(
+            "ring_run_quarantine_{}.txt",
+            std::process::id()
+        ));
+        let fail_out = fail_out.to_str().unwrap();
+        let _ = std::fs::remove_file(fail_out);
+
+        let contents = "A = 1\nB = x\n\n[S]\nA = 2\n\nA = 3\n\nA = 4\n\nA = 5\n";
+        let mut ok = Vec::new();
+        let num_quarantined = test::run_quarantine(
+            test::File {
+                file_name: "run_quarantine",
+                contents,
+            },
+            fail_out,
+            |_, test_case| {
+                let a = test_case.consume_usize("A");
+                match a {
+                    // Doesn't consume `B`.
+                    1 => (),
+                    3 => return Err(error::Unspecified),
+                    4 => panic!("Oh Noes!"),
+                    _ => ok.push(a),
+                }
+                Ok(())
+            },
+        );
+        assert_eq!(num_quarantined, 3);
+        assert_eq!(ok, [2, 5]);
+
+        let quarantined = std::fs::read_to_string(fail_out).unwrap();
+        std::fs::remove_file(fail_out).unwrap();
+        assert_eq!(quarantined, "A = 1\nB = x\n\n[S]\n\nA = 3\n\nA = 4\n\n");
+
+        // The quarantined file can itself be run.
+        let mut failed = Vec::new();
+        test::run(
+            test::File {
+                file_name: fail_out,
+                contents: &quarantined,
+            },
+            |section, test_case| {
+                let _ = test_case.consume_optional_string("B");
+                failed.push((String::from(section), test_case.consume_usize("A")));
+                Ok(())
+            },
+        );
+        assert_eq!(
+            failed,
+            [
+                (String::from(""), 1),
+                (String::from("S"), 3),
+                (String::from("S"), 4)
+            ]
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn from_file_json() {
+        let contents =
+            "A = 1\nB = \"x\\y\"\n\n[S]\nA = 2\nC = y\n\nA = 3\nC = y\n\nA = 4\nC = y\n\nA = 5\n";
+        let report = test::from_file_json(
+            test::File {
+                file_name: "from_file_json",
+                contents,
+            },
+            |section, test_case| {
+                if section == "S" && test_case.consume_optional_string("C").is_none() {
+                    // Skipped.
+                    return Ok(());
+                }
+                match test_case.consume_usize("A") {
+                    // Doesn't consume `B`.
+                    1 => (),
+                    3 => return Err(error::Unspecified),
+                    4 => panic!("Oh Noes!"),
+                    _ => (),
+                }
+                Ok(())
+            },
+        );
+        assert_eq!(report.file_name, "from_file_json");
+        assert_eq!(report.total, 5);
+        assert_eq!(report.passed, 1);
+        assert_eq!(report.failed, 3);
+        assert_eq!(report.skipped, 1);
+        assert_eq!(
+            report
+                .failures
+                .iter()
+                .map(|failure| (failure.index, failure.section.as_str(), failure.message))
+                .collect::<Vec<_>>(),
+            [
+                (0, "", "Test didn't consume all attributes."),
+                (2, "S", "Test returned Err(error::Unspecified)."),
+                (3, "S", "Test panicked."),
+            ]
+        );
+        assert_eq!(
+            report.failures[0].attributes,
+            [
+                (String::from("A"), String::from("1")),
+                (String::from("B"), String::from("\"x\\y\""))
+            ]
+        );
+
+        assert_eq!(
+            report.to_json(),
+            concat!(
+                r#"{"file_name":"from_file_json","total":5,"passed":1,"failed":3,"skipped":1,"#,
+                r#""failures":["#,
+                r#"{"index":0,"section":"","message":"Test didn't consume all attributes.","#,
+                r#""attributes":[["A","1"],["B","\"x\\y\""]]},"#,
+                r#"{"index":2,"section":"S","message":"Test returned Err(error::Unspecified).","#,
+                r#""attributes":[["A","3"],["C","y"]]},"#,
+                r#"{"index":3,"section":"S","message":"Test panicked.","#,
+                r#""attributes":[["A","4"],["C","y"]]}"#,
+                r#"]}"#
+            )
+        );
+    }
+
     #[test]
     #[should_panic(expected = "Syntax error: Expected Key = Value.")]
     fn syntax_error() {
         test::run(test_file!("test_1_syntax_error_tests.txt"), |_, _| Ok(()));
     }
+
+    #[derive(Debug, PartialEq)]
+    enum Padding {
+        PKCS1,
+        PSS,
+    }
+
+    impl core::str::FromStr for Padding {
+        type Err = ();
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            match s {
+                "PKCS1" => Ok(Padding::PKCS1),
+                "PSS" => Ok(Padding::PSS),
+                _ => Err(()),
+            }
+        }
+    }
+
+    fn consume_padding(contents: &str) -> Vec<Padding> {
+        let mut paddings = Vec::new();
+        test::run(
+            test::File {
+                file_name: "consume_enum",
+                contents,
+            },
+            |_, test_case| {
+                paddings.push(test_case.consume_enum("Padding"));
+                Ok(())
+            },
+        );
+        paddings
+    }
+
+    #[test]
+    fn consume_enum() {
+        assert_eq!(
+            consume_padding("Padding = PSS\n\nPadding = PKCS1\n"),
+            [Padding::PSS, Padding::PKCS1]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid value for attribute \"Padding\": OAEP")]
+    fn consume_enum_invalid() {
+        let _ = consume_padding("Padding = OAEP\n");
+    }
+
+    #[test]
+    fn consume_u64() {
+        let mut values = Vec::new();
+        test::run(
+            test::File {
+                file_name: "consume_u64",
+                contents: "BE = 00000001\nLE = 00000001\n\n\
+                           BE = 0102030405060708\nLE = 0102030405060708\n\n\
+                           BE = \"\"\nLE = \"\"\n\n\
+                           BE = ffffffffffffffff\nLE = ffffffffffffffff\n\n\
+                           BE = \"\\x01\\x00\"\nLE = \"\\x01\\x00\"\n",
+            },
+            |_, test_case| {
+                values.push((
+                    test_case.consume_u64_be("BE"),
+                    test_case.consume_u64_le("LE"),
+                ));
+                Ok(())
+            },
+        );
+        assert_eq!(
+            values,
+            [
+                (1, 0x0100_0000),
+                (0x0102_0304_0506_0708, 0x0807_0605_0403_0201),
+                (0, 0),
+                (u64::max_value(), u64::max_value()),
+                (0x0100, 0x0001),
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Value of attribute \"Counter\" is too long for a u64: 9 bytes")]
+    fn consume_u64_too_long() {
+        test::run(
+            test::File {
+                file_name: "consume_u64_too_long",
+                contents: "Counter = 000000000000000001\n",
+            },
+            |_, test_case| {
+                let _ = test_case.consume_u64_be("Counter");
+                Ok(())
+            },
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn consume_duration() {
+        extern crate std;
+        use std::time::Duration;
+
+        let mut durations = Vec::new();
+        test::run(
+            test::File {
+                file_name: "consume_duration",
+                contents: "Time = 59\nStep = 30s\n\nTime = 1111111109\nStep = 1500ms\n\n\
+                           Time = 0ms\nStep = 0s\n",
+            },
+            |_, test_case| {
+                durations.push((
+                    test_case.consume_duration("Time"),
+                    test_case.consume_duration("Step"),
+                ));
+                Ok(())
+            },
+        );
+        assert_eq!(
+            durations,
+            [
+                (Duration::from_secs(59), Duration::from_secs(30)),
+                (Duration::from_secs(1111111109), Duration::from_millis(1500)),
+                (Duration::from_secs(0), Duration::from_secs(0)),
+            ]
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    #[should_panic(expected = "Invalid value for attribute \"Time\": -1s")]
+    fn consume_duration_invalid() {
+        test::run(
+            test::File {
+                file_name: "consume_duration_invalid",
+                contents: "Time = -1s\n",
+            },
+            |_, test_case| {
+                let _ = test_case.consume_duration("Time");
+                Ok(())
+            },
+        );
+    }
+
+    #[test]
+    fn next_attribute() {
+        let contents = "Version = 1\nSend = a\nRecv = b\nSend = c\n";
+        test::run(
+            test::File {
+                file_name: "next_attribute",
+                contents,
+            },
+            |_, test_case| {
+                assert_eq!(test_case.consume_usize("Version"), 1);
+                let mut transcript = Vec::new();
+                while let Some(attribute) = test_case.next_attribute() {
+                    transcript.push(attribute);
+                }
+                assert_eq!(
+                    transcript,
+                    [
+                        (String::from("Send"), String::from("a")),
+                        (String::from("Recv"), String::from("b")),
+                        (String::from("Send"), String::from("c")),
+                    ]
+                );
+                assert_eq!(test_case.next_attribute(), None);
+                Ok(())
+            },
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn bench_aead() {
+        use crate::aead;
+
+        const SIZES: &[usize] = &[0, 1, 16, 1350];
+        for algorithm in &[
+            &aead::AES_128_GCM,
+            &aead::AES_256_GCM,
+            &aead::CHACHA20_POLY1305,
+        ] {
+            let result = test::bench_aead(algorithm, SIZES);
+            assert_eq!(result.algorithm, *algorithm);
+            assert_eq!(result.throughputs.len(), SIZES.len());
+            for (t, size) in result.throughputs.iter().zip(SIZES) {
+                assert_eq!(t.input_len, *size);
+                for &bytes_per_second in &[t.seal_bytes_per_second, t.open_bytes_per_second] {
+                    assert!(bytes_per_second.is_finite());
+                    if *size == 0 {
+                        assert_eq!(bytes_per_second, 0.0);
+                    } else {
+                        assert!(bytes_per_second > 0.0);
+                    }
+                }
+            }
+        }
+    }
+
+    // Timing measurements are noisy, especially on shared CI machines, so the
+    // timing tests are only run on request, e.g. with
+    // `cargo test --release --features=std -- --ignored`.
+    #[cfg(feature = "std")]
+    #[test]
+    #[ignore]
+    fn assert_constant_time() {
+        use crate::constant_time;
+
+        let mut secret = [0; test::CONSTANT_TIME_INPUT_LEN];
+        secret[test::CONSTANT_TIME_INPUT_LEN - 1] = 1;
+
+        test::assert_constant_time(
+            |input| constant_time::verify_slices_are_equal(&secret, input).is_ok(),
+            10_000,
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    #[ignore]
+    #[should_panic(expected = "Timing leak detected")]
+    fn assert_constant_time_leak() {
+        let mut secret = [0; test::CONSTANT_TIME_INPUT_LEN];
+        secret[test::CONSTANT_TIME_INPUT_LEN - 1] = 1;
+
+        // An early-exit comparison leaks how many leading bytes match. The
+        // volatile reads keep the optimizer from replacing the loop with a
+        // (faster, and more nearly constant-time) `memcmp`.
+        test::assert_constant_time(
+            |input| {
+                for (a, b) in input.iter().zip(secret.iter()) {
+                    if *a != unsafe { core::ptr::read_volatile(b) } {
+                        return false;
+                    }
+                }
+                true
+            },
+            10_000,
+        );
+    }
+
+    // Files that end in the middle of a test case, without a final newline
+    // or blank line, must still yield the last test case with all of its
+    // attributes. These are given inline since editors tend to add the final
+    // newline to files on disk.
+    #[test]
+    fn no_final_newline() {
+        for contents in &[
+            "A = 1\nB = 2\n\nA = 3\nB = 4",
+            "A = 1\r\nB = 2\r\n\r\nA = 3\r\nB = 4",
+            "A = 1\r\nB = 2\r\n\r\nA = 3\r\nB = 4\r",
+            "[S]\nA = 1\nB = 2\n\nA = 3\nB = 4",
+        ] {
+            let mut cases = Vec::new();
+            test::run(
+                test::File {
+                    file_name: "no_final_newline",
+                    contents,
+                },
+                |_, test_case| {
+                    let a = test_case.consume_usize("A");
+                    let b = test_case.consume_usize("B");
+                    cases.push((a, b));
+                    Ok(())
+                },
+            );
+            assert_eq!(cases, [(1, 2), (3, 4)]);
+        }
+    }
+
+    // A section header ends the preceding test case even without a blank line
+    // between them.
+    const INTERLEAVED_SECTIONS: &str =
+        "[S1]\nA = 1\n[S2]\nA = 2\nA2 = 3\n[S3]\r\nA = 4\r\n\n[S4]\nA = 5";
+
+    #[test]
+    fn interleaved_sections() {
+        let mut cases = Vec::new();
+        test::run(
+            test::File {
+                file_name: "interleaved_sections",
+                contents: INTERLEAVED_SECTIONS,
+            },
+            |section, test_case| {
+                let a = test_case.consume_usize("A");
+                let _ = test_case.consume_optional_string("A2");
+                cases.push((String::from(section), a));
+                Ok(())
+            },
+        );
+        assert_eq!(
+            cases,
+            [
+                (String::from("S1"), 1),
+                (String::from("S2"), 2),
+                (String::from("S3"), 4),
+                (String::from("S4"), 5),
+            ]
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn interleaved_sections_parallel() {
+        use core::sync::atomic::{AtomicUsize, Ordering};
+
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        test::run_parallel(
+            test::File {
+                file_name: "interleaved_sections",
+                contents: INTERLEAVED_SECTIONS,
+            },
+            |section, test_case| {
+                let a = test_case.consume_usize("A");
+                let _ = test_case.consume_optional_string("A2");
+                let expected = match a {
+                    1 => "S1",
+                    2 => "S2",
+                    4 => "S3",
+                    5 => "S4",
+                    _ => unreachable!(),
+                };
+                assert_eq!(section, expected);
+                let _ = COUNT.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            },
+        );
+        assert_eq!(COUNT.load(Ordering::SeqCst), 4);
+    }
 }
@@ -21,7 +21,7 @@ use wasm_bindgen_test::{wasm_bindgen_test, wasm_bindgen_test_configure};
 wasm_bindgen_test_configure!(run_in_browser);
 
 use core::ops::RangeFrom;
-use ring::{aead, error, test, test_file};
+use ring::{aead, error, hkdf, hmac, rand, test, test_file};
 
 #[test]
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
@@ -38,6 +38,12 @@ fn aead_aes_gcm_128() {
         open_with_less_safe_key,
         test_file!("aead_aes_128_gcm_tests.txt"),
     );
+    test_aead(
+        &aead::AES_128_GCM,
+        seal_with_less_safe_key,
+        open_with_separate_buffers,
+        test_file!("aead_aes_128_gcm_tests.txt"),
+    );
 }
 
 #[test]
@@ -55,6 +61,12 @@ fn aead_aes_gcm_256() {
         open_with_less_safe_key,
         test_file!("aead_aes_256_gcm_tests.txt"),
     );
+    test_aead(
+        &aead::AES_256_GCM,
+        seal_with_less_safe_key,
+        open_with_separate_buffers,
+        test_file!("aead_aes_256_gcm_tests.txt"),
+    );
 }
 
 #[cfg(any(
@@ -77,6 +89,64 @@ fn aead_chacha20_poly1305() {
         open_with_less_safe_key,
         test_file!("aead_chacha20_poly1305_tests.txt"),
     );
+    test_aead(
+        &aead::CHACHA20_POLY1305,
+        seal_with_less_safe_key,
+        open_with_separate_buffers,
+        test_file!("aead_chacha20_poly1305_tests.txt"),
+    );
+}
+
+// Large AAD with tiny inputs, to check that the AAD and input lengths are
+// both encoded correctly in the final length block.
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn aead_large_aad() {
+    test::run(
+        test_file!("aead_large_aad_tests.txt"),
+        |section, test_case| {
+            assert_eq!(section, "");
+            let alg = aead::Algorithm::from_name(&test_case.consume_string("ALG")).unwrap();
+            let key_bytes = test_case.consume_bytes("KEY");
+            let nonce_bytes = test_case.consume_bytes("NONCE");
+            let plaintext = test_case.consume_bytes("IN");
+            let ad_len = test_case.consume_usize("AD_LEN");
+            let ct = test_case.consume_bytes("CT");
+            let tag = test_case.consume_bytes("TAG");
+
+            let ad: Vec<u8> = (0..ad_len).map(|i| (i % 251) as u8).collect();
+            let key = make_less_safe_key(alg, &key_bytes);
+
+            let mut in_out = plaintext.clone();
+            let actual_tag = key.seal_in_place_separate_tag(
+                aead::Nonce::try_assume_unique_for_key(&nonce_bytes)?,
+                aead::Aad::from(&ad[..]),
+                &mut in_out,
+            )?;
+            assert_eq!(in_out, ct);
+            assert_eq!(actual_tag.as_ref(), &tag[..]);
+
+            let mut in_out = [&ct[..], &tag[..]].concat();
+            let opened = key.open_in_place(
+                aead::Nonce::try_assume_unique_for_key(&nonce_bytes)?,
+                aead::Aad::from(&ad[..]),
+                &mut in_out,
+            )?;
+            assert_eq!(opened, &plaintext[..]);
+
+            // The AAD length is authenticated.
+            let mut in_out = [&ct[..], &tag[..]].concat();
+            assert!(key
+                .open_in_place(
+                    aead::Nonce::try_assume_unique_for_key(&nonce_bytes)?,
+                    aead::Aad::from(&ad[..(ad_len - 1)]),
+                    &mut in_out,
+                )
+                .is_err());
+
+            Ok(())
+        },
+    );
 }
 
 fn test_aead<Seal, Open>(
@@ -282,6 +352,22 @@ fn open_with_less_safe_key<'a>(
 }
 
 #[allow(clippy::range_plus_one)]
+fn open_with_separate_buffers<'a>(
+    algorithm: &'static aead::Algorithm,
+    key: &[u8],
+    nonce: aead::Nonce,
+    aad: aead::Aad<&[u8]>,
+    in_out: &'a mut [u8],
+    ciphertext_and_tag: RangeFrom<usize>,
+) -> Result<&'a mut [u8], error::Unspecified> {
+    let key = make_less_safe_key(algorithm, key);
+    let input = in_out[ciphertext_and_tag].to_vec();
+    let mut out = vec![0; input.len()];
+    let plaintext_len = aead::open(&key, nonce, aad, &input, &mut out)?;
+    in_out[..plaintext_len].copy_from_slice(&out[..plaintext_len]);
+    Ok(&mut in_out[..plaintext_len])
+}
+
 fn test_aead_key_sizes(aead_alg: &'static aead::Algorithm) {
     let key_len = aead_alg.key_len();
     let key_data = vec![0u8; key_len * 2];
@@ -327,6 +413,382 @@ fn test_aead_nonce_sizes() -> Result<(), error::Unspecified> {
     Ok(())
 }
 
+#[test]
+fn test_aead_unbound_key_derive() {
+    const MASTER_KEY: [u8; 32] = [0x42; 32];
+    let master_key = hmac::Key::new(hmac::HMAC_SHA256, &MASTER_KEY);
+    let prk = hkdf::Prk::new_less_safe(hkdf::HKDF_SHA256, &MASTER_KEY);
+
+    let seal = |key: aead::UnboundKey| {
+        let mut in_out = b"plaintext".to_vec();
+        aead::LessSafeKey::new(key)
+            .seal_in_place_append_tag(
+                aead::Nonce::assume_unique_for_key([0; aead::NONCE_LEN]),
+                aead::Aad::empty(),
+                &mut in_out,
+            )
+            .unwrap();
+        in_out
+    };
+
+    for &alg in &[
+        &aead::AES_128_GCM,
+        &aead::AES_256_GCM,
+        &aead::CHACHA20_POLY1305,
+    ] {
+        let derived = aead::UnboundKey::derive(&master_key, b"context A", alg).unwrap();
+        assert_eq!(derived.algorithm(), alg);
+
+        // Equivalent to HKDF-Expand with the context as the info.
+        let expected: aead::UnboundKey = prk.expand(&[b"context A"], alg).unwrap().into();
+        let sealed = seal(derived);
+        assert_eq!(sealed, seal(expected));
+
+        // Different contexts produce different keys.
+        let other = aead::UnboundKey::derive(&master_key, b"context B", alg).unwrap();
+        assert_ne!(sealed, seal(other));
+    }
+}
+
+#[test]
+fn test_aead_seal_open_in_place_with() {
+    let key = make_less_safe_key(&aead::CHACHA20_POLY1305, &[0x42; 32]);
+    let aad = b"aad";
+    let plaintext = b"nonce computed by a closure";
+
+    // Derive the nonce from a sequence number, as a record protocol might.
+    let make_nonce = |seq: u64| {
+        let mut nonce = [0x24; aead::NONCE_LEN];
+        for (n, s) in nonce[4..].iter_mut().zip(&seq.to_be_bytes()) {
+            *n ^= *s;
+        }
+        aead::Nonce::assume_unique_for_key(nonce)
+    };
+
+    for seq in 0..3 {
+        let mut calls = 0;
+        let mut in_out = plaintext.to_vec();
+        aead::seal_in_place_with(&key, aead::Aad::from(aad), &mut in_out, || {
+            calls += 1;
+            make_nonce(seq)
+        })
+        .unwrap();
+        assert_eq!(calls, 1);
+
+        // Same result as passing the nonce directly.
+        let mut expected = plaintext.to_vec();
+        key.seal_in_place_append_tag(make_nonce(seq), aead::Aad::from(aad), &mut expected)
+            .unwrap();
+        assert_eq!(in_out, expected);
+
+        let opened =
+            aead::open_in_place_with(&key, aead::Aad::from(aad), &mut in_out, || make_nonce(seq))
+                .unwrap();
+        assert_eq!(opened, &plaintext[..]);
+
+        // The wrong nonce is rejected.
+        let mut in_out = expected.clone();
+        assert!(
+            aead::open_in_place_with(&key, aead::Aad::from(aad), &mut in_out, || {
+                make_nonce(seq + 1)
+            })
+            .is_err()
+        );
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_aead_seal_open_to_vec() {
+    let key = make_less_safe_key(&aead::AES_256_GCM, &[0x42; 32]);
+    let nonce_bytes = [0x24; aead::NONCE_LEN];
+    let aad = b"aad";
+
+    for plaintext in &[&b""[..], &b"to and from a vector"[..]] {
+        let sealed = aead::seal_to_vec(
+            &key,
+            aead::Nonce::assume_unique_for_key(nonce_bytes),
+            aead::Aad::from(aad),
+            plaintext,
+        )
+        .unwrap();
+        assert_eq!(sealed.len(), plaintext.len() + key.algorithm().tag_len());
+
+        // Same result as the in-place API.
+        let mut in_out = plaintext.to_vec();
+        key.seal_in_place_append_tag(
+            aead::Nonce::assume_unique_for_key(nonce_bytes),
+            aead::Aad::from(aad),
+            &mut in_out,
+        )
+        .unwrap();
+        assert_eq!(sealed, in_out);
+
+        let opened = aead::open_to_vec(
+            &key,
+            aead::Nonce::assume_unique_for_key(nonce_bytes),
+            aead::Aad::from(aad),
+            &sealed,
+        )
+        .unwrap();
+        assert_eq!(&opened[..], *plaintext);
+
+        // Wrong AAD.
+        assert!(aead::open_to_vec(
+            &key,
+            aead::Nonce::assume_unique_for_key(nonce_bytes),
+            aead::Aad::empty(),
+            &sealed,
+        )
+        .is_err());
+
+        // Shorter than the tag.
+        assert!(aead::open_to_vec(
+            &key,
+            aead::Nonce::assume_unique_for_key(nonce_bytes),
+            aead::Aad::from(aad),
+            &sealed[..key.algorithm().tag_len() - 1],
+        )
+        .is_err());
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_aead_seal_open_self_contained() {
+    let key = make_less_safe_key(&aead::AES_128_GCM, &[0x42; 16]);
+    let plaintext = b"stored together with its nonce";
+    let aad = b"aad";
+
+    let rng = test::rand::FixedByteRandom { byte: 0x24 };
+    let blob = aead::seal_self_contained(&key, aead::Aad::from(aad), plaintext, &rng).unwrap();
+    assert_eq!(
+        blob.len(),
+        aead::NONCE_LEN + plaintext.len() + key.algorithm().tag_len()
+    );
+
+    // The blob is the nonce followed by the output of sealing with it.
+    assert_eq!(&blob[..aead::NONCE_LEN], &[0x24; aead::NONCE_LEN]);
+    let expected = aead::seal_to_vec(
+        &key,
+        aead::Nonce::assume_unique_for_key([0x24; aead::NONCE_LEN]),
+        aead::Aad::from(aad),
+        plaintext,
+    )
+    .unwrap();
+    assert_eq!(&blob[aead::NONCE_LEN..], &expected[..]);
+
+    let opened = aead::open_self_contained(&key, aead::Aad::from(aad), &blob).unwrap();
+    assert_eq!(&opened[..], &plaintext[..]);
+
+    // Each random nonce is used.
+    let blob2 = aead::seal_self_contained(
+        &key,
+        aead::Aad::from(aad),
+        plaintext,
+        &rand::SystemRandom::new(),
+    )
+    .unwrap();
+    assert_ne!(blob, blob2);
+    assert_eq!(
+        aead::open_self_contained(&key, aead::Aad::from(aad), &blob2).unwrap(),
+        &plaintext[..]
+    );
+
+    // Wrong AAD, a modified nonce, and blobs that are too short are rejected.
+    assert!(aead::open_self_contained(&key, aead::Aad::empty(), &blob).is_err());
+    let mut modified = blob.clone();
+    modified[0] ^= 1;
+    assert!(aead::open_self_contained(&key, aead::Aad::from(aad), &modified).is_err());
+    for len in &[
+        0,
+        aead::NONCE_LEN - 1,
+        aead::NONCE_LEN,
+        blob.len() - plaintext.len() - 1,
+    ] {
+        assert!(aead::open_self_contained(&key, aead::Aad::from(aad), &blob[..*len]).is_err());
+    }
+}
+
+#[test]
+fn test_aead_open_separate_buffers() {
+    let key = make_less_safe_key(&aead::AES_128_GCM, &[0x42; 16]);
+    let nonce_bytes = [0x24; aead::NONCE_LEN];
+    let plaintext = b"separate input and output";
+
+    let mut sealed = plaintext.to_vec();
+    key.seal_in_place_append_tag(
+        aead::Nonce::assume_unique_for_key(nonce_bytes),
+        aead::Aad::empty(),
+        &mut sealed,
+    )
+    .unwrap();
+    let sealed = sealed; // The input is never modified.
+    let tag_len = key.algorithm().tag_len();
+
+    // `out` may be longer than the plaintext; the excess is left untouched.
+    let mut out = vec![0xff; sealed.len()];
+    let plaintext_len = aead::open(
+        &key,
+        aead::Nonce::assume_unique_for_key(nonce_bytes),
+        aead::Aad::empty(),
+        &sealed,
+        &mut out,
+    )
+    .unwrap();
+    assert_eq!(plaintext_len, plaintext.len());
+    assert_eq!(&out[..plaintext_len], &plaintext[..]);
+    assert!(out[plaintext_len..].iter().all(|&b| b == 0xff));
+
+    // `out` must be at least as long as the ciphertext.
+    let mut out = vec![0; sealed.len() - tag_len - 1];
+    assert!(aead::open(
+        &key,
+        aead::Nonce::assume_unique_for_key(nonce_bytes),
+        aead::Aad::empty(),
+        &sealed,
+        &mut out,
+    )
+    .is_err());
+
+    // The input must be at least as long as the tag.
+    let mut out = vec![0; sealed.len()];
+    assert!(aead::open(
+        &key,
+        aead::Nonce::assume_unique_for_key(nonce_bytes),
+        aead::Aad::empty(),
+        &sealed[..(tag_len - 1)],
+        &mut out,
+    )
+    .is_err());
+
+    // A bad tag is rejected and the plaintext isn't leaked.
+    let mut tampered = sealed.clone();
+    *tampered.last_mut().unwrap() ^= 1;
+    let mut out = vec![0xff; tampered.len() - tag_len];
+    assert!(aead::open(
+        &key,
+        aead::Nonce::assume_unique_for_key(nonce_bytes),
+        aead::Aad::empty(),
+        &tampered,
+        &mut out,
+    )
+    .is_err());
+    assert!(out.iter().all(|&b| b == 0));
+}
+
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn aead_aes_gcm_truncated_tag() {
+    test_aead_truncated_tag(&aead::AES_128_GCM, test_file!("aead_aes_128_gcm_tests.txt"));
+    test_aead_truncated_tag(&aead::AES_256_GCM, test_file!("aead_aes_256_gcm_tests.txt"));
+}
+
+fn test_aead_truncated_tag(aead_alg: &'static aead::Algorithm, test_file: test::File) {
+    test::run(test_file, |section, test_case| {
+        assert_eq!(section, "");
+        let key_bytes = test_case.consume_bytes("KEY");
+        let nonce_bytes = test_case.consume_bytes("NONCE");
+        let plaintext = test_case.consume_bytes("IN");
+        let aad = test_case.consume_bytes("AD");
+        let ct = test_case.consume_bytes("CT");
+        let tag = test_case.consume_bytes("TAG");
+        if test_case.consume_optional_string("FAILS").is_some() {
+            return Ok(());
+        }
+
+        for tag_len in 12..=16 {
+            let key = aead::TruncatedTagKey::new(
+                aead::UnboundKey::new(aead_alg, &key_bytes).unwrap(),
+                tag_len,
+            )
+            .unwrap();
+            assert_eq!(key.tag_len(), tag_len);
+            assert_eq!(key.algorithm(), aead_alg);
+
+            // The truncated tag is the prefix of the full tag.
+            let mut expected = ct.clone();
+            expected.extend_from_slice(&tag[..tag_len]);
+
+            let mut in_out = plaintext.clone();
+            key.seal_in_place_append_tag(
+                aead::Nonce::try_assume_unique_for_key(&nonce_bytes).unwrap(),
+                aead::Aad::from(&aad[..]),
+                &mut in_out,
+            )
+            .unwrap();
+            assert_eq!(in_out, expected);
+
+            let opened = key
+                .open_in_place(
+                    aead::Nonce::try_assume_unique_for_key(&nonce_bytes).unwrap(),
+                    aead::Aad::from(&aad[..]),
+                    &mut in_out,
+                )
+                .unwrap();
+            assert_eq!(opened, &plaintext[..]);
+
+            // Every byte of the truncated tag is checked, and the plaintext
+            // isn't leaked on failure.
+            for i in 0..tag_len {
+                let mut tampered = expected.clone();
+                tampered[ct.len() + i] ^= 1;
+                assert!(key
+                    .open_in_place(
+                        aead::Nonce::try_assume_unique_for_key(&nonce_bytes).unwrap(),
+                        aead::Aad::from(&aad[..]),
+                        &mut tampered,
+                    )
+                    .is_err());
+                assert!(tampered[..ct.len()].iter().all(|&b| b == 0));
+            }
+
+            // Input shorter than the tag is rejected.
+            assert!(key
+                .open_in_place(
+                    aead::Nonce::try_assume_unique_for_key(&nonce_bytes).unwrap(),
+                    aead::Aad::from(&aad[..]),
+                    &mut expected[..(tag_len - 1)],
+                )
+                .is_err());
+        }
+
+        Ok(())
+    });
+}
+
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn test_aead_truncated_tag_key_new() {
+    let key_bytes = [0; 32];
+    for &tag_len in &[0, 1, 8, 11, 17, 32] {
+        let key = aead::UnboundKey::new(&aead::AES_128_GCM, &key_bytes[..16]).unwrap();
+        assert!(aead::TruncatedTagKey::new(key, tag_len).is_err());
+    }
+
+    // Tag truncation is only defined for AES-GCM.
+    let key = aead::UnboundKey::new(&aead::CHACHA20_POLY1305, &key_bytes).unwrap();
+    assert!(aead::TruncatedTagKey::new(key, 12).is_err());
+
+    let key = aead::UnboundKey::new(&aead::AES_256_GCM, &key_bytes).unwrap();
+    let key = aead::TruncatedTagKey::new(key, 12).unwrap();
+    assert_eq!(
+        "TruncatedTagKey { algorithm: AES_256_GCM, tag_len: 12 }",
+        format!("{:?}", key)
+    );
+}
+
+#[test]
+fn test_aead_nonce_constructors_agree() {
+    let bytes = [
+        0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef, 0x01, 0x23, 0x45, 0x67,
+    ];
+    let from_slice = aead::Nonce::try_assume_unique_for_key(&bytes[..]).unwrap();
+    let from_array = aead::Nonce::assume_unique_for_key(bytes);
+    assert_eq!(from_slice.as_ref(), &bytes);
+    assert_eq!(from_slice.as_ref(), from_array.as_ref());
+}
+
 #[cfg(any(
     target_arch = "aarch64",
     target_arch = "arm",
@@ -381,13 +843,197 @@ fn aead_chacha20_poly1305_openssh() {
     );
 }
 
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn test_aead_algorithm_from_name() {
+    assert_eq!(
+        aead::Algorithm::from_name("aes-128-gcm"),
+        Some(&aead::AES_128_GCM)
+    );
+    assert_eq!(
+        aead::Algorithm::from_name("aes-256-gcm"),
+        Some(&aead::AES_256_GCM)
+    );
+    assert_eq!(
+        aead::Algorithm::from_name("chacha20-poly1305"),
+        Some(&aead::CHACHA20_POLY1305)
+    );
+
+    for name in &[
+        "",
+        "aes-192-gcm",
+        "AES-128-GCM",
+        "chacha20_poly1305",
+        "aes-128-gcm ",
+    ] {
+        assert_eq!(aead::Algorithm::from_name(name), None);
+    }
+}
+
 #[test]
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
 fn test_tag_traits() {
+    test::compile_time_assert_clone::<aead::Tag>();
+    test::compile_time_assert_copy::<aead::Tag>();
     test::compile_time_assert_send::<aead::Tag>();
     test::compile_time_assert_sync::<aead::Tag>();
 }
 
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn test_tag_from_bytes() {
+    use core::convert::TryFrom;
+
+    let bytes = [0x5a; aead::MAX_TAG_LEN];
+    let tag = aead::Tag::from(bytes);
+    assert_eq!(tag.as_ref(), &bytes[..]);
+
+    let from_slice = aead::Tag::try_from(&bytes[..]).unwrap();
+    assert_eq!(from_slice.as_ref(), tag.as_ref());
+    assert!(aead::Tag::try_from(&bytes[1..]).is_err());
+    assert!(aead::Tag::try_from(&[0x5a; aead::MAX_TAG_LEN + 1][..]).is_err());
+    assert!(aead::Tag::try_from(&[][..]).is_err());
+
+    assert!(tag.verify_eq(&bytes).is_ok());
+    for i in 0..bytes.len() {
+        let mut other = bytes;
+        other[i] ^= 1;
+        assert!(tag.verify_eq(&other).is_err());
+    }
+    assert!(tag.verify_eq(&bytes[1..]).is_err());
+    assert!(tag.verify_eq(&[]).is_err());
+}
+
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn test_aead_verify_tag() {
+    let key = make_less_safe_key(&aead::AES_128_GCM, &[0x42; 16]);
+    let mut in_out = *b"hello, world";
+    let computed = key
+        .seal_in_place_separate_tag(
+            aead::Nonce::assume_unique_for_key([0; aead::NONCE_LEN]),
+            aead::Aad::empty(),
+            &mut in_out,
+        )
+        .unwrap();
+    let received = computed.as_ref().to_vec();
+
+    assert_eq!(aead::verify_tag(&computed, &received), Ok(()));
+    let mut other = received.clone();
+    other[0] ^= 0x80;
+    assert_eq!(aead::verify_tag(&computed, &other), Err(error::Unspecified));
+}
+
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn test_aead_gmac() {
+    test::run(
+        test_file!("aead_aes_gcm_gmac_tests.txt"),
+        |section, test_case| {
+            assert_eq!(section, "");
+            let key_bytes = test_case.consume_bytes("KEY");
+            let nonce = test_case.consume_bytes("NONCE");
+            let aad = test_case.consume_bytes("AD");
+            let expected_tag = test_case.consume_bytes("TAG");
+
+            let alg = match key_bytes.len() {
+                16 => &aead::AES_128_GCM,
+                32 => &aead::AES_256_GCM,
+                _ => unreachable!(),
+            };
+            let key = make_less_safe_key(alg, &key_bytes);
+
+            let tag = aead::gmac(&key, aead::Nonce::try_assume_unique_for_key(&nonce)?, &aad)?;
+            assert_eq!(tag.as_ref(), &expected_tag[..]);
+
+            // Sealing an empty plaintext produces the same tag.
+            let mut in_out = Vec::new();
+            key.seal_in_place_append_tag(
+                aead::Nonce::try_assume_unique_for_key(&nonce)?,
+                aead::Aad::from(&aad[..]),
+                &mut in_out,
+            )?;
+            assert_eq!(in_out, expected_tag);
+
+            let plaintext = key.open_in_place(
+                aead::Nonce::try_assume_unique_for_key(&nonce)?,
+                aead::Aad::from(&aad[..]),
+                &mut in_out,
+            )?;
+            assert!(plaintext.is_empty());
+
+            Ok(())
+        },
+    );
+
+    let key = make_less_safe_key(&aead::CHACHA20_POLY1305, &[0x42; 32]);
+    assert!(aead::gmac(
+        &key,
+        aead::Nonce::assume_unique_for_key([0; aead::NONCE_LEN]),
+        b"aad"
+    )
+    .is_err());
+}
+
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn test_aead_open_in_place_separate_tag() {
+    use core::convert::TryFrom;
+
+    for &alg in &[&aead::AES_128_GCM, &aead::AES_256_GCM] {
+        let key = make_less_safe_key(alg, &[0x42; 32][..alg.key_len()]);
+        let nonce = [0x24; aead::NONCE_LEN];
+        let plaintext = b"detached tag";
+
+        let mut in_out = plaintext.to_vec();
+        let tag = key
+            .seal_in_place_separate_tag(
+                aead::Nonce::assume_unique_for_key(nonce),
+                aead::Aad::from(b"aad"),
+                &mut in_out,
+            )
+            .unwrap();
+
+        // The detached tag is the same as an appended one.
+        let mut appended = plaintext.to_vec();
+        key.seal_in_place_append_tag(
+            aead::Nonce::assume_unique_for_key(nonce),
+            aead::Aad::from(b"aad"),
+            &mut appended,
+        )
+        .unwrap();
+        assert_eq!(&appended[..in_out.len()], &in_out[..]);
+        assert!(tag.verify_eq(&appended[in_out.len()..]).is_ok());
+
+        // A tag received over the wire can be parsed from a slice.
+        let received_tag = aead::Tag::try_from(&appended[in_out.len()..]).unwrap();
+        let mut ciphertext = in_out.clone();
+        let opened = key
+            .open_in_place_separate_tag(
+                aead::Nonce::assume_unique_for_key(nonce),
+                aead::Aad::from(b"aad"),
+                &received_tag,
+                &mut ciphertext,
+            )
+            .unwrap();
+        assert_eq!(opened, &plaintext[..]);
+
+        let mut bad_tag = [0; aead::MAX_TAG_LEN];
+        bad_tag.copy_from_slice(tag.as_ref());
+        bad_tag[0] ^= 1;
+        let mut ciphertext = in_out.clone();
+        assert!(key
+            .open_in_place_separate_tag(
+                aead::Nonce::assume_unique_for_key(nonce),
+                aead::Aad::from(b"aad"),
+                &aead::Tag::from(bad_tag),
+                &mut ciphertext,
+            )
+            .is_err());
+        assert!(ciphertext.iter().all(|&b| b == 0));
+    }
+}
+
 #[test]
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
 fn test_aead_key_debug() {
@@ -442,6 +1088,31 @@ fn make_less_safe_key(algorithm: &'static aead::Algorithm, key: &[u8]) -> aead::
     aead::LessSafeKey::new(key)
 }
 
+#[cfg(feature = "nonce_reuse_check")]
+#[test]
+fn test_aead_nonce_reuse_check() {
+    // A broken nonce sequence that always returns the same nonce.
+    struct StuckNonceSequence;
+
+    impl aead::NonceSequence for StuckNonceSequence {
+        fn advance(&mut self) -> Result<aead::Nonce, error::Unspecified> {
+            Ok(aead::Nonce::assume_unique_for_key([0; aead::NONCE_LEN]))
+        }
+    }
+
+    let key = aead::UnboundKey::new(&aead::AES_128_GCM, &[0x42; 16]).unwrap();
+    let mut key: aead::SealingKey<_> = aead::BoundKey::new(key, StuckNonceSequence);
+    let mut in_out = b"first".to_vec();
+    key.seal_in_place_append_tag(aead::Aad::empty(), &mut in_out)
+        .unwrap();
+    let mut in_out = b"second".to_vec();
+    assert_eq!(
+        key.seal_in_place_append_tag(aead::Aad::empty(), &mut in_out),
+        Err(error::Unspecified)
+    );
+    assert_eq!(in_out, b"second");
+}
+
 struct OneNonceSequence(Option<aead::Nonce>);
 
 impl OneNonceSequence {
@@ -0,0 +1,94 @@
+use ring::{aes::xts, error, test, test_file};
+
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen_test::{wasm_bindgen_test, wasm_bindgen_test_configure};
+
+#[cfg(target_arch = "wasm32")]
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn aes_xts_ieee1619_test() {
+    test::run(test_file!("aes_xts_tests.txt"), |section, test_case| {
+        assert_eq!(section, "");
+        let key1 = test_case.consume_bytes("Key1");
+        let key2 = test_case.consume_bytes("Key2");
+        let sector = test_case
+            .consume_bytes("Sector")
+            .iter()
+            .fold(0u128, |acc, &b| (acc << 8) | u128::from(b));
+        let plaintext = test_case.consume_bytes("PTX");
+        let ciphertext = test_case.consume_bytes("CTX");
+
+        let key = xts::XtsKey::new(&key1, &key2)?;
+
+        let mut in_out = plaintext.clone();
+        key.encrypt_sector(sector, &mut in_out)?;
+        assert_eq!(in_out, ciphertext);
+
+        key.decrypt_sector(sector, &mut in_out)?;
+        assert_eq!(in_out, plaintext);
+
+        // The sector index is part of the tweak.
+        let mut in_out = ciphertext.clone();
+        key.decrypt_sector(sector + 1, &mut in_out)?;
+        assert_ne!(in_out, plaintext);
+
+        Ok(())
+    });
+}
+
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn aes_xts_round_trip_test() {
+    let key = xts::XtsKey::new(&[1; 32], &[2; 32]).unwrap();
+    let plaintext: Vec<u8> = (0..100).map(|i| i as u8).collect();
+    // Every length from one block up to several blocks, including the
+    // lengths that need ciphertext stealing.
+    for len in xts::MIN_SECTOR_LEN..=plaintext.len() {
+        let mut in_out = plaintext[..len].to_vec();
+        key.encrypt_sector(u128::max_value(), &mut in_out).unwrap();
+        assert_ne!(&in_out[..], &plaintext[..len]);
+        key.decrypt_sector(u128::max_value(), &mut in_out).unwrap();
+        assert_eq!(&in_out[..], &plaintext[..len]);
+    }
+}
+
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn aes_xts_key_len_test() {
+    assert!(xts::XtsKey::new(&[1; 16], &[2; 16]).is_ok());
+    assert!(xts::XtsKey::new(&[1; 32], &[2; 32]).is_ok());
+    assert!(xts::XtsKey::new(&[1; 16], &[2; 32]).is_err());
+    assert!(xts::XtsKey::new(&[1; 32], &[2; 16]).is_err());
+    assert!(xts::XtsKey::new(&[1; 24], &[2; 24]).is_err());
+    assert!(xts::XtsKey::new(&[], &[]).is_err());
+}
+
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn aes_xts_sector_len_test() {
+    let key = xts::XtsKey::new(&[1; 16], &[2; 16]).unwrap();
+
+    let mut too_short = [0u8; xts::MIN_SECTOR_LEN - 1];
+    assert_eq!(
+        key.encrypt_sector(0, &mut too_short),
+        Err(error::Unspecified)
+    );
+    assert_eq!(
+        key.decrypt_sector(0, &mut too_short),
+        Err(error::Unspecified)
+    );
+    assert_eq!(too_short, [0u8; xts::MIN_SECTOR_LEN - 1]);
+
+    let mut too_long = vec![0u8; xts::MAX_SECTOR_LEN + 1];
+    assert_eq!(
+        key.encrypt_sector(0, &mut too_long),
+        Err(error::Unspecified)
+    );
+    assert_eq!(
+        key.decrypt_sector(0, &mut too_long),
+        Err(error::Unspecified)
+    );
+    assert!(too_long.iter().all(|&b| b == 0));
+}
@@ -14,7 +14,7 @@
 
 extern crate alloc;
 
-use ring::{agreement, error, rand, test, test_file};
+use ring::{agreement, error, hkdf, rand, test, test_file};
 
 #[test]
 fn agreement_traits() {
@@ -34,6 +34,17 @@ fn agreement_traits() {
 
     let public_key = private_key.compute_public_key().unwrap();
 
+    let static_private_key =
+        agreement::StaticPrivateKey::generate(&agreement::ECDH_P256, &rng).unwrap();
+
+    test::compile_time_assert_send::<agreement::StaticPrivateKey>();
+    test::compile_time_assert_sync::<agreement::StaticPrivateKey>();
+
+    assert_eq!(
+        format!("{:?}", &static_private_key),
+        "StaticPrivateKey { algorithm: Algorithm { curve: P256 } }"
+    );
+
     test::compile_time_assert_clone::<agreement::PublicKey>();
     test::compile_time_assert_send::<agreement::PublicKey>();
     test::compile_time_assert_sync::<agreement::PublicKey>();
@@ -119,6 +130,357 @@ fn agreement_agree_ephemeral() {
     });
 }
 
+#[cfg(feature = "dangerous")]
+#[test]
+fn agreement_ephemeral_private_key_from_bytes() {
+    test::run(test_file!("agreement_tests.txt"), |section, test_case| {
+        assert_eq!(section, "");
+
+        let curve_name = test_case.consume_string("Curve");
+        let alg = alg_from_curve_name(&curve_name);
+        let peer_public = agreement::UnparsedPublicKey::new(alg, test_case.consume_bytes("PeerQ"));
+
+        if test_case.consume_optional_string("Error").is_some() {
+            return Ok(());
+        }
+
+        let my_private = test_case.consume_bytes("D");
+        let my_public = test_case.consume_bytes("MyQ");
+        let output = test_case.consume_bytes("Output");
+
+        let my_private = agreement::EphemeralPrivateKey::from_bytes(alg, &my_private)?;
+        assert_eq!(my_private.algorithm(), alg);
+
+        let computed_public = my_private.compute_public_key()?;
+        assert_eq!(computed_public.as_ref(), &my_public[..]);
+
+        let result = agreement::agree_ephemeral(my_private, &peer_public, (), |key_material| {
+            assert_eq!(key_material, &output[..]);
+            Ok(())
+        });
+        assert_eq!(result, Ok(()));
+
+        Ok(())
+    });
+}
+
+#[test]
+fn agreement_agree_ephemeral_hkdf() {
+    const INFO: &[&[u8]] = &[b"agreement", b" hkdf"];
+
+    test::run(test_file!("agreement_tests.txt"), |section, test_case| {
+        assert_eq!(section, "");
+
+        let curve_name = test_case.consume_string("Curve");
+        let alg = alg_from_curve_name(&curve_name);
+        let peer_public = agreement::UnparsedPublicKey::new(alg, test_case.consume_bytes("PeerQ"));
+        let salt = hkdf::Salt::new(hkdf::HKDF_SHA256, b"salt");
+
+        if test_case.consume_optional_string("Error").is_some() {
+            // An invalid peer public key is rejected before HKDF is used.
+            let my_private =
+                agreement::EphemeralPrivateKey::generate(alg, &rand::SystemRandom::new())?;
+            let mut out = [0u8; 32];
+            assert_eq!(
+                agreement::agree_ephemeral_hkdf(my_private, &peer_public, &salt, INFO, &mut out),
+                Err(error::Unspecified)
+            );
+            return Ok(());
+        }
+
+        let my_private = test_case.consume_bytes("D");
+        let _ = test_case.consume_bytes("MyQ");
+        let output = test_case.consume_bytes("Output");
+
+        // The same as extracting and expanding the raw shared secret.
+        #[cfg(feature = "dangerous")]
+        {
+            let mut expected = [0u8; 42];
+            hkdf::expand_multi(&salt.extract(&output), INFO, &[42], &mut [&mut expected])?;
+
+            let my_private = agreement::EphemeralPrivateKey::from_bytes(alg, &my_private)?;
+            let mut out = [0u8; 42];
+            agreement::agree_ephemeral_hkdf(my_private, &peer_public, &salt, INFO, &mut out)?;
+            assert_eq!(&out[..], &expected[..]);
+        }
+        #[cfg(not(feature = "dangerous"))]
+        let _ = (my_private, output);
+
+        // `out` is too long for HKDF-Expand.
+        let my_private = agreement::EphemeralPrivateKey::generate(alg, &rand::SystemRandom::new())?;
+        let mut out = vec![0u8; 255 * 32 + 1];
+        assert_eq!(
+            agreement::agree_ephemeral_hkdf(my_private, &peer_public, &salt, INFO, &mut out),
+            Err(error::Unspecified)
+        );
+
+        Ok(())
+    });
+}
+
+#[cfg(feature = "dangerous")]
+#[test]
+fn agreement_ephemeral_private_key_from_bytes_invalid() {
+    // Wrong lengths.
+    for alg in &[
+        &agreement::ECDH_P256,
+        &agreement::ECDH_P384,
+        &agreement::X25519,
+    ] {
+        assert!(agreement::EphemeralPrivateKey::from_bytes(alg, &[]).is_err());
+        assert!(agreement::EphemeralPrivateKey::from_bytes(alg, &[1; 31]).is_err());
+        assert!(agreement::EphemeralPrivateKey::from_bytes(alg, &[1; 49]).is_err());
+    }
+
+    // Zero and values not less than the group order are out of range for the
+    // NIST curves.
+    for alg in &[&agreement::ECDH_P256, &agreement::ECDH_P384] {
+        let len = if *alg == &agreement::ECDH_P256 {
+            32
+        } else {
+            48
+        };
+        assert!(agreement::EphemeralPrivateKey::from_bytes(alg, &vec![0; len]).is_err());
+        assert!(agreement::EphemeralPrivateKey::from_bytes(alg, &vec![0xff; len]).is_err());
+        let mut one = vec![0; len];
+        one[len - 1] = 1;
+        assert!(agreement::EphemeralPrivateKey::from_bytes(alg, &one).is_ok());
+    }
+}
+
+#[test]
+fn agreement_public_key_from_private() {
+    test::run(test_file!("agreement_tests.txt"), |section, test_case| {
+        assert_eq!(section, "");
+
+        let curve_name = test_case.consume_string("Curve");
+        let alg = alg_from_curve_name(&curve_name);
+        let _ = test_case.consume_bytes("PeerQ");
+
+        if test_case.consume_optional_string("Error").is_some() {
+            return Ok(());
+        }
+
+        let my_private = test_case.consume_bytes("D");
+        let my_public = test_case.consume_bytes("MyQ");
+        let _ = test_case.consume_bytes("Output");
+
+        let computed_public = agreement::public_key_from_private(alg, &my_private)?;
+        assert_eq!(computed_public, my_public);
+
+        // The same as the public key of a `StaticPrivateKey`.
+        let static_private = agreement::StaticPrivateKey::from_bytes(alg, &my_private).unwrap();
+        assert_eq!(
+            static_private.compute_public_key()?.as_ref(),
+            &computed_public[..]
+        );
+
+        Ok(())
+    });
+
+    for alg in &[
+        &agreement::ECDH_P256,
+        &agreement::ECDH_P384,
+        &agreement::X25519,
+    ] {
+        assert!(agreement::public_key_from_private(alg, &[]).is_err());
+        assert!(agreement::public_key_from_private(alg, &[1; 31]).is_err());
+    }
+    for &(alg, len) in &[(&agreement::ECDH_P256, 32), (&agreement::ECDH_P384, 48)] {
+        assert!(agreement::public_key_from_private(alg, &vec![0; len]).is_err());
+        assert!(agreement::public_key_from_private(alg, &vec![0xff; len]).is_err());
+    }
+}
+
+#[test]
+fn agreement_reduce_scalar() {
+    test::run(
+        test_file!("agreement_reduce_scalar_tests.txt"),
+        |section, test_case| {
+            assert_eq!(section, "");
+
+            let curve_name = test_case.consume_string("Curve");
+            let alg = alg_from_curve_name(&curve_name);
+            let input = test_case.consume_bytes("Input");
+            let output = test_case.consume_bytes("Output");
+
+            let scalar = agreement::reduce_scalar(alg, &input)?;
+            assert_eq!(scalar.as_ref(), &output[..]);
+            assert_eq!(scalar.algorithm(), alg);
+
+            Ok(())
+        },
+    );
+}
+
+#[test]
+fn agreement_reduce_scalar_invalid() {
+    for (alg, scalar_len) in &[(&agreement::ECDH_P256, 32), (&agreement::ECDH_P384, 48)] {
+        assert!(agreement::reduce_scalar(alg, &[]).is_err());
+        assert!(agreement::reduce_scalar(alg, &vec![0xff; 2 * scalar_len]).is_ok());
+        assert!(agreement::reduce_scalar(alg, &vec![0; (2 * scalar_len) + 1]).is_err());
+    }
+    assert!(agreement::reduce_scalar(&agreement::X25519, &[1; 64]).is_err());
+}
+
+#[test]
+fn agreement_generate_private_scalar() {
+    let rng = rand::SystemRandom::new();
+    for (alg, scalar_len) in &[(&agreement::ECDH_P256, 32), (&agreement::ECDH_P384, 48)] {
+        let scalar = agreement::generate_private_scalar(alg, &rng).unwrap();
+        assert_eq!(scalar.as_ref().len(), *scalar_len);
+        assert!(scalar.as_ref().iter().any(|b| *b != 0));
+        assert!(scalar.algorithm() == *alg);
+
+        // Out-of-range candidates (all ones exceeds n; zero is excluded) are
+        // rejected and sampling continues.
+        let too_big = vec![0xff; *scalar_len];
+        let zero = vec![0; *scalar_len];
+        let mut one = vec![0; *scalar_len];
+        one[scalar_len - 1] = 1;
+        let rng = test::rand::FixedSliceSequenceRandom {
+            bytes: &[&too_big, &zero, &one],
+            current: core::cell::UnsafeCell::new(0),
+        };
+        let scalar = agreement::generate_private_scalar(alg, &rng).unwrap();
+        assert_eq!(scalar.as_ref(), &one[..]);
+    }
+    assert!(agreement::generate_private_scalar(&agreement::X25519, &rng).is_err());
+}
+
+#[test]
+fn agreement_agree_static() {
+    let rng = rand::SystemRandom::new();
+
+    test::run(test_file!("agreement_tests.txt"), |section, test_case| {
+        assert_eq!(section, "");
+
+        let curve_name = test_case.consume_string("Curve");
+        let alg = alg_from_curve_name(&curve_name);
+        let peer_public = agreement::UnparsedPublicKey::new(alg, test_case.consume_bytes("PeerQ"));
+
+        match test_case.consume_optional_string("Error") {
+            None => {
+                let my_private = test_case.consume_bytes("D");
+                let my_private = agreement::StaticPrivateKey::from_bytes(alg, &my_private).unwrap();
+                let my_public = test_case.consume_bytes("MyQ");
+                let output = test_case.consume_bytes("Output");
+
+                assert_eq!(my_private.algorithm(), alg);
+
+                let computed_public = my_private.compute_public_key().unwrap();
+                assert_eq!(computed_public.as_ref(), &my_public[..]);
+
+                // The same private key can be used more than once.
+                for _ in 0..2 {
+                    let result =
+                        agreement::agree_static(&my_private, &peer_public, (), |key_material| {
+                            assert_eq!(key_material, &output[..]);
+                            Ok(())
+                        });
+                    assert_eq!(result, Ok(()));
+                }
+            }
+
+            Some(_) => {
+                let dummy_private_key = agreement::StaticPrivateKey::generate(alg, &rng)?;
+                fn kdf_not_called(_: &[u8]) -> Result<(), ()> {
+                    panic!(
+                        "The KDF was called during ECDH when the peer's \
+                         public key is invalid."
+                    );
+                }
+                assert!(agreement::agree_static(
+                    &dummy_private_key,
+                    &peer_public,
+                    (),
+                    kdf_not_called
+                )
+                .is_err());
+            }
+        }
+
+        Ok(())
+    });
+}
+
+#[test]
+fn agreement_static_private_key_from_pkcs8() {
+    test::run(
+        test_file!("agreement_from_pkcs8_tests.txt"),
+        |section, test_case| {
+            assert_eq!(section, "");
+
+            let curve_name = test_case.consume_string("Curve");
+            let alg = alg_from_curve_name(&curve_name);
+            let input = test_case.consume_bytes("Input");
+
+            let result = agreement::StaticPrivateKey::from_pkcs8(alg, &input);
+            match test_case.consume_optional_string("Error") {
+                None => {
+                    let my_public = test_case.consume_bytes("MyQ");
+                    let private_key = result.unwrap();
+                    assert_eq!(private_key.algorithm(), alg);
+                    let computed_public = private_key.compute_public_key().unwrap();
+                    assert_eq!(computed_public.as_ref(), &my_public[..]);
+                }
+                Some(error) => {
+                    assert_eq!(format!("{}", result.unwrap_err()), error);
+                }
+            }
+
+            Ok(())
+        },
+    );
+}
+
+#[test]
+fn agreement_static_ephemeral_agree() {
+    let rng = rand::SystemRandom::new();
+    for alg in &[
+        &agreement::ECDH_P256,
+        &agreement::ECDH_P384,
+        &agreement::X25519,
+    ] {
+        let static_private = agreement::StaticPrivateKey::generate(alg, &rng).unwrap();
+        let static_public = static_private.compute_public_key().unwrap();
+        let static_public = agreement::UnparsedPublicKey::new(alg, static_public);
+
+        let ephemeral_private = agreement::EphemeralPrivateKey::generate(alg, &rng).unwrap();
+        let ephemeral_public = ephemeral_private.compute_public_key().unwrap();
+        let ephemeral_public = agreement::UnparsedPublicKey::new(alg, ephemeral_public);
+
+        let a = agreement::agree_ephemeral(
+            ephemeral_private,
+            &static_public,
+            error::Unspecified,
+            |key_material| Ok(Vec::from(key_material)),
+        )
+        .unwrap();
+        let b = agreement::agree_static(
+            &static_private,
+            &ephemeral_public,
+            error::Unspecified,
+            |key_material| Ok(Vec::from(key_material)),
+        )
+        .unwrap();
+        assert_eq!(a, b);
+
+        // Keys for a different algorithm are rejected.
+        let other_alg = if *alg == &agreement::X25519 {
+            &agreement::ECDH_P256
+        } else {
+            &agreement::X25519
+        };
+        let other_public = agreement::StaticPrivateKey::generate(other_alg, &rng)
+            .unwrap()
+            .compute_public_key()
+            .unwrap();
+        let other_public = agreement::UnparsedPublicKey::new(other_alg, other_public);
+        assert!(agreement::agree_static(&static_private, &other_public, (), |_| Ok(())).is_err());
+    }
+}
+
 #[test]
 fn test_agreement_ecdh_x25519_rfc_iterated() {
     let mut k = h("0900000000000000000000000000000000000000000000000000000000000000");
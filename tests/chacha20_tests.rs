@@ -0,0 +1,68 @@
+use ring::{aead::chacha20, error, test};
+
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen_test::{wasm_bindgen_test, wasm_bindgen_test_configure};
+
+#[cfg(target_arch = "wasm32")]
+wasm_bindgen_test_configure!(run_in_browser);
+
+fn rfc8439_key() -> chacha20::Key {
+    let mut key_bytes = [0u8; chacha20::KEY_LEN];
+    for (i, b) in key_bytes.iter_mut().enumerate() {
+        *b = i as u8;
+    }
+    chacha20::Key::new(key_bytes)
+}
+
+const RFC8439_NONCE: [u8; 12] = [0, 0, 0, 0, 0, 0, 0, 0x4a, 0, 0, 0, 0];
+
+const RFC8439_PLAINTEXT: &[u8] = b"Ladies and Gentlemen of the class of '99: \
+    If I could offer you only one tip for the future, sunscreen would be it.";
+
+/// RFC 8439 Section 2.4.2.
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn chacha20_rfc8439_test() {
+    let key = rfc8439_key();
+    let expected = test::from_hex(
+        "6e2e359a2568f98041ba0728dd0d6981e97e7aec1d4360c20a27afccfd9fae0b\
+         f91b65c5524733ab8f593dabcd62b3571639d624e65152ab8f530c359f0861d8\
+         07ca0dbf500d6a6156a38e088a22b65e52bc514d16ccf806818ce91ab7793736\
+         5af90bbf74a35be6b40b8eedf2785e42874d",
+    )
+    .unwrap();
+
+    let mut in_out = RFC8439_PLAINTEXT.to_vec();
+    key.apply_keystream(&RFC8439_NONCE, 1, &mut in_out).unwrap();
+    assert_eq!(in_out, expected);
+
+    // Decryption is the same operation as encryption.
+    key.apply_keystream(&RFC8439_NONCE, 1, &mut in_out).unwrap();
+    assert_eq!(in_out, RFC8439_PLAINTEXT);
+
+    // Starting at a later block skips the corresponding part of the keystream.
+    let mut in_out = RFC8439_PLAINTEXT[64..].to_vec();
+    key.apply_keystream(&RFC8439_NONCE, 2, &mut in_out).unwrap();
+    assert_eq!(in_out, &expected[64..]);
+}
+
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn chacha20_counter_overflow_test() {
+    let key = rfc8439_key();
+
+    // The last block of the keystream may be used.
+    let mut block = [0u8; 64];
+    assert_eq!(
+        key.apply_keystream(&RFC8439_NONCE, u32::max_value(), &mut block),
+        Ok(())
+    );
+
+    // Going beyond it fails, without modifying the input.
+    let mut too_long = [0u8; 65];
+    assert_eq!(
+        key.apply_keystream(&RFC8439_NONCE, u32::max_value(), &mut too_long),
+        Err(error::Unspecified)
+    );
+    assert!(too_long.iter().all(|b| *b == 0));
+}
@@ -13,7 +13,7 @@
 // CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
 
 #![cfg(any(not(target_arch = "wasm32"), feature = "wasm32_c"))]
-use ring::{constant_time, error, rand};
+use ring::{constant_time, error, rand, test, test_file};
 
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen_test::{wasm_bindgen_test, wasm_bindgen_test_configure};
@@ -80,3 +80,213 @@ fn test_verify_slices_are_equal() {
         }
     }
 }
+
+#[test]
+#[cfg_attr(all(target_arch = "wasm32", feature = "wasm32_c"), wasm_bindgen_test)]
+fn test_verify_slices_are_equal_checked() {
+    use constant_time::Mismatch;
+
+    let a = [1u8, 2, 3, 4];
+    let mut b = a;
+
+    assert_eq!(
+        constant_time::verify_slices_are_equal_checked(&a, &b),
+        Ok(())
+    );
+    assert_eq!(
+        constant_time::verify_slices_are_equal_checked(&a, &b[..3]),
+        Err(Mismatch::Length)
+    );
+    assert_eq!(
+        constant_time::verify_slices_are_equal_checked(&[], &b),
+        Err(Mismatch::Length)
+    );
+
+    b[3] ^= 1;
+    assert_eq!(
+        constant_time::verify_slices_are_equal_checked(&a, &b),
+        Err(Mismatch::Value)
+    );
+    // The unchecked variant doesn't distinguish the two cases.
+    assert_eq!(
+        constant_time::verify_slices_are_equal(&a, &b),
+        Err(error::Unspecified)
+    );
+    assert_eq!(
+        constant_time::verify_slices_are_equal(&a, &b[..3]),
+        Err(error::Unspecified)
+    );
+}
+
+#[test]
+#[cfg_attr(all(target_arch = "wasm32", feature = "wasm32_c"), wasm_bindgen_test)]
+fn test_is_zero() {
+    assert!(constant_time::is_zero(&[]));
+    assert!(constant_time::is_zero(&[0]));
+    assert!(constant_time::is_zero(&[0u8; 32]));
+    assert!(constant_time::is_zero(&[0u8; 200]));
+
+    for len in 1..=130 {
+        for i in 0..len {
+            for &b in &[0x01, 0x80, 0xff] {
+                let mut bytes = vec![0u8; len];
+                bytes[i] = b;
+                assert!(!constant_time::is_zero(&bytes));
+            }
+        }
+    }
+}
+
+#[test]
+#[cfg_attr(all(target_arch = "wasm32", feature = "wasm32_c"), wasm_bindgen_test)]
+fn test_select_word() {
+    assert_eq!(
+        constant_time::mask_from_bool(true),
+        constant_time::Word::max_value()
+    );
+    assert_eq!(constant_time::mask_from_bool(false), 0);
+
+    const VALUES: &[constant_time::Word] =
+        &[0, 1, 0x8000_0000_0000_0000, 0x0123_4567_89ab_cdef, !0];
+    for &a in VALUES {
+        for &b in VALUES {
+            let t = constant_time::mask_from_bool(true);
+            let f = constant_time::mask_from_bool(false);
+            assert_eq!(constant_time::select_word(t, a, b), a);
+            assert_eq!(constant_time::select_word(f, a, b), b);
+        }
+    }
+}
+
+#[test]
+#[cfg_attr(all(target_arch = "wasm32", feature = "wasm32_c"), wasm_bindgen_test)]
+fn test_gf128_mul() {
+    test::run(
+        test_file!("constant_time_gf128_mul_tests.txt"),
+        |section, test_case| {
+            assert_eq!(section, "");
+            let a = test_case.consume_bytes("A");
+            let b = test_case.consume_bytes("B");
+            let product = test_case.consume_bytes("Product");
+
+            let mut a_block = [0u8; 16];
+            a_block.copy_from_slice(&a);
+            let mut b_block = [0u8; 16];
+            b_block.copy_from_slice(&b);
+            assert_eq!(
+                &constant_time::gf128_mul(a_block, b_block)[..],
+                &product[..]
+            );
+            assert_eq!(
+                &constant_time::gf128_mul(b_block, a_block)[..],
+                &product[..]
+            );
+            Ok(())
+        },
+    );
+}
+
+#[test]
+#[cfg_attr(all(target_arch = "wasm32", feature = "wasm32_c"), wasm_bindgen_test)]
+fn test_base64() {
+    use constant_time::Base64Alphabet::{Standard, UrlSafe};
+
+    // From RFC 4648 Section 10.
+    const RFC4648: &[(&str, &str)] = &[
+        ("", ""),
+        ("f", "Zg=="),
+        ("fo", "Zm8="),
+        ("foo", "Zm9v"),
+        ("foob", "Zm9vYg=="),
+        ("fooba", "Zm9vYmE="),
+        ("foobar", "Zm9vYmFy"),
+    ];
+
+    let mut buf = [0u8; 64];
+    for (decoded, encoded) in RFC4648 {
+        for alphabet in &[Standard, UrlSafe] {
+            assert_eq!(
+                constant_time::base64_encode(decoded.as_bytes(), *alphabet, &mut buf),
+                Ok(encoded.as_bytes())
+            );
+            assert_eq!(
+                constant_time::base64_decode(encoded.as_bytes(), *alphabet, &mut buf),
+                Ok(decoded.as_bytes())
+            );
+            // Padding is optional when decoding.
+            let unpadded = encoded.trim_end_matches('=');
+            assert_eq!(
+                constant_time::base64_decode(unpadded.as_bytes(), *alphabet, &mut buf),
+                Ok(decoded.as_bytes())
+            );
+        }
+    }
+
+    // Every value maps to the right character in each alphabet.
+    const STANDARD: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    const URL_SAFE: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+    for (alphabet, chars) in &[(Standard, STANDARD), (UrlSafe, URL_SAFE)] {
+        // Three bytes per four characters; 64 characters encode 48 bytes.
+        let mut decoded = [0u8; 48];
+        let decoded = constant_time::base64_decode(chars, *alphabet, &mut decoded)
+            .unwrap()
+            .to_vec();
+        assert_eq!(
+            constant_time::base64_encode(&decoded, *alphabet, &mut buf),
+            Ok(*chars)
+        );
+    }
+
+    // Round trips.
+    let input: [u8; 256] = rand::generate(&rand::SystemRandom::new()).unwrap().expose();
+    let mut encoded = [0u8; 344];
+    let mut decoded = [0u8; 256];
+    for len in 0..=input.len() {
+        for alphabet in &[Standard, UrlSafe] {
+            let e = constant_time::base64_encode(&input[..len], *alphabet, &mut encoded).unwrap();
+            assert_eq!(e.len(), ((len + 2) / 3) * 4);
+            let d = constant_time::base64_decode(e, *alphabet, &mut decoded).unwrap();
+            assert_eq!(d, &input[..len]);
+        }
+    }
+
+    // Invalid inputs.
+    for (alphabet, invalid) in &[
+        (Standard, &b"Z"[..]),
+        (Standard, b"Zg="),
+        (Standard, b"Zg=a"),
+        (Standard, b"Z==="),
+        (Standard, b"===="),
+        (Standard, b"Zh=="), // Nonzero trailing bits.
+        (Standard, b"Zm9="), // Nonzero trailing bits.
+        (Standard, b"Zm9v!A=="),
+        (Standard, b"Zm9v Yg=="),
+        (Standard, b"-_8="),
+        (UrlSafe, b"+/8="),
+        (UrlSafe, b"Zm\x00v"),
+    ] {
+        let mut out = [0u8; 16];
+        assert_eq!(
+            constant_time::base64_decode(invalid, *alphabet, &mut out),
+            Err(error::Unspecified)
+        );
+    }
+
+    // The output is zeroed on failure.
+    let mut out = [0xffu8; 6];
+    assert_eq!(
+        constant_time::base64_decode(b"Zm9v!A==", Standard, &mut out),
+        Err(error::Unspecified)
+    );
+    assert_eq!(out, [0, 0, 0, 0, 0xff, 0xff]);
+
+    // Output buffers that are too small.
+    assert_eq!(
+        constant_time::base64_encode(b"foo", Standard, &mut buf[..3]),
+        Err(error::Unspecified)
+    );
+    assert_eq!(
+        constant_time::base64_decode(b"Zm9vYg==", Standard, &mut buf[..3]),
+        Err(error::Unspecified)
+    );
+}
@@ -47,13 +47,90 @@ fn digest_misc() {
     });
 }
 
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn digest_update_chunks() {
+    let data: Vec<u8> = (0..1000).map(|i| (i & 0xff) as u8).collect();
+    // Chunk boundaries both aligned and unaligned to the block length,
+    // including empty chunks.
+    let splits: &[&[usize]] = &[&[], &[0], &[1], &[64, 128], &[63, 63, 200, 999], &[1000]];
+    for alg in &[
+        &digest::SHA1_FOR_LEGACY_USE_ONLY,
+        &digest::SHA256,
+        &digest::SHA384,
+        &digest::SHA512,
+    ] {
+        let expected = digest::digest(alg, &data);
+        for split in splits {
+            let mut chunks = Vec::new();
+            let mut start = 0;
+            for &end in split.iter() {
+                chunks.push(&data[start..end]);
+                start = end;
+            }
+            chunks.push(&data[start..]);
+
+            let mut ctx = digest::Context::new(alg);
+            ctx.update_chunks(chunks.iter().cloned());
+            assert_eq!(ctx.finish().as_ref(), expected.as_ref());
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn digest_update_from_reader() {
+    // Lengths around the internal chunk length, which is a multiple of the
+    // block length.
+    for len in &[0, 1, 63, 64, 65, 128, 8191, 8192, 8193, 3 * 8192 + 17] {
+        let data: Vec<u8> = (0..*len).map(|i| (i & 0xff) as u8).collect();
+        for alg in &[
+            &digest::SHA1_FOR_LEGACY_USE_ONLY,
+            &digest::SHA256,
+            &digest::SHA384,
+            &digest::SHA512,
+        ] {
+            let mut ctx = digest::Context::new(alg);
+            let mut reader = std::io::Cursor::new(&data);
+            assert_eq!(ctx.update_from_reader(&mut reader).unwrap(), *len as u64);
+            let actual = ctx.finish();
+
+            let expected = digest::digest(alg, &data);
+            assert_eq!(expected.as_ref(), actual.as_ref());
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn digest_io_write() {
+    use std::io::Write;
+
+    let data: Vec<u8> = (0..1000).map(|i| (i & 0xff) as u8).collect();
+    for alg in &[
+        &digest::SHA1_FOR_LEGACY_USE_ONLY,
+        &digest::SHA256,
+        &digest::SHA384,
+        &digest::SHA512,
+    ] {
+        let mut ctx = digest::Context::new(alg);
+        ctx.write_all(&data[..1]).unwrap();
+        ctx.write_all(&data[1..500]).unwrap();
+        ctx.flush().unwrap();
+        assert_eq!(ctx.write(&data[500..]).unwrap(), 500);
+        let actual = ctx.finish();
+
+        let expected = digest::digest(alg, &data);
+        assert_eq!(expected.as_ref(), actual.as_ref());
+    }
+}
+
 mod digest_shavs {
     use ring::{digest, test};
 
     fn run_known_answer_test(digest_alg: &'static digest::Algorithm, test_file: test::File) {
-        let section_name = &format!("L = {}", digest_alg.output_len);
-        test::run(test_file, |section, test_case| {
-            assert_eq!(section_name, section);
+        test::run_sectioned(test_file, |section, test_case| {
+            assert_eq!(section.get_usize("L"), digest_alg.output_len);
             let len_bits = test_case.consume_usize("Len");
 
             let mut msg = test_case.consume_bytes("Msg");
@@ -126,13 +203,11 @@ mod digest_shavs {
     }
 
     fn run_monte_carlo_test(digest_alg: &'static digest::Algorithm, test_file: test::File) {
-        let section_name = &format!("L = {}", digest_alg.output_len);
-
         let mut expected_count: isize = -1;
         let mut seed = Vec::with_capacity(digest_alg.output_len);
 
-        test::run(test_file, |section, test_case| {
-            assert_eq!(section_name, section);
+        test::run_sectioned(test_file, |section, test_case| {
+            assert_eq!(section.get_usize("L"), digest_alg.output_len);
 
             if expected_count == -1 {
                 seed.extend(test_case.consume_bytes("Seed"));
@@ -315,6 +390,290 @@ test_large_digest!(
 // TODO: test_large_digest!(digest_test_large_digest_sha512_256,
 //                            digest::SHA512_256, 256 / 8, [ ... ]);
 
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn test_algorithm_lengths() {
+    // (algorithm, block_len, chaining_len, output_len)
+    for &(alg, block_len, chaining_len, output_len) in &[
+        (&digest::SHA1_FOR_LEGACY_USE_ONLY, 64, 20, 20),
+        (&digest::SHA256, 64, 32, 32),
+        (&digest::SHA384, 128, 64, 48),
+        (&digest::SHA512, 128, 64, 64),
+        (&digest::SHA512_256, 128, 64, 32),
+        (&digest::SM3, 64, 32, 32),
+    ] {
+        assert_eq!(alg.block_len, block_len);
+        assert_eq!(alg.chaining_len, chaining_len);
+        assert_eq!(alg.output_len, output_len);
+        assert!(alg.block_len <= digest::MAX_BLOCK_LEN);
+        assert!(alg.chaining_len <= digest::MAX_CHAINING_LEN);
+        assert!(alg.output_len <= digest::MAX_OUTPUT_LEN);
+    }
+}
+
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn test_is_fips_approved() {
+    assert!(digest::SHA1_FOR_LEGACY_USE_ONLY.is_fips_approved());
+    assert!(digest::SHA256.is_fips_approved());
+    assert!(digest::SHA384.is_fips_approved());
+    assert!(digest::SHA512.is_fips_approved());
+    assert!(digest::SHA512_256.is_fips_approved());
+    assert!(!digest::SM3.is_fips_approved());
+}
+
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn test_context_bytes_processed() {
+    let data = [0x5au8; 1000];
+    for &alg in &[
+        &digest::SHA1_FOR_LEGACY_USE_ONLY,
+        &digest::SHA256,
+        &digest::SHA384,
+        &digest::SHA512,
+    ] {
+        let mut ctx = digest::Context::new(alg);
+        assert_eq!(ctx.bytes_processed(), 0);
+
+        // Chunks both smaller and larger than the block length, so that
+        // bytes are both buffered and processed in whole blocks.
+        let mut expected = 0;
+        for &len in &[
+            0,
+            1,
+            alg.block_len - 2,
+            1,
+            alg.block_len,
+            3 * alg.block_len + 5,
+        ] {
+            ctx.update(&data[..len]);
+            expected += len as u64;
+            assert_eq!(ctx.bytes_processed(), expected);
+        }
+
+        // Clones carry the count with them.
+        assert_eq!(ctx.clone().bytes_processed(), expected);
+    }
+}
+
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn test_context_finish_into() {
+    for &alg in &[
+        &digest::SHA1_FOR_LEGACY_USE_ONLY,
+        &digest::SHA256,
+        &digest::SHA384,
+        &digest::SHA512,
+        &digest::SHA512_256,
+    ] {
+        let expected = digest::digest(alg, b"hello, world");
+        let len = alg.output_len;
+
+        let mut out = [0u8; digest::MAX_OUTPUT_LEN + 1];
+        let mut ctx = digest::Context::new(alg);
+        ctx.update(b"hello, ");
+        ctx.update(b"world");
+        assert!(ctx.finish_into(&mut out[..len]).is_ok());
+        assert_eq!(&out[..len], expected.as_ref());
+        assert!(out[len..].iter().all(|&b| b == 0));
+
+        // The length must be exact, and `out` is untouched on failure.
+        for &wrong_len in &[0, len - 1, len + 1] {
+            let mut out = [0xffu8; digest::MAX_OUTPUT_LEN + 1];
+            let ctx = digest::Context::new(alg);
+            assert!(ctx.finish_into(&mut out[..wrong_len]).is_err());
+            assert!(out.iter().all(|&b| b == 0xff));
+        }
+    }
+}
+
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn test_digest_from_bytes() {
+    for &alg in &[
+        &digest::SHA1_FOR_LEGACY_USE_ONLY,
+        &digest::SHA256,
+        &digest::SHA384,
+        &digest::SHA512,
+        &digest::SHA512_256,
+    ] {
+        let computed = digest::digest(alg, b"hello, world");
+        let received = digest::Digest::from_bytes(alg, computed.as_ref()).unwrap();
+        assert_eq!(received.algorithm(), alg);
+        assert_eq!(received.as_ref(), computed.as_ref());
+        assert_eq!(format!("{:?}", received), format!("{:?}", computed));
+
+        let len = alg.output_len;
+        let too_long = vec![0; len + 1];
+        assert!(digest::Digest::from_bytes(alg, &too_long[..(len - 1)]).is_err());
+        assert!(digest::Digest::from_bytes(alg, &too_long).is_err());
+        assert!(digest::Digest::from_bytes(alg, &[]).is_err());
+    }
+
+    // The length is checked against the given algorithm.
+    let sha256 = digest::digest(&digest::SHA256, b"hello, world");
+    assert!(digest::Digest::from_bytes(&digest::SHA512_256, sha256.as_ref()).is_ok());
+    assert!(digest::Digest::from_bytes(&digest::SHA384, sha256.as_ref()).is_err());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn test_digest_serde() {
+    use serde::{
+        de::{value, IntoDeserializer},
+        Deserialize, Serialize,
+    };
+
+    // Serializes with serde's `Serializer` for `fmt::Formatter`, which
+    // writes strings without quotes.
+    struct Serialized<'a>(&'a digest::Digest);
+
+    impl core::fmt::Display for Serialized<'_> {
+        fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+            self.0.serialize(fmt)
+        }
+    }
+
+    fn deserialize(serialized: &str) -> Result<digest::Digest, value::Error> {
+        digest::Digest::deserialize(serialized.into_deserializer())
+    }
+
+    // The serialized names are part of the format.
+    for &(alg, name) in &[
+        (&digest::SHA1_FOR_LEGACY_USE_ONLY, "SHA1"),
+        (&digest::SHA256, "SHA256"),
+        (&digest::SHA384, "SHA384"),
+        (&digest::SHA512, "SHA512"),
+        (&digest::SHA512_256, "SHA512_256"),
+        (&digest::SM3, "SM3"),
+    ] {
+        let computed = digest::digest(alg, b"hello, world");
+        let serialized = Serialized(&computed).to_string();
+        assert_eq!(serialized, format!("{}:{:x}", name, computed));
+        let parsed = deserialize(&serialized).unwrap();
+        assert_eq!(parsed.algorithm(), alg);
+        assert_eq!(parsed.as_ref(), computed.as_ref());
+    }
+
+    let sha256 = digest::digest(&digest::SHA256, b"");
+    assert_eq!(
+        Serialized(&sha256).to_string(),
+        "SHA256:e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+    );
+
+    // Uppercase hex is accepted.
+    let parsed =
+        deserialize("SHA256:E3B0C44298FC1C149AFBF4C8996FB92427AE41E4649B934CA495991B7852B855")
+            .unwrap();
+    assert_eq!(parsed.as_ref(), sha256.as_ref());
+
+    // SHA-256 and SHA-512/256 have the same output length but are different
+    // algorithms.
+    let parsed =
+        deserialize("SHA512_256:e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855")
+            .unwrap();
+    assert_eq!(parsed.algorithm(), &digest::SHA512_256);
+
+    for invalid in &[
+        "",
+        ":",
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+        "SHA3:e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+        "sha256:e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+        // Too short, too long, and not hex.
+        "SHA256:e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b8",
+        "SHA256:e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b85500",
+        "SHA256:e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b8zz",
+        "SHA384:e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+    ] {
+        assert!(deserialize(invalid).is_err());
+    }
+
+    // Only strings are accepted.
+    assert!(
+        digest::Digest::deserialize(IntoDeserializer::<value::Error>::into_deserializer(42u32))
+            .is_err()
+    );
+}
+
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn test_tuple_hash() {
+    // The encoding is 01 10 61 62 01 00.
+    let computed = digest::tuple_hash(&digest::SHA256, &[b"ab", b""]);
+    assert_eq!(
+        computed.as_ref(),
+        &digest::digest(&digest::SHA256, &[0x01, 0x10, b'a', b'b', 0x01, 0x00]).as_ref()[..]
+    );
+    assert_eq!(
+        computed.as_ref(),
+        &test::from_hex("f2f69095ebb51edb6c27eb23064b8624b56aed43bf5d9166090c6981c5faf2af")
+            .unwrap()[..]
+    );
+
+    // The empty sequence encodes as nothing.
+    assert_eq!(
+        digest::tuple_hash(&digest::SHA256, &[]).as_ref(),
+        digest::digest(&digest::SHA256, b"").as_ref()
+    );
+
+    // A 512-byte component has a two-byte bit length: 02 10 00.
+    let long = [0x5a; 512];
+    let computed = digest::tuple_hash(&digest::SHA384, &[&long[..], b"x"]);
+    let mut encoded = vec![0x02, 0x10, 0x00];
+    encoded.extend_from_slice(&long);
+    encoded.extend_from_slice(&[0x01, 0x08, b'x']);
+    assert_eq!(computed.algorithm(), &digest::SHA384);
+    assert_eq!(
+        computed.as_ref(),
+        digest::digest(&digest::SHA384, &encoded).as_ref()
+    );
+
+    // Sequences that concatenate to the same bytes are distinguished.
+    let sequences: &[&[&[u8]]] = &[
+        &[b"abc"],
+        &[b"ab", b"c"],
+        &[b"a", b"bc"],
+        &[b"abc", b""],
+        &[b"", b"abc"],
+        &[b""],
+        &[],
+    ];
+    for (i, a) in sequences.iter().enumerate() {
+        for (j, b) in sequences.iter().enumerate() {
+            let a = digest::tuple_hash(&digest::SHA256, a);
+            let b = digest::tuple_hash(&digest::SHA256, b);
+            assert_eq!(a.as_ref() == b.as_ref(), i == j);
+        }
+    }
+}
+
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn test_has_hw_acceleration() {
+    for alg in &[
+        &digest::SHA1_FOR_LEGACY_USE_ONLY,
+        &digest::SHA384,
+        &digest::SHA512,
+        &digest::SHA512_256,
+        &digest::SM3,
+    ] {
+        assert!(!alg.has_hw_acceleration());
+    }
+
+    // The result must be stable across calls.
+    let sha256 = digest::SHA256.has_hw_acceleration();
+    assert_eq!(digest::SHA256.has_hw_acceleration(), sha256);
+
+    if cfg!(not(any(target_arch = "aarch64", target_arch = "arm"))) {
+        assert!(!sha256);
+    }
+    if cfg!(all(target_arch = "aarch64", target_vendor = "apple")) {
+        assert!(sha256);
+    }
+}
+
 #[test]
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
 fn test_fmt_algorithm() {
@@ -323,6 +682,7 @@ fn test_fmt_algorithm() {
     assert_eq!("SHA384", &format!("{:?}", digest::SHA384));
     assert_eq!("SHA512", &format!("{:?}", digest::SHA512));
     assert_eq!("SHA512_256", &format!("{:?}", digest::SHA512_256));
+    assert_eq!("SM3", &format!("{:?}", digest::SM3));
 }
 
 #[test]
@@ -359,3 +719,25 @@ fn digest_test_fmt() {
         &format!("{:?}", digest::digest(&digest::SHA512_256, b"hello, world"))
     );
 }
+
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn digest_test_fmt_hex() {
+    let d = digest::digest(&digest::SHA256, b"hello, world");
+    let expected = "09ca7e4eaa6e8ae9c7d261167129184883644d\
+                    07dfba7cbfbc4c8a2e08360d5b";
+    assert_eq!(expected, &format!("{}", d));
+    assert_eq!(expected, &format!("{:x}", d));
+    assert_eq!(expected.to_uppercase(), format!("{:X}", d));
+    assert_eq!(format!("0x{}", expected), format!("{:#x}", d));
+    assert_eq!(
+        format!("0x{}", expected.to_uppercase()),
+        format!("{:#X}", d)
+    );
+
+    let d = digest::digest(&digest::SHA1_FOR_LEGACY_USE_ONLY, b"hello, world");
+    assert_eq!(
+        "b7e23ec29af22b0b4e41da31e868d57226121c84",
+        &format!("{}", d)
+    );
+}
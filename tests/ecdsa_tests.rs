@@ -13,7 +13,7 @@
 // CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
 
 use ring::{
-    rand,
+    digest, pkcs8, rand,
     signature::{self, KeyPair},
     test, test_file,
 };
@@ -175,6 +175,34 @@ fn signature_ecdsa_verify_fixed_test() {
     );
 }
 
+#[test]
+fn ecdsa_key_pair_from_pkcs8_encrypted() {
+    const PRIVATE_KEY: &[u8] = include_bytes!("ecdsa_test_private_key_p256.p8");
+    const ENCRYPTED: &[u8] = include_bytes!("ecdsa_test_private_key_p256_encrypted.p8");
+    const PUBLIC_KEY: &[u8] = include_bytes!("ecdsa_test_public_key_p256.der");
+    const PASSWORD: &[u8] = b"correct horse battery staple";
+
+    assert_eq!(pkcs8::decrypt(ENCRYPTED, PASSWORD).unwrap(), PRIVATE_KEY);
+
+    let alg = &signature::ECDSA_P256_SHA256_FIXED_SIGNING;
+    let key_pair = signature::EcdsaKeyPair::from_pkcs8_encrypted(alg, ENCRYPTED, PASSWORD).unwrap();
+    assert_eq!(key_pair.public_key().as_ref(), PUBLIC_KEY);
+
+    assert!(signature::EcdsaKeyPair::from_pkcs8_encrypted(alg, ENCRYPTED, b"").is_err());
+    // A key that decrypts but doesn't parse is rejected the same way as a
+    // wrong password.
+    assert_eq!(
+        signature::EcdsaKeyPair::from_pkcs8_encrypted(
+            &signature::ECDSA_P384_SHA384_FIXED_SIGNING,
+            ENCRYPTED,
+            PASSWORD
+        )
+        .err()
+        .map(|e| e.description_()),
+        Some("DecryptionFailed")
+    );
+}
+
 #[test]
 fn ecdsa_test_public_key_coverage() {
     const PRIVATE_KEY: &[u8] = include_bytes!("ecdsa_test_private_key_p256.p8");
@@ -308,7 +336,230 @@ fn signature_ecdsa_sign_asn1_test() {
             let public_key = signature::UnparsedPublicKey::new(verification_alg, q);
             assert_eq!(public_key.verify(&msg, signature.as_ref()), Ok(()));
 
+            // The owned copy has the actual (variable) length of the DER
+            // encoding.
+            let signature_bytes = signature.to_vec();
+            assert_eq!(&signature_bytes[..], signature.as_ref());
+            assert_eq!(public_key.verify(&msg, &signature_bytes), Ok(()));
+
+            Ok(())
+        },
+    );
+}
+
+#[test]
+fn signature_ecdsa_secp256k1_wycheproof_test() {
+    test::run(
+        test_file!("ecdsa_secp256k1_wycheproof_tests.txt"),
+        |section, test_case| {
+            assert_eq!(section, "");
+
+            let msg = test_case.consume_bytes("Msg");
+            let public_key = test_case.consume_bytes("Q");
+            let sig = test_case.consume_bytes("Sig");
+            let sig_fixed = test_case.consume_optional_bytes("SigFixed");
+            let high_s = test_case
+                .consume_optional_string("HighS")
+                .map_or(false, |s| s == "true");
+            let expected_result = test_case.consume_string("Result");
+            let valid_low_s = match expected_result.as_str() {
+                "P" => true,
+                "F" => false,
+                s => panic!("{:?} is not a valid result", s),
+            };
+
+            let verify = |alg, sig: &[u8]| {
+                signature::UnparsedPublicKey::new(alg, &public_key)
+                    .verify(&msg, sig)
+                    .is_ok()
+            };
+
+            assert_eq!(
+                verify(&signature::ECDSA_SECP256K1_SHA256_ASN1_LOW_S, &sig),
+                valid_low_s
+            );
+            assert_eq!(
+                verify(&signature::ECDSA_SECP256K1_SHA256_ASN1, &sig),
+                valid_low_s || high_s
+            );
+            if let Some(sig_fixed) = sig_fixed {
+                assert_eq!(
+                    verify(&signature::ECDSA_SECP256K1_SHA256_FIXED_LOW_S, &sig_fixed),
+                    valid_low_s
+                );
+                assert_eq!(
+                    verify(&signature::ECDSA_SECP256K1_SHA256_FIXED, &sig_fixed),
+                    valid_low_s || high_s
+                );
+            }
+
             Ok(())
         },
     );
 }
+
+#[test]
+fn signature_ecdsa_normalize_s_test() {
+    test::run(
+        test_file!("ecdsa_normalize_s_tests.txt"),
+        |section, test_case| {
+            assert_eq!(section, "");
+
+            let curve_name = test_case.consume_string("Curve");
+            let alg = match curve_name.as_str() {
+                "P-256" => &signature::ECDSA_P256_SHA256_ASN1,
+                "P-384" => &signature::ECDSA_P384_SHA384_ASN1,
+                _ => unreachable!(),
+            };
+            let msg = test_case.consume_bytes("Msg");
+            let public_key = test_case.consume_bytes("Q");
+            let sig = test_case.consume_bytes("Sig");
+            let expected = test_case.consume_optional_bytes("Normalized");
+
+            let actual = alg.normalize_s(&sig);
+            assert_eq!(actual.as_ref().ok(), expected.as_ref());
+
+            if let Ok(normalized) = actual {
+                // Normalizing is idempotent.
+                assert_eq!(alg.normalize_s(&normalized), Ok(normalized.clone()));
+
+                let public_key = signature::UnparsedPublicKey::new(alg, &public_key);
+                assert_eq!(public_key.verify(&msg, &normalized), Ok(()));
+            }
+
+            Ok(())
+        },
+    );
+}
+
+#[test]
+fn signature_ecdsa_normalize_s_fixed_test() {
+    // n - 1, the largest valid `s`, is high; its negation is 1.
+    let mut sig = [0u8; 64];
+    sig[31] = 1;
+    sig[32..].copy_from_slice(
+        &test::from_hex("ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632550")
+            .unwrap(),
+    );
+    let mut expected = [0u8; 64];
+    expected[31] = 1;
+    expected[63] = 1;
+
+    let alg = &signature::ECDSA_P256_SHA256_FIXED;
+    assert_eq!(alg.normalize_s(&sig).unwrap(), &expected[..]);
+    assert_eq!(alg.normalize_s(&expected).unwrap(), &expected[..]);
+    assert!(alg.normalize_s(&sig[..63]).is_err());
+}
+
+#[test]
+fn signature_ecdsa_secp256k1_generate_sign_verify_test() {
+    let rng = rand::SystemRandom::new();
+    let msg = b"hello, world";
+
+    let pkcs8 = signature::EcdsaKeyPair::generate_pkcs8(
+        &signature::ECDSA_SECP256K1_SHA256_FIXED_SIGNING,
+        &rng,
+    )
+    .unwrap();
+    let key_pair = signature::EcdsaKeyPair::from_pkcs8(
+        &signature::ECDSA_SECP256K1_SHA256_FIXED_SIGNING,
+        pkcs8.as_ref(),
+    )
+    .unwrap();
+    assert!(signature::EcdsaKeyPair::from_pkcs8(
+        &signature::ECDSA_P256_SHA256_FIXED_SIGNING,
+        pkcs8.as_ref()
+    )
+    .is_err());
+
+    // Signing always produces "low S" signatures, so they are accepted even
+    // by the strict verification algorithm.
+    for _ in 0..8 {
+        let sig = key_pair.sign(&rng, msg).unwrap();
+        let public_key = signature::UnparsedPublicKey::new(
+            &signature::ECDSA_SECP256K1_SHA256_FIXED_LOW_S,
+            key_pair.public_key().as_ref(),
+        );
+        assert_eq!(public_key.verify(msg, sig.as_ref()), Ok(()));
+    }
+}
+
+#[test]
+fn signature_ecdsa_recover_public_key_test() {
+    test::run(
+        test_file!("ecdsa_recover_tests.txt"),
+        |section, test_case| {
+            assert_eq!(section, "");
+
+            let curve_name = test_case.consume_string("Curve");
+            let msg_hash = test_case.consume_bytes("MsgHash");
+            let sig = test_case.consume_bytes("Sig");
+            let recovery_id = test_case.consume_usize("RecoveryId") as u8;
+            let error = test_case.consume_optional_string("Error");
+
+            let alg = match curve_name.as_str() {
+                "P-256" => &signature::ECDSA_P256_SHA256_FIXED,
+                "P-384" => &signature::ECDSA_P384_SHA384_FIXED,
+                "secp256k1" => &signature::ECDSA_SECP256K1_SHA256_FIXED,
+                _ => {
+                    panic!("Unsupported curve: {}", curve_name);
+                }
+            };
+
+            let actual_result = alg.recover_public_key(&msg_hash, &sig, recovery_id);
+            match error {
+                None => {
+                    let expected = test_case.consume_bytes("Public");
+                    assert_eq!(actual_result.unwrap().as_ref(), &expected[..]);
+
+                    // The other parity recovers a different key, if any.
+                    if let Ok(other) = alg.recover_public_key(&msg_hash, &sig, recovery_id ^ 1) {
+                        assert_ne!(other.as_ref(), &expected[..]);
+                    }
+                }
+                Some(_) => assert!(actual_result.is_err()),
+            }
+
+            Ok(())
+        },
+    );
+}
+
+#[test]
+fn signature_ecdsa_sign_and_recover_test() {
+    let rng = rand::SystemRandom::new();
+    let msg = b"hello, world";
+
+    for &(signing_alg, verification_alg, digest_alg) in &[
+        (
+            &signature::ECDSA_P256_SHA256_ASN1_SIGNING,
+            &signature::ECDSA_P256_SHA256_ASN1,
+            &digest::SHA256,
+        ),
+        (
+            &signature::ECDSA_P384_SHA384_FIXED_SIGNING,
+            &signature::ECDSA_P384_SHA384_FIXED,
+            &digest::SHA384,
+        ),
+        (
+            &signature::ECDSA_SECP256K1_SHA256_FIXED_SIGNING,
+            &signature::ECDSA_SECP256K1_SHA256_FIXED,
+            &digest::SHA256,
+        ),
+    ] {
+        let pkcs8 = signature::EcdsaKeyPair::generate_pkcs8(signing_alg, &rng).unwrap();
+        let key_pair = signature::EcdsaKeyPair::from_pkcs8(signing_alg, pkcs8.as_ref()).unwrap();
+        let sig = key_pair.sign(&rng, msg).unwrap();
+        let msg_hash = digest::digest(digest_alg, msg);
+
+        let recovered = (0..4)
+            .filter_map(|recovery_id| {
+                verification_alg
+                    .recover_public_key(msg_hash.as_ref(), sig.as_ref(), recovery_id)
+                    .ok()
+            })
+            .filter(|public_key| public_key.as_ref() == key_pair.public_key().as_ref())
+            .count();
+        assert_eq!(recovered, 1);
+    }
+}
@@ -13,7 +13,7 @@
 // CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
 
 use ring::{
-    error,
+    error, pkcs8,
     signature::{self, Ed25519KeyPair, KeyPair},
     test, test_file,
 };
@@ -39,6 +39,14 @@ fn test_signature_ed25519() {
             assert_eq!(&expected_sig[..], actual_sig.as_ref());
         }
 
+        // Test deriving the key pair from the raw seed alone.
+        {
+            let key_pair = Ed25519KeyPair::from_seed_unchecked(&seed).unwrap();
+            assert_eq!(public_key, key_pair.public_key().as_ref());
+            let actual_sig = key_pair.sign(&msg);
+            assert_eq!(&expected_sig[..], actual_sig.as_ref());
+        }
+
         // Test PKCS#8 generation, parsing, and private-to-public calculations.
         let rng = test::rand::FixedSliceRandom { bytes: &seed };
         let pkcs8 = Ed25519KeyPair::generate_pkcs8(&rng).unwrap();
@@ -50,12 +58,24 @@ fn test_signature_ed25519() {
         assert_eq!(&expected_sig[..], actual_sig.as_ref());
 
         // Test Signature verification.
-        test_signature_verification(&public_key, &msg, &expected_sig, Ok(()));
+        test_signature_verification(
+            &signature::ED25519,
+            &public_key,
+            &msg,
+            &expected_sig,
+            Ok(()),
+        );
 
         let mut tampered_sig = expected_sig;
         tampered_sig[0] ^= 1;
 
-        test_signature_verification(&public_key, &msg, &tampered_sig, Err(error::Unspecified));
+        test_signature_verification(
+            &signature::ED25519,
+            &public_key,
+            &msg,
+            &tampered_sig,
+            Err(error::Unspecified),
+        );
 
         Ok(())
     });
@@ -77,13 +97,133 @@ fn test_signature_ed25519_verify() {
                 "F" => Err(error::Unspecified),
                 s => panic!("{:?} is not a valid result", s),
             };
-            test_signature_verification(&public_key, &msg, &sig, expected_result);
+            // None of these use non-canonical encodings.
+            let algs: [&'static dyn signature::VerificationAlgorithm; 2] =
+                [&signature::ED25519, &signature::ED25519_STRICT];
+            for &alg in &algs {
+                test_signature_verification(alg, &public_key, &msg, &sig, expected_result);
+            }
             Ok(())
         },
     );
 }
 
+#[test]
+fn test_signature_ed25519_verify_strict() {
+    test::run(
+        test_file!("ed25519_verify_strict_tests.txt"),
+        |section, test_case| {
+            assert_eq!(section, "");
+
+            let public_key = test_case.consume_bytes("PUB");
+            let msg = test_case.consume_bytes("MESSAGE");
+            let sig = test_case.consume_bytes("SIG");
+            let mut consume_result = |key| match test_case.consume_string(key).as_str() {
+                "P" => Ok(()),
+                "F" => Err(error::Unspecified),
+                s => panic!("{:?} is not a valid result", s),
+            };
+            let expected_result = consume_result("Result");
+            let expected_strict_result = consume_result("StrictResult");
+            test_signature_verification(
+                &signature::ED25519,
+                &public_key,
+                &msg,
+                &sig,
+                expected_result,
+            );
+            test_signature_verification(
+                &signature::ED25519_STRICT,
+                &public_key,
+                &msg,
+                &sig,
+                expected_strict_result,
+            );
+            Ok(())
+        },
+    );
+}
+
+/// Ed25519ctx test vectors from RFC 8032.
+#[test]
+fn test_signature_ed25519ctx() {
+    test::run(test_file!("ed25519ctx_tests.txt"), |section, test_case| {
+        assert_eq!(section, "");
+        let seed = test_case.consume_bytes("SEED");
+        let public_key = test_case.consume_bytes("PUB");
+        let msg = test_case.consume_bytes("MESSAGE");
+        let context = test_case.consume_bytes("CONTEXT");
+        let expected_sig = test_case.consume_bytes("SIG");
+
+        let key_pair = Ed25519KeyPair::from_seed_and_public_key(&seed, &public_key).unwrap();
+        let actual_sig = signature::ED25519CTX
+            .sign(&key_pair, &context, &msg)
+            .unwrap();
+        assert_eq!(&expected_sig[..], actual_sig.as_ref());
+
+        assert_eq!(
+            signature::ED25519CTX.verify(&public_key, &context, &msg, &expected_sig),
+            Ok(())
+        );
+
+        // The signature is bound to the context.
+        let mut other_context = context.clone();
+        other_context[0] ^= 1;
+        assert_eq!(
+            signature::ED25519CTX.verify(&public_key, &other_context, &msg, &expected_sig),
+            Err(error::Unspecified)
+        );
+        assert_eq!(
+            signature::ED25519CTX.verify(&public_key, &[], &msg, &expected_sig),
+            Err(error::Unspecified)
+        );
+
+        // Ed25519ctx signatures aren't Ed25519 signatures.
+        test_signature_verification(
+            &signature::ED25519,
+            &public_key,
+            &msg,
+            &expected_sig,
+            Err(error::Unspecified),
+        );
+
+        Ok(())
+    });
+}
+
+#[test]
+fn test_ed25519ctx_context_len() {
+    const PRIVATE_KEY: &[u8] = include_bytes!("ed25519_test_private_key.bin");
+    let key_pair = Ed25519KeyPair::from_seed_unchecked(PRIVATE_KEY).unwrap();
+    let public_key = key_pair.public_key().as_ref();
+    let msg = b"message";
+
+    for &context_len in &[0, 1, 255] {
+        let context = vec![0x5a; context_len];
+        let sig = signature::ED25519CTX
+            .sign(&key_pair, &context, msg)
+            .unwrap();
+        assert_eq!(
+            signature::ED25519CTX.verify(public_key, &context, msg, sig.as_ref()),
+            Ok(())
+        );
+    }
+
+    let context = vec![0x5a; 256];
+    assert!(signature::ED25519CTX
+        .sign(&key_pair, &context, msg)
+        .is_err());
+    let sig = signature::ED25519CTX
+        .sign(&key_pair, &context[..255], msg)
+        .unwrap();
+    assert_eq!(
+        signature::ED25519CTX.verify(public_key, &context, msg, sig.as_ref()),
+        Err(error::Unspecified)
+    );
+}
+
 fn test_signature_verification(
+    alg: &'static dyn signature::VerificationAlgorithm,
     public_key: &[u8],
     msg: &[u8],
     sig: &[u8],
@@ -91,7 +231,7 @@ fn test_signature_verification(
 ) {
     assert_eq!(
         expected_result,
-        signature::UnparsedPublicKey::new(&signature::ED25519, public_key).verify(msg, sig)
+        signature::UnparsedPublicKey::new(alg, public_key).verify(msg, sig)
     );
 }
 
@@ -112,6 +252,38 @@ fn test_ed25519_from_seed_and_public_key_misuse() {
     assert!(Ed25519KeyPair::from_seed_and_public_key(PUBLIC_KEY, PRIVATE_KEY).is_err());
 }
 
+#[test]
+fn test_ed25519_from_seed_unchecked_misuse() {
+    const PRIVATE_KEY: &[u8] = include_bytes!("ed25519_test_private_key.bin");
+    const PUBLIC_KEY: &[u8] = include_bytes!("ed25519_test_public_key.bin");
+
+    let key_pair = Ed25519KeyPair::from_seed_unchecked(PRIVATE_KEY).unwrap();
+    assert_eq!(key_pair.public_key().as_ref(), PUBLIC_KEY);
+
+    // The seed must be exactly 32 bytes.
+    let mut long_seed = PRIVATE_KEY.to_vec();
+    long_seed.push(0);
+    for seed in &[&PRIVATE_KEY[..31], &long_seed[..], &[]] {
+        let err = Ed25519KeyPair::from_seed_unchecked(seed).unwrap_err();
+        assert_eq!(err.description_(), "InvalidEncoding");
+    }
+}
+
+#[test]
+fn test_ed25519_from_pkcs8_encrypted() {
+    const PRIVATE_KEY: &[u8] = include_bytes!("ed25519_test_private_key.p8");
+    const ENCRYPTED: &[u8] = include_bytes!("ed25519_test_private_key_encrypted.p8");
+    const PUBLIC_KEY: &[u8] = include_bytes!("ed25519_test_public_key.der");
+    const PASSWORD: &[u8] = b"correct horse battery staple";
+
+    assert_eq!(pkcs8::decrypt(ENCRYPTED, PASSWORD).unwrap(), PRIVATE_KEY);
+
+    let key_pair = Ed25519KeyPair::from_pkcs8_encrypted(ENCRYPTED, PASSWORD).unwrap();
+    assert_eq!(key_pair.public_key().as_ref(), PUBLIC_KEY);
+
+    assert!(Ed25519KeyPair::from_pkcs8_encrypted(ENCRYPTED, b"correct horse").is_err());
+}
+
 #[test]
 fn test_ed25519_from_pkcs8_unchecked() {
     // Just test that we can parse the input.
@@ -12,7 +12,7 @@
 // OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
 // CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
 
-use ring::{digest, error, hkdf, test, test_file};
+use ring::{digest, error, hkdf, hmac, test, test_file};
 
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen_test::{wasm_bindgen_test, wasm_bindgen_test_configure};
@@ -29,17 +29,25 @@ fn hkdf_tests() {
             let digest_alg = test_case
                 .consume_digest_alg("Hash")
                 .ok_or(error::Unspecified)?;
-            if digest_alg == &digest::SHA256 {
-                hkdf::HKDF_SHA256
-            } else {
-                // TODO: add test vectors for other algorithms
-                panic!("unsupported algorithm: {:?}", digest_alg);
-            }
+            let hmac_alg = [
+                hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY,
+                hmac::HMAC_SHA256,
+                hmac::HMAC_SHA384,
+                hmac::HMAC_SHA512,
+                hmac::HMAC_SM3,
+            ]
+            .iter()
+            .copied()
+            .find(|hmac_alg| hmac_alg.digest_algorithm() == digest_alg)
+            .unwrap_or_else(|| panic!("unsupported algorithm: {:?}", digest_alg));
+            hkdf::Algorithm::from(hmac_alg)
         };
         let secret = test_case.consume_bytes("IKM");
-        let salt = test_case.consume_bytes("salt");
+        let salt = test_case
+            .consume_optional_bytes("salt")
+            .unwrap_or_else(|| vec![0; alg.hmac_algorithm().digest_algorithm().output_len]);
         let info = test_case.consume_bytes("info");
-        let _ = test_case.consume_bytes("PRK");
+        let prk = test_case.consume_bytes("PRK");
         let expected_out = test_case.consume_bytes("OKM");
 
         let salt = hkdf::Salt::new(alg, &salt);
@@ -52,10 +60,106 @@ fn hkdf_tests() {
             .into();
         assert_eq!(out, expected_out);
 
+        // Expanding from the PRK directly skips HKDF-Extract.
+        let My(out) = hkdf::Prk::new_less_safe(alg, &prk)
+            .expand(&[&info], My(expected_out.len()))
+            .unwrap()
+            .into();
+        assert_eq!(out, expected_out);
+
         Ok(())
     });
 }
 
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn hkdf_algorithm_from_hmac_algorithm() {
+    for &(hkdf_alg, hmac_alg) in &[
+        (
+            hkdf::HKDF_SHA1_FOR_LEGACY_USE_ONLY,
+            hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY,
+        ),
+        (hkdf::HKDF_SHA256, hmac::HMAC_SHA256),
+        (hkdf::HKDF_SHA384, hmac::HMAC_SHA384),
+        (hkdf::HKDF_SHA512, hmac::HMAC_SHA512),
+    ] {
+        assert_eq!(hkdf::Algorithm::from(hmac_alg), hkdf_alg);
+    }
+
+    let alg = hkdf::Algorithm::from(hmac::HMAC_SM3);
+    assert_eq!(alg.hmac_algorithm(), hmac::HMAC_SM3);
+    assert_eq!(hkdf::KeyType::len(&alg), digest::SM3_OUTPUT_LEN);
+}
+
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn hkdf_prk_chain_tests() {
+    for &alg in &[hkdf::HKDF_SHA256, hkdf::HKDF_SHA384, hkdf::HKDF_SHA512] {
+        let prk_len = alg.hmac_algorithm().digest_algorithm().output_len;
+        let chain_key = hkdf::Salt::new(alg, b"salt").extract(b"secret");
+
+        // The next chain key, derived without exposing its value.
+        let next: hkdf::Prk = chain_key.expand(&[b"chain"], alg).unwrap().into();
+
+        // The same chain key, reconstructed from its raw value.
+        let My(next_bytes) = chain_key.expand(&[b"chain"], My(prk_len)).unwrap().into();
+        let next_less_safe = hkdf::Prk::new_less_safe(alg, &next_bytes);
+
+        let My(a) = next.expand(&[b"message"], My(32)).unwrap().into();
+        let My(b) = next_less_safe.expand(&[b"message"], My(32)).unwrap().into();
+        assert_eq!(a, b);
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn hkdf_expand_reader_tests() {
+    use std::io::Read;
+
+    for &alg in &[hkdf::HKDF_SHA256, hkdf::HKDF_SHA384, hkdf::HKDF_SHA512] {
+        let max_len = 255 * alg.hmac_algorithm().digest_algorithm().output_len;
+        let prk = hkdf::Salt::new(alg, b"salt").extract(b"secret");
+        let info: &[&[u8]] = &[b"multi", b"", b"part"];
+
+        let My(expected) = prk.expand(info, My(max_len)).unwrap().into();
+
+        // Reads of various sizes, including ones that straddle blocks.
+        for &chunk_len in &[1, 7, 32, 33, 100, max_len] {
+            let mut reader = hkdf::ExpandReader::new(&prk, info);
+            let mut actual = vec![0; max_len];
+            for chunk in actual.chunks_mut(chunk_len) {
+                reader.read_exact(chunk).unwrap();
+            }
+            assert_eq!(actual, expected);
+
+            // The limit has been reached.
+            let mut byte = [0];
+            assert!(reader.read(&mut byte).is_err());
+            assert_eq!(reader.read(&mut []).unwrap(), 0);
+        }
+
+        // Reading N bytes matches a direct expansion of N bytes.
+        for &len in &[0, 1, 42, max_len / 2] {
+            let My(expected) = prk.expand(info, My(len)).unwrap().into();
+            let mut actual = vec![0; len];
+            hkdf::ExpandReader::new(&prk, info)
+                .read_exact(&mut actual)
+                .unwrap();
+            assert_eq!(actual, expected);
+        }
+
+        // A read that crosses the limit is short, and the next one fails.
+        let mut reader = hkdf::ExpandReader::new(&prk, info);
+        let mut actual = vec![0; max_len + 1];
+        assert_eq!(reader.read(&mut actual).unwrap(), max_len);
+        assert_eq!(&actual[..max_len], &expected[..]);
+        assert!(reader.read(&mut actual).is_err());
+
+        let mut reader = hkdf::ExpandReader::new(&prk, info);
+        assert!(reader.read_exact(&mut vec![0; max_len + 1]).is_err());
+    }
+}
+
 #[test]
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
 fn hkdf_output_len_tests() {
@@ -109,6 +213,130 @@ fn hkdf_output_len_tests() {
     }
 }
 
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn hkdf_expand_multi_tests() {
+    const INFO: &[&[u8]] = &[b"key", b" expansion"];
+
+    for &alg in &[
+        hkdf::HKDF_SHA1_FOR_LEGACY_USE_ONLY,
+        hkdf::HKDF_SHA256,
+        hkdf::HKDF_SHA384,
+        hkdf::HKDF_SHA512,
+    ] {
+        let prk = hkdf::Salt::new(alg, b"salt").extract(b"secret");
+        let max_out_len = 255 * alg.hmac_algorithm().digest_algorithm().output_len;
+
+        for lengths in &[
+            &[][..],
+            &[0][..],
+            &[16, 16, 32, 32, 12, 12][..],
+            &[1, 0, 63, 64, 65][..],
+            &[max_out_len][..],
+        ] {
+            let total_len = lengths.iter().sum();
+            let My(expected) = prk.expand(INFO, My(total_len)).unwrap().into();
+
+            let mut bufs: Vec<Vec<u8>> = lengths.iter().map(|len| vec![0u8; *len]).collect();
+            let mut outs: Vec<&mut [u8]> = bufs.iter_mut().map(|buf| &mut buf[..]).collect();
+            assert_eq!(hkdf::expand_multi(&prk, INFO, lengths, &mut outs), Ok(()));
+
+            let actual: Vec<u8> = bufs.concat();
+            assert_eq!(actual, expected);
+        }
+
+        // Too long in total, even though each output is small enough.
+        let mut a = vec![0u8; max_out_len];
+        let mut b = [0u8; 1];
+        assert_eq!(
+            hkdf::expand_multi(&prk, INFO, &[max_out_len, 1], &mut [&mut a, &mut b]),
+            Err(error::Unspecified)
+        );
+
+        // `lengths` doesn't match `outs`.
+        let mut a = [0u8; 16];
+        let mut b = [0u8; 12];
+        assert_eq!(
+            hkdf::expand_multi(&prk, INFO, &[16, 16], &mut [&mut a, &mut b]),
+            Err(error::Unspecified)
+        );
+        assert_eq!(
+            hkdf::expand_multi(&prk, INFO, &[16], &mut [&mut a, &mut b]),
+            Err(error::Unspecified)
+        );
+    }
+}
+
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn hkdf_info_builder_tests() {
+    // The server handshake traffic key and IV from the "Simple 1-RTT
+    // Handshake" of RFC 8448 Section 3.
+    let prk = hkdf::Prk::new_less_safe(
+        hkdf::HKDF_SHA256,
+        &test::from_hex("b67b7d690cc16c4e75e54213cb2d37b4e9c912bcded9105d42befd59d391ad38")
+            .unwrap(),
+    );
+    for &(label, len, expected_info, expected_okm) in &[
+        (
+            &b"key"[..],
+            16,
+            "001009746c733133206b657900",
+            "3fce516009c21727d0f2e4e86ee403bc",
+        ),
+        (
+            &b"iv"[..],
+            12,
+            "000c08746c73313320697600",
+            "5d313eb2671276ee13000b30",
+        ),
+    ] {
+        let info = hkdf::InfoBuilder::new()
+            .push_u16(len as u16)
+            .push_u8_len_prefixed(&[&b"tls13 "[..], label].concat())
+            .push_u8_len_prefixed(b"")
+            .build()
+            .unwrap();
+        assert_eq!(info, test::from_hex(expected_info).unwrap());
+
+        let My(okm) = prk.expand(&[&info], My(len)).unwrap().into();
+        assert_eq!(okm, test::from_hex(expected_okm).unwrap());
+    }
+
+    let info = hkdf::InfoBuilder::new()
+        .push_u8(1)
+        .push(b"ab")
+        .push_u16_len_prefixed(b"cd")
+        .build()
+        .unwrap();
+    assert_eq!(&info[..], b"\x01ab\x00\x02cd");
+
+    // Values that are too long for their length prefix.
+    let long = vec![0u8; 256];
+    assert!(hkdf::InfoBuilder::new()
+        .push_u8_len_prefixed(&long[..255])
+        .build()
+        .is_ok());
+    assert_eq!(
+        hkdf::InfoBuilder::new()
+            .push_u8_len_prefixed(&long)
+            .push(b"more")
+            .build(),
+        Err(error::Unspecified)
+    );
+    let long = vec![0u8; 65536];
+    assert!(hkdf::InfoBuilder::new()
+        .push_u16_len_prefixed(&long[..65535])
+        .build()
+        .is_ok());
+    assert_eq!(
+        hkdf::InfoBuilder::new()
+            .push_u16_len_prefixed(&long)
+            .build(),
+        Err(error::Unspecified)
+    );
+}
+
 /// Generic newtype wrapper that lets us implement traits for externally-defined
 /// types.
 #[derive(Debug, PartialEq)]
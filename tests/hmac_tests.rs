@@ -12,7 +12,8 @@
 // OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
 // CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
 
-use ring::{digest, error, hmac, test, test_file};
+use core::num::NonZeroU32;
+use ring::{digest, error, hmac, pbkdf2, test, test_file};
 
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen_test::{wasm_bindgen_test, wasm_bindgen_test_configure};
@@ -45,6 +46,8 @@ fn hmac_tests() {
                 hmac::HMAC_SHA384
             } else if digest_alg == &digest::SHA512 {
                 hmac::HMAC_SHA512
+            } else if digest_alg == &digest::SM3 {
+                hmac::HMAC_SM3
             } else {
                 unreachable!()
             }
@@ -63,6 +66,60 @@ fn hmac_tests() {
     });
 }
 
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn hmac_truncated_tests() {
+    test::run(
+        test_file!("hmac_truncated_tests.txt"),
+        |section, test_case| {
+            assert_eq!(section, "");
+            let digest_alg = test_case.consume_digest_alg("HMAC").unwrap();
+            let key_value = test_case.consume_bytes("Key");
+            let mut input = test_case.consume_bytes("Input");
+            let output = test_case.consume_bytes("Output");
+
+            let algorithm = if digest_alg == &digest::SHA256 {
+                hmac::HMAC_SHA256
+            } else if digest_alg == &digest::SHA384 {
+                hmac::HMAC_SHA384
+            } else if digest_alg == &digest::SHA512 {
+                hmac::HMAC_SHA512
+            } else if digest_alg == &digest::SM3 {
+                hmac::HMAC_SM3
+            } else {
+                unreachable!()
+            };
+            let key = hmac::Key::new(algorithm, &key_value);
+            let tag_len = output.len();
+
+            let tag = hmac::sign_truncated(&key, &input, tag_len)?;
+            assert_eq!(tag.as_ref(), &output[..]);
+            assert_eq!(tag.as_ref(), &hmac::sign(&key, &input).as_ref()[..tag_len]);
+            assert!(hmac::verify_truncated(&key, &input, &output, tag_len).is_ok());
+
+            // The tag must be exactly `tag_len` bytes.
+            assert!(
+                hmac::verify_truncated(&key, &input, &output[..(tag_len - 1)], tag_len).is_err()
+            );
+            let full = hmac::sign(&key, &input);
+            assert!(hmac::verify_truncated(&key, &input, full.as_ref(), tag_len).is_err());
+
+            // Out-of-range tag lengths are rejected.
+            let output_len = digest_alg.output_len;
+            assert!(hmac::sign_truncated(&key, &input, 0).is_err());
+            assert!(hmac::sign_truncated(&key, &input, output_len + 1).is_err());
+            assert!(hmac::verify_truncated(&key, &input, &[], 0).is_err());
+            assert!(hmac::verify_truncated(&key, &input, full.as_ref(), output_len).is_ok());
+
+            // Tamper with the input and check that verification fails.
+            input[0] ^= 1;
+            assert!(hmac::verify_truncated(&key, &input, &output, tag_len).is_err());
+
+            Ok(())
+        },
+    );
+}
+
 fn hmac_test_case_inner(
     algorithm: hmac::Algorithm,
     key_value: &[u8],
@@ -102,6 +159,83 @@ fn hmac_test_case_inner(
     Ok(())
 }
 
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn hmac_key_generate_serializable() {
+    const MESSAGE: &[u8] = b"message";
+
+    for &alg in &[
+        hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY,
+        hmac::HMAC_SHA256,
+        hmac::HMAC_SHA384,
+        hmac::HMAC_SHA512,
+    ] {
+        let output_len = alg.digest_algorithm().output_len;
+        let rng = test::rand::FixedByteRandom { byte: 0x42 };
+
+        let mut key_bytes = vec![0; output_len];
+        let key = hmac::Key::generate_serializable(alg, &rng, &mut key_bytes).unwrap();
+        assert_eq!(key_bytes, vec![0x42; output_len]);
+        assert_eq!(key.algorithm(), alg);
+
+        // The serialized key value reconstructs an equivalent key, which is
+        // the same key `generate()` produces from the same random bytes.
+        let tag = hmac::sign(&key, MESSAGE);
+        let restored = hmac::Key::new(alg, &key_bytes);
+        assert!(hmac::verify(&restored, MESSAGE, tag.as_ref()).is_ok());
+        let generated = hmac::Key::generate(alg, &rng).unwrap();
+        assert!(hmac::verify(&generated, MESSAGE, tag.as_ref()).is_ok());
+
+        // `key_bytes` must be exactly the digest length.
+        for &len in &[0, output_len - 1, output_len + 1] {
+            let mut key_bytes = vec![0; len];
+            assert!(hmac::Key::generate_serializable(alg, &rng, &mut key_bytes).is_err());
+        }
+    }
+}
+
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn hmac_key_from_password() {
+    const MESSAGE: &[u8] = b"message";
+    let iterations = NonZeroU32::new(100).unwrap();
+
+    for &(hmac_alg, pbkdf2_alg) in &[
+        (
+            hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY,
+            pbkdf2::PBKDF2_HMAC_SHA1,
+        ),
+        (hmac::HMAC_SHA256, pbkdf2::PBKDF2_HMAC_SHA256),
+        (hmac::HMAC_SHA384, pbkdf2::PBKDF2_HMAC_SHA384),
+        (hmac::HMAC_SHA512, pbkdf2::PBKDF2_HMAC_SHA512),
+    ] {
+        let key = hmac::Key::from_password(hmac_alg, iterations, b"salt", b"password");
+        assert_eq!(key.algorithm(), hmac_alg);
+
+        let mut key_bytes = vec![0; hmac_alg.digest_algorithm().output_len];
+        pbkdf2::derive(pbkdf2_alg, iterations, b"salt", b"password", &mut key_bytes);
+        let expected = hmac::Key::new(hmac_alg, &key_bytes);
+
+        let tag = hmac::sign(&key, MESSAGE);
+        assert_eq!(tag.as_ref(), hmac::sign(&expected, MESSAGE).as_ref());
+        assert!(hmac::verify(&expected, MESSAGE, tag.as_ref()).is_ok());
+
+        // Each input affects the key.
+        for other in &[
+            hmac::Key::from_password(hmac_alg, iterations, b"salt", b"passwore"),
+            hmac::Key::from_password(hmac_alg, iterations, b"salu", b"password"),
+            hmac::Key::from_password(
+                hmac_alg,
+                NonZeroU32::new(101).unwrap(),
+                b"salt",
+                b"password",
+            ),
+        ] {
+            assert!(hmac::verify(other, MESSAGE, tag.as_ref()).is_err());
+        }
+    }
+}
+
 #[test]
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
 fn hmac_debug() {
@@ -111,3 +245,61 @@ fn hmac_debug() {
     let ctx = hmac::Context::with_key(&key);
     assert_eq!("Context { algorithm: SHA256 }", format!("{:?}", &ctx));
 }
+
+#[cfg(feature = "dangerous")]
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn hmac_save_restore_state() {
+    let data: Vec<u8> = (0..300).map(|i| (i % 251) as u8).collect();
+    for &alg in &[
+        hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY,
+        hmac::HMAC_SHA256,
+        hmac::HMAC_SHA384,
+        hmac::HMAC_SHA512,
+    ] {
+        let key = hmac::Key::new(alg, &[0x0b; 20]);
+        let expected = hmac::sign(&key, &data);
+
+        for split in 0..=data.len() {
+            let mut ctx = hmac::Context::with_key(&key);
+            ctx.update(&data[..split]);
+            let state = ctx.save_state();
+
+            let mut ctx = hmac::Context::restore_state(&key, &state).unwrap();
+            ctx.update(&data[split..]);
+            assert_eq!(ctx.sign().as_ref(), expected.as_ref());
+        }
+    }
+}
+
+#[cfg(feature = "dangerous")]
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn hmac_restore_state_invalid() {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, &[0x0b; 20]);
+    let mut ctx = hmac::Context::with_key(&key);
+    ctx.update(&[0x5a; 100]);
+    let state = ctx.save_state();
+    assert!(hmac::Context::restore_state(&key, &state).is_ok());
+
+    // Truncated, including within the chaining value and the block count.
+    for len in 0..(1 + 32 + 8) {
+        assert!(hmac::Context::restore_state(&key, &state[..len]).is_err());
+    }
+
+    // Too many pending bytes.
+    let mut too_long = state.clone();
+    too_long.extend_from_slice(&[0; 64]);
+    assert!(hmac::Context::restore_state(&key, &too_long).is_err());
+
+    // A key for a different algorithm.
+    let other_key = hmac::Key::new(hmac::HMAC_SHA512, &[0x0b; 20]);
+    assert!(hmac::Context::restore_state(&other_key, &state).is_err());
+
+    // A block count whose length in bits overflows.
+    let mut overflow = state;
+    for b in &mut overflow[(1 + 32)..(1 + 32 + 8)] {
+        *b = 0xff;
+    }
+    assert!(hmac::Context::restore_state(&key, &overflow).is_err());
+}
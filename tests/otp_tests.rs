@@ -0,0 +1,75 @@
+use ring::{error, hmac, otp, test, test_file};
+
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen_test::{wasm_bindgen_test, wasm_bindgen_test_configure};
+
+#[cfg(target_arch = "wasm32")]
+wasm_bindgen_test_configure!(run_in_browser);
+
+/// Test vectors from RFC 4226 and RFC 6238.
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn otp_tests() {
+    test::run(test_file!("otp_tests.txt"), |section, test_case| {
+        let algorithm = match test_case.consume_string("HMAC").as_str() {
+            "SHA1" => hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY,
+            "SHA256" => hmac::HMAC_SHA256,
+            "SHA512" => hmac::HMAC_SHA512,
+            name => panic!("Unsupported HMAC algorithm: {}", name),
+        };
+        let key = hmac::Key::new(algorithm, &test_case.consume_bytes("Key"));
+        let digits = test_case.consume_usize("Digits") as u8;
+        let expected = test_case.consume_usize("OTP") as u32;
+
+        let actual = match section {
+            "HOTP" => {
+                let counter = test_case.consume_usize("Counter") as u64;
+                otp::hotp(&key, counter, digits).unwrap()
+            }
+            "TOTP" => {
+                // Some of the times don't fit in a 32-bit `usize`.
+                let mut consume_u64 = |key| test_case.consume_string(key).parse::<u64>().unwrap();
+                let time = consume_u64("Time");
+                let step = consume_u64("Step");
+                let t0 = consume_u64("T0");
+                otp::totp(&key, time, step, t0, digits).unwrap()
+            }
+            _ => unreachable!(),
+        };
+        assert_eq!(actual, expected);
+
+        Ok(())
+    });
+}
+
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn otp_digits() {
+    let key = hmac::Key::new(hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY, b"12345678901234567890");
+    // The truncated value for counter 0 is 0x4c93cf18 = 1284755224.
+    assert_eq!(otp::hotp(&key, 0, 9), Ok(284755224));
+    assert_eq!(otp::hotp(&key, 0, 8), Ok(84755224));
+    assert_eq!(otp::hotp(&key, 0, 1), Ok(4));
+}
+
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn otp_invalid_parameters() {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, b"key");
+    assert_eq!(otp::hotp(&key, 0, 0), Err(error::Unspecified));
+    assert_eq!(otp::hotp(&key, 0, 10), Err(error::Unspecified));
+    assert_eq!(otp::totp(&key, 0, 30, 0, 10), Err(error::Unspecified));
+    assert_eq!(otp::totp(&key, 1000, 0, 0, 6), Err(error::Unspecified));
+    assert_eq!(otp::totp(&key, 999, 30, 1000, 6), Err(error::Unspecified));
+}
+
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn totp_t0() {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, b"key");
+    assert_eq!(
+        otp::totp(&key, 1000 + 59, 30, 1000, 6),
+        otp::hotp(&key, 1, 6)
+    );
+    assert_eq!(otp::totp(&key, 1000, 30, 1000, 6), otp::hotp(&key, 0, 6));
+}
@@ -13,6 +13,7 @@
 // CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
 
 use ring::{
+    error,
     rand::{self, SecureRandom as _},
     test,
 };
@@ -78,3 +79,301 @@ fn test_system_random_traits() {
         format!("{:?}", rand::SystemRandom::new())
     );
 }
+
+// `SystemRandom` must not cache random bytes in userspace, so consecutive
+// calls must produce independent output.
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn test_system_random_no_caching() {
+    let rng = rand::SystemRandom::new();
+    let mut a = [0u8; 32];
+    let mut b = [0u8; 32];
+    rng.fill(&mut a).unwrap();
+    rng.fill(&mut b).unwrap();
+    assert_ne!(a, b);
+}
+
+// A forked child must not observe the same random bytes as its parent.
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[test]
+fn test_system_random_fork_safety() {
+    const LEN: usize = 32;
+
+    let rng = rand::SystemRandom::new();
+
+    // Ensure any lazily-initialized state exists before forking.
+    let mut warm_up = [0u8; LEN];
+    rng.fill(&mut warm_up).unwrap();
+
+    let mut fds = [0; 2];
+    assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+    let (read_fd, write_fd) = (fds[0], fds[1]);
+
+    let pid = unsafe { libc::fork() };
+    assert!(pid >= 0);
+    if pid == 0 {
+        // Child: report its bytes over the pipe and `_exit` without unwinding.
+        let mut child_bytes = [0u8; LEN];
+        let status = match rng.fill(&mut child_bytes) {
+            Ok(()) => {
+                let written = unsafe {
+                    libc::write(write_fd, child_bytes.as_ptr() as *const libc::c_void, LEN)
+                };
+                if written == LEN as isize {
+                    0
+                } else {
+                    1
+                }
+            }
+            Err(_) => 1,
+        };
+        unsafe { libc::_exit(status) };
+    }
+
+    let mut parent_bytes = [0u8; LEN];
+    rng.fill(&mut parent_bytes).unwrap();
+
+    let mut child_bytes = [0u8; LEN];
+    let read = unsafe { libc::read(read_fd, child_bytes.as_mut_ptr() as *mut libc::c_void, LEN) };
+    let mut status = 0;
+    assert_eq!(unsafe { libc::waitpid(pid, &mut status, 0) }, pid);
+    unsafe {
+        let _ = libc::close(read_fd);
+        let _ = libc::close(write_fd);
+    }
+
+    assert!(libc::WIFEXITED(status));
+    assert_eq!(libc::WEXITSTATUS(status), 0);
+    assert_eq!(read, LEN as isize);
+    assert_ne!(parent_bytes, child_bytes);
+    assert_ne!(warm_up, child_bytes);
+}
+
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn test_xor_random() {
+    let rng = rand::XorRandom::new(
+        test::rand::FixedByteRandom { byte: 0x5a },
+        test::rand::FixedByteRandom { byte: 0x0f },
+    );
+    // Cover lengths that span several internal scratch buffers.
+    for &len in &[0, 1, 255, 256, 257, 1000] {
+        let mut buf = vec![0u8; len];
+        assert!(rng.fill(&mut buf).is_ok());
+        assert!(buf.iter().all(|&b| b == 0x5a ^ 0x0f));
+    }
+
+    // The combination of independent sources isn't the output of either.
+    let rng = rand::XorRandom::new(rand::SystemRandom::new(), rand::SystemRandom::new());
+    let mut buf = [0u8; 32];
+    assert!(rng.fill(&mut buf).is_ok());
+    assert_ne!(buf, [0u8; 32]);
+
+    // If either source fails, the whole `fill` fails.
+    let mut buf = [0u8; 4];
+    let failing = test::rand::SliceCursorRandom::new(&[]);
+    let rng = rand::XorRandom::new(&failing, rand::SystemRandom::new());
+    assert_eq!(rng.fill(&mut buf), Err(error::Unspecified));
+    let rng = rand::XorRandom::new(rand::SystemRandom::new(), &failing);
+    assert_eq!(rng.fill(&mut buf), Err(error::Unspecified));
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[test]
+fn test_rdrand() {
+    let rng = rand::RdRand::new();
+    let mut buf = [0u8; 33];
+    if !rng.is_available() {
+        assert_eq!(rng.fill(&mut buf), Err(error::Unspecified));
+        return;
+    }
+
+    // Cover lengths that aren't a multiple of the word size.
+    for len in 0..buf.len() {
+        assert!(rng.fill(&mut buf[..len]).is_ok());
+    }
+    assert_ne!(buf, [0u8; 33]);
+    assert_eq!(rand::self_test(&rng), Ok(()));
+
+    let rng = rand::XorRandom::new(rand::SystemRandom::new(), rand::RdRand::new());
+    assert!(rng.fill(&mut buf).is_ok());
+}
+
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn test_self_test() {
+    assert_eq!(rand::self_test(&rand::SystemRandom::new()), Ok(()));
+
+    // Stuck sources fail the continuous test.
+    for &byte in &[0x00, 0x55, 0xff] {
+        let rng = test::rand::FixedByteRandom { byte };
+        assert_eq!(rand::self_test(&rng), Err(error::Unspecified));
+    }
+    let pattern: Vec<u8> = (0u8..20).collect();
+    let rng = test::rand::SliceCursorRandom::new_wrapping(&pattern);
+    assert_eq!(rand::self_test(&rng), Err(error::Unspecified));
+
+    // A source whose blocks all differ but which is biased fails the monobit
+    // test.
+    let biased: Vec<u8> = (0..2500).map(|i| (i % 251) as u8 & 0x0f).collect();
+    let rng = test::rand::SliceCursorRandom::new(&biased);
+    assert_eq!(rand::self_test(&rng), Err(error::Unspecified));
+
+    // A source that runs out fails.
+    let rng = test::rand::SliceCursorRandom::new(&biased[..100]);
+    assert_eq!(rand::self_test(&rng), Err(error::Unspecified));
+}
+
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn test_uniform_usize_below() {
+    // 0x0a and 0x0c aren't below 10 after masking with 0x0f, so they are
+    // rejected.
+    let rng = test::rand::SliceCursorRandom::new(&[0xfa, 0x1c, 0x07]);
+    assert_eq!(rand::uniform_usize_below(&rng, 10), Ok(7));
+    assert_eq!(rng.remaining(), 0);
+
+    // 0x0300 masked with 0x01ff is 0x0100.
+    let rng = test::rand::SliceCursorRandom::new(&[0x03, 0x00]);
+    assert_eq!(rand::uniform_usize_below(&rng, 257), Ok(256));
+
+    let rng = test::rand::SliceCursorRandom::new(&[0xff]);
+    assert_eq!(rand::uniform_usize_below(&rng, 256), Ok(255));
+
+    // A single value needs no randomness.
+    let rng = test::rand::SliceCursorRandom::new(&[]);
+    assert_eq!(rand::uniform_usize_below(&rng, 1), Ok(0));
+
+    let rng = rand::SystemRandom::new();
+    assert_eq!(rand::uniform_usize_below(&rng, 0), Err(error::Unspecified));
+
+    // The RNG's failure is propagated, including when every value drawn is
+    // rejected.
+    let rng = test::rand::SliceCursorRandom::new(&[0xfa]);
+    assert_eq!(rand::uniform_usize_below(&rng, 10), Err(error::Unspecified));
+
+    // The largest `n` uses every bit of a `usize`.
+    let len = core::mem::size_of::<usize>();
+    let mut bytes = vec![0xff; 2 * len];
+    bytes[2 * len - 1] = 0xfe;
+    let rng = test::rand::SliceCursorRandom::new(&bytes);
+    assert_eq!(
+        rand::uniform_usize_below(&rng, usize::max_value()),
+        Ok(usize::max_value() - 1)
+    );
+
+    let rng = rand::SystemRandom::new();
+    let mut counts = [0usize; 3];
+    for _ in 0..3000 {
+        counts[rand::uniform_usize_below(&rng, 3).unwrap()] += 1;
+    }
+    assert!(counts.iter().all(|&count| count > 0));
+}
+
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn test_fill_all() {
+    let bytes: Vec<u8> = (0u8..10).collect();
+
+    // The buffers are filled in order from the same stream.
+    let rng = test::rand::SliceCursorRandom::new(&bytes);
+    let mut key = [0u8; 4];
+    let mut empty = [0u8; 0];
+    let mut iv = [0u8; 3];
+    assert!(rng.fill_all(&mut [&mut key, &mut empty, &mut iv]).is_ok());
+    assert_eq!(key, [0, 1, 2, 3]);
+    assert_eq!(iv, [4, 5, 6]);
+    assert_eq!(rng.remaining(), 3);
+
+    // Works through a `&dyn SecureRandom` too.
+    let rng: &dyn rand::SecureRandom = &rng;
+    let mut rest = [0u8; 3];
+    assert!(rng.fill_all(&mut [&mut rest]).is_ok());
+    assert_eq!(rest, [7, 8, 9]);
+    assert!(rng.fill_all(&mut []).is_ok());
+
+    // A failure for any buffer fails the whole call.
+    let rng = test::rand::SliceCursorRandom::new(&bytes);
+    let mut a = [0u8; 8];
+    let mut b = [0u8; 8];
+    assert_eq!(rng.fill_all(&mut [&mut a, &mut b]), Err(error::Unspecified));
+}
+
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn test_limited_random() {
+    let rng = rand::LimitedRandom::new(test::rand::FixedByteRandom { byte: 0x5a }, 32);
+    assert_eq!(rng.max_per_fill(), 32);
+
+    for &len in &[0, 1, 31, 32] {
+        let mut buf = vec![0u8; len];
+        assert!(rng.fill(&mut buf).is_ok());
+        assert!(buf.iter().all(|&b| b == 0x5a));
+    }
+
+    // Too-large requests fail without touching `dest` or using the inner RNG.
+    let mut buf = vec![0u8; 33];
+    assert_eq!(rng.fill(&mut buf), Err(error::Unspecified));
+    assert!(buf.iter().all(|&b| b == 0));
+    let bytes = [1, 2, 3, 4];
+    let rng = rand::LimitedRandom::new(test::rand::SliceCursorRandom::new(&bytes), 2);
+    assert_eq!(rng.fill(&mut [0u8; 3]), Err(error::Unspecified));
+    let mut buf = [0u8; 2];
+    assert!(rng.fill(&mut buf).is_ok());
+    assert_eq!(buf, [1, 2]);
+
+    // The limit applies to each `fill`, not to the total.
+    let mut a = [0u8; 2];
+    let mut b = [0u8; 3];
+    assert!(rng.fill(&mut a).is_ok());
+    assert_eq!(rng.fill_all(&mut [&mut b]), Err(error::Unspecified));
+
+    // Inner failures are passed through.
+    let rng = rand::LimitedRandom::new(test::rand::SliceCursorRandom::new(&[]), 32);
+    assert_eq!(rng.fill(&mut [0u8; 1]), Err(error::Unspecified));
+}
+
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn test_dyn_secure_random() {
+    use ring::{agreement, signature};
+
+    let rngs: Vec<Box<dyn rand::SecureRandom>> = vec![
+        Box::new(rand::SystemRandom::new()),
+        Box::new(test::rand::FixedByteRandom { byte: 0x42 }),
+    ];
+    for rng in &rngs {
+        // A `&Box<dyn SecureRandom>` coerces to a `&dyn SecureRandom`.
+        let private_key =
+            agreement::EphemeralPrivateKey::generate(&agreement::X25519, rng).unwrap();
+        let _ = private_key.compute_public_key().unwrap();
+
+        let pkcs8 = signature::Ed25519KeyPair::generate_pkcs8(rng).unwrap();
+        let _ = signature::Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+
+        // So does a `&&dyn SecureRandom`.
+        let rng: &dyn rand::SecureRandom = rng.as_ref();
+        let pkcs8 = signature::EcdsaKeyPair::generate_pkcs8(
+            &signature::ECDSA_P256_SHA256_ASN1_SIGNING,
+            &rng,
+        )
+        .unwrap();
+        let key_pair = signature::EcdsaKeyPair::from_pkcs8(
+            &signature::ECDSA_P256_SHA256_ASN1_SIGNING,
+            pkcs8.as_ref(),
+        )
+        .unwrap();
+        let _ = key_pair.sign(&rng, b"message").unwrap();
+
+        let mut buf = [0u8; 4];
+        assert!(rand::XorRandom::new(rng, &rngs[0]).fill(&mut buf).is_ok());
+    }
+}
+
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn test_system_random_is_ready() {
+    let rng = rand::SystemRandom::new();
+    // The test environment is long past early boot.
+    assert!(rng.is_ready());
+}
@@ -16,7 +16,7 @@
 use ring::{
     error,
     io::der,
-    rand,
+    pkcs8, rand,
     signature::{self, KeyPair},
     test, test_file,
 };
@@ -317,3 +317,88 @@ fn rsa_test_public_key_coverage() {
         format!("{:?}", key_pair)
     );
 }
+
+#[cfg(feature = "alloc")]
+#[test]
+#[cfg_attr(all(target_arch = "wasm32", feature = "wasm32_c"), wasm_bindgen_test)]
+fn rsa_key_pair_from_pkcs8_encrypted() {
+    const PUBLIC_KEY: &[u8] = include_bytes!("rsa_test_public_key_2048.der");
+    const PASSWORD: &[u8] = b"correct horse battery staple";
+
+    // Encrypted with `openssl pkcs8 -topk8 -v2 aes-256-cbc -v2prf
+    // hmacWithSHA256 -iter 2048`.
+    const ENCRYPTED: &[u8] = include_bytes!("rsa_test_private_key_2048_encrypted.p8");
+    let key_pair = signature::RsaKeyPair::from_pkcs8_encrypted(ENCRYPTED, PASSWORD).unwrap();
+    assert_eq!(key_pair.public_key().as_ref(), PUBLIC_KEY);
+
+    let pkcs8 = pkcs8::decrypt(ENCRYPTED, PASSWORD).unwrap();
+    let key_pair = signature::RsaKeyPair::from_pkcs8(&pkcs8).unwrap();
+    assert_eq!(key_pair.public_key().as_ref(), PUBLIC_KEY);
+
+    assert_eq!(
+        pkcs8::decrypt(ENCRYPTED, b"wrong password").err(),
+        Some(error::Unspecified)
+    );
+    assert_eq!(
+        signature::RsaKeyPair::from_pkcs8_encrypted(ENCRYPTED, b"wrong password")
+            .err()
+            .map(|e| e.description_()),
+        Some("DecryptionFailed")
+    );
+
+    // Truncated input.
+    assert!(pkcs8::decrypt(&ENCRYPTED[..(ENCRYPTED.len() - 1)], PASSWORD).is_err());
+
+    // AES-128-CBC isn't supported.
+    const ENCRYPTED_AES128: &[u8] = include_bytes!("rsa_test_private_key_2048_encrypted_aes128.p8");
+    assert!(pkcs8::decrypt(ENCRYPTED_AES128, PASSWORD).is_err());
+
+    // The unencrypted key isn't an `EncryptedPrivateKeyInfo`.
+    const PRIVATE_KEY: &[u8] = include_bytes!("rsa_test_private_key_2048.p8");
+    assert!(pkcs8::decrypt(PRIVATE_KEY, PASSWORD).is_err());
+}
+
+#[test]
+#[cfg_attr(all(target_arch = "wasm32", feature = "wasm32_c"), wasm_bindgen_test)]
+fn rsa_public_key_parse() {
+    const PRIVATE_KEY: &[u8] = include_bytes!("rsa_test_private_key_2048.p8");
+    const PUBLIC_KEY: &[u8] = include_bytes!("rsa_test_public_key_2048.der");
+    const MESSAGE: &[u8] = b"hello, world";
+
+    let public_key = signature::RsaPublicKey::parse(PUBLIC_KEY).unwrap();
+    assert_eq!(public_key.modulus_bits(), 2048);
+    assert_eq!(public_key.as_ref(), PUBLIC_KEY);
+
+    let rng = rand::SystemRandom::new();
+    let key_pair = signature::RsaKeyPair::from_pkcs8(PRIVATE_KEY).unwrap();
+    let mut sig = vec![0; key_pair.public_modulus_len()];
+    key_pair
+        .sign(&signature::RSA_PKCS1_SHA256, &rng, MESSAGE, &mut sig)
+        .unwrap();
+
+    assert!(public_key
+        .verify(&signature::RSA_PKCS1_2048_8192_SHA256, MESSAGE, &sig)
+        .is_ok());
+    assert!(public_key
+        .verify(&signature::RSA_PKCS1_2048_8192_SHA256, b"goodbye", &sig)
+        .is_err());
+    // The key is too small for these parameters.
+    assert!(public_key
+        .verify(&signature::RSA_PKCS1_3072_8192_SHA384, MESSAGE, &sig)
+        .is_err());
+
+    // A 1024-bit key parses, so its size can be reported, but it is rejected
+    // by the 2048-bit minimum during verification.
+    let mut small = vec![0x30, 0x81, 0x89, 0x02, 0x81, 0x81, 0x00];
+    small.extend(std::iter::repeat(0xc1).take(128));
+    small.extend(&[0x02, 0x03, 0x01, 0x00, 0x01]);
+    let small = signature::RsaPublicKey::parse(&small).unwrap();
+    assert_eq!(small.modulus_bits(), 1024);
+    assert!(small
+        .verify(&signature::RSA_PKCS1_2048_8192_SHA256, MESSAGE, &[0; 128])
+        .is_err());
+
+    // Malformed encodings are rejected.
+    assert!(signature::RsaPublicKey::parse(&[]).is_err());
+    assert!(signature::RsaPublicKey::parse(&PUBLIC_KEY[..PUBLIC_KEY.len() - 1]).is_err());
+}
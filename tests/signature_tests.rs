@@ -1,4 +1,8 @@
-use ring::{signature, test};
+use ring::{
+    error, rand,
+    signature::{self, KeyPair},
+    test,
+};
 
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen_test::{wasm_bindgen_test, wasm_bindgen_test_configure};
@@ -14,3 +18,126 @@ fn signature_impl_test() {
     test::compile_time_assert_send::<signature::Signature>();
     test::compile_time_assert_sync::<signature::Signature>();
 }
+
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn unparsed_public_key_impl_test() {
+    test::compile_time_assert_clone::<signature::UnparsedPublicKey<&[u8]>>();
+    test::compile_time_assert_copy::<signature::UnparsedPublicKey<&[u8]>>();
+    test::compile_time_assert_sync::<signature::UnparsedPublicKey<&[u8]>>();
+
+    test::compile_time_assert_clone::<signature::UnparsedPublicKey<Vec<u8>>>();
+    test::compile_time_assert_sync::<signature::UnparsedPublicKey<Vec<u8>>>();
+
+    let unparsed_public_key =
+        signature::UnparsedPublicKey::new(&signature::ED25519, &[0x01, 0x02, 0x03]);
+    assert_eq!(
+        format!("{:?}", unparsed_public_key),
+        r#"UnparsedPublicKey { algorithm: ring::signature::ED25519, bytes: "010203" }"#
+    );
+}
+
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn unparsed_public_key_verify() {
+    const MESSAGE: &[u8] = b"hello, world";
+
+    let key_pair = signature::Ed25519KeyPair::from_seed_unchecked(&[7; 32]).unwrap();
+    let sig = key_pair.sign(MESSAGE);
+    let public_key = key_pair.public_key().as_ref().to_vec();
+
+    let unparsed = signature::UnparsedPublicKey::new(&signature::ED25519, public_key.clone());
+    assert_eq!(unparsed.verify(MESSAGE, sig.as_ref()), Ok(()));
+
+    // A bad signature and a bad key are both rejected the same way.
+    assert_eq!(
+        unparsed.verify(b"goodbye, world", sig.as_ref()),
+        Err(error::Unspecified)
+    );
+    assert_eq!(
+        signature::UnparsedPublicKey::new(&signature::ED25519, &public_key[1..])
+            .verify(MESSAGE, sig.as_ref()),
+        Err(error::Unspecified)
+    );
+    assert_eq!(
+        signature::UnparsedPublicKey::new(&signature::ECDSA_P256_SHA256_ASN1, &public_key)
+            .verify(MESSAGE, sig.as_ref()),
+        Err(error::Unspecified)
+    );
+}
+
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn signature_verify_any() {
+    const MESSAGE: &[u8] = b"hello, world";
+
+    let key_pair = signature::Ed25519KeyPair::from_seed_unchecked(&[7; 32]).unwrap();
+    let sig = key_pair.sign(MESSAGE);
+    let public_key = key_pair.public_key().as_ref();
+
+    let algorithms: &[&dyn signature::VerificationAlgorithm] = &[
+        &signature::ECDSA_P256_SHA256_ASN1,
+        &signature::ECDSA_P384_SHA384_ASN1,
+        &signature::ED25519,
+    ];
+    assert_eq!(
+        signature::verify_any(algorithms, public_key, MESSAGE, sig.as_ref()),
+        Ok(2)
+    );
+
+    // The first match wins.
+    assert_eq!(
+        signature::verify_any(
+            &[&signature::ED25519, &signature::ED25519_STRICT],
+            public_key,
+            MESSAGE,
+            sig.as_ref()
+        ),
+        Ok(0)
+    );
+
+    assert_eq!(
+        signature::verify_any(algorithms, public_key, b"goodbye, world", sig.as_ref()),
+        Err(error::Unspecified)
+    );
+    assert_eq!(
+        signature::verify_any(&algorithms[..2], public_key, MESSAGE, sig.as_ref()),
+        Err(error::Unspecified)
+    );
+    assert_eq!(
+        signature::verify_any(&[], public_key, MESSAGE, sig.as_ref()),
+        Err(error::Unspecified)
+    );
+}
+
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn signature_sign() {
+    const MESSAGE: &[u8] = b"hello, world";
+    let rng = rand::SystemRandom::new();
+
+    // Ed25519 ignores the RNG.
+    let key_pair = signature::Ed25519KeyPair::from_seed_unchecked(&[7; 32]).unwrap();
+    let sig = signature::sign(&key_pair, &rng, MESSAGE).unwrap();
+    assert_eq!(sig, key_pair.sign(MESSAGE).as_ref());
+    let public_key = signature::UnparsedPublicKey::new(&signature::ED25519, key_pair.public_key());
+    assert_eq!(public_key.verify(MESSAGE, &sig), Ok(()));
+
+    // ECDSA uses it.
+    for &(signing_alg, verification_alg) in &[
+        (
+            &signature::ECDSA_P256_SHA256_ASN1_SIGNING,
+            &signature::ECDSA_P256_SHA256_ASN1,
+        ),
+        (
+            &signature::ECDSA_P384_SHA384_FIXED_SIGNING,
+            &signature::ECDSA_P384_SHA384_FIXED,
+        ),
+    ] {
+        let pkcs8 = signature::EcdsaKeyPair::generate_pkcs8(signing_alg, &rng).unwrap();
+        let key_pair = signature::EcdsaKeyPair::from_pkcs8(signing_alg, pkcs8.as_ref()).unwrap();
+        let sig = signature::sign(&key_pair, &rng, MESSAGE).unwrap();
+        let public_key = signature::UnparsedPublicKey::new(verification_alg, key_pair.public_key());
+        assert_eq!(public_key.verify(MESSAGE, &sig), Ok(()));
+    }
+}